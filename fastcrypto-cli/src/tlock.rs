@@ -2,17 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use clap::Parser;
-use fastcrypto::error::{FastCryptoError, FastCryptoResult};
-use fastcrypto::groups::{bls12381, GroupElement, HashToGroupElement, Pairing};
-use fastcrypto::hash::{Blake2b256, HashFunction, Sha256};
+use fastcrypto::error::FastCryptoResult;
+use fastcrypto::groups::{bls12381, GroupElement, Pairing};
+use fastcrypto::hash::{Blake2b256, HashFunction};
 use fastcrypto::serde_helpers::ToFromByteArray;
-use rand::{thread_rng, Rng};
-use serde::{Deserialize, Serialize};
+use fastcrypto_tbls::ibe::{Bls12381MinSig, Ciphertext};
+use fastcrypto_tbls::tlock::{round_identity, ChainInfo};
+use rand::thread_rng;
+use serde::Deserialize;
 use std::io::{Error, ErrorKind};
 
 #[derive(Parser)]
 #[command(name = "tlock-cli")]
-#[command(about = "Basic tlock interface (for quicknet).", long_about = None)]
+#[command(about = "Basic tlock interface (for quicknet).")]
+#[command(long_about = "Basic tlock interface (for quicknet).\n\n\
+    Ciphertexts use the hashing of drand's IBE scheme and the BCS encoding of \
+    fastcrypto_tbls::ibe::Ciphertext. This is not compatible with ciphertexts produced by earlier \
+    versions of this tool (Blake2b hashing, fixed size fields), but such ciphertexts can still be \
+    decrypted.")]
 enum Command {
     Encrypt(EncryptArguments),
     Decrypt(DecryptArguments),
@@ -31,7 +38,8 @@ struct EncryptArguments {
     plaintext: String,
 }
 
-/// Decrypt a ciphertext. Returns a plaintext (hex).
+/// Decrypt a ciphertext, either in the current format or in the format used by earlier versions of
+/// this tool. Returns a plaintext (hex).
 #[derive(Parser, Clone)]
 struct DecryptArguments {
     /// Ciphertext to decrypt (hex)
@@ -55,79 +63,34 @@ struct VerifyArguments {
     signature: String,
 }
 
-/// An encryption of 32 bytes message following https://eprint.iacr.org/2023/189.pdf.
-#[derive(Serialize, Deserialize, Debug)]
-struct Encryption {
+type Encryption = Ciphertext<Bls12381MinSig>;
+
+/// An encryption of 32 bytes message following https://eprint.iacr.org/2023/189.pdf as produced by
+/// earlier versions of this tool. Only kept so old ciphertexts can still be decrypted.
+#[derive(Deserialize, Debug)]
+struct LegacyEncryption {
     u: bls12381::G2Element,
     v: [u8; 32],
     w: [u8; 32],
 }
 
 fn drand_pk() -> bls12381::G2Element {
-    // The public key from 'curl https://api.drand.sh/52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971/info'
-    let pk = hex::decode("83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a").unwrap().try_into().unwrap();
-    bls12381::G2Element::from_byte_array(&pk).unwrap()
+    bcs::from_bytes(&ChainInfo::quicknet().public_key()).unwrap()
 }
 
-fn encode_round(round: &u64) -> [u8; 32] {
-    let mut sha = Sha256::new();
-    sha.update(round.to_be_bytes());
-    sha.finalize().digest
+fn encrypt(round: u64, msg: &[u8]) -> FastCryptoResult<Encryption> {
+    Encryption::encrypt(&drand_pk(), &round_identity(round), msg, &mut thread_rng())
 }
 
-fn xor_arrays(a: &[u8], b: &[u8]) -> Vec<u8> {
-    a.iter().zip(b.iter()).map(|(a, b)| a ^ b).collect()
+fn decrypt(enc: Encryption, target_key: bls12381::G1Element) -> Option<Vec<u8>> {
+    enc.decrypt(&target_key).ok()
 }
 
-fn encrypt(round: u64, msg: &[u8]) -> Encryption {
-    assert_eq!(msg.len(), 32);
-    let pk = drand_pk();
-    let target = encode_round(&round);
-
-    // pk_rho = e(H1(target), pk)
-    let target_hash = bls12381::G1Element::hash_to_group_element(&target);
-    let pk_rho = target_hash.pairing(&pk);
-
-    // r = H3(sigma | m) as a scalar
-    let mut sigma: [u8; 32];
-    let r: bls12381::Scalar;
-    loop {
-        sigma = thread_rng().gen();
-        let mut hash_function = Blake2b256::default();
-        hash_function.update(b"HASH3 - ");
-        hash_function.update(sigma);
-        hash_function.update(msg);
-        let hash = hash_function.finalize().digest;
-        let r_as_res = bls12381::Scalar::from_byte_array(&hash);
-        // rejection sampling until we find a sigma that results in a valid r
-        if let Ok(valid_r) = r_as_res {
-            r = valid_r;
-            break;
-        }
-    }
-
-    // U = r*g2
-    let u = bls12381::G2Element::generator() * r;
-
-    // V = sigma xor H2(pk_rho^r)
-    let pk_rho_r = pk_rho * r;
-    let mut hash_function = Blake2b256::default();
-    hash_function.update(b"HASH2 - ");
-    hash_function.update(pk_rho_r.to_byte_array());
-    let hash = hash_function.finalize().digest;
-    let v = xor_arrays(&sigma, &hash).try_into().unwrap();
-
-    // W = m xor H4(sigma)
-    let mut hash_function = Blake2b256::default();
-    hash_function.update(b"HASH4 - ");
-    hash_function.update(sigma);
-    let hash = hash_function.finalize().digest;
-    let w = xor_arrays(msg, &hash).try_into().unwrap();
-
-    Encryption { u, v, w }
+fn xor_arrays(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b.iter()).map(|(a, b)| a ^ b).collect()
 }
 
-fn decrypt(enc: Encryption, target_key: bls12381::G1Element) -> Option<Vec<u8>> {
+fn legacy_decrypt(enc: LegacyEncryption, target_key: bls12381::G1Element) -> Option<Vec<u8>> {
     // sigma_prime = V xor H2(e(target_key, u))
     let e = target_key.pairing(&enc.u);
     let mut hash_function = Blake2b256::default();
@@ -143,18 +106,16 @@ fn decrypt(enc: Encryption, target_key: bls12381::G1Element) -> Option<Vec<u8>>
     let hash = hash_function.finalize().digest;
     let m_prime = xor_arrays(&enc.w, &hash);
 
-    // r = H3(sigma_prime | m_prime) as a scalar (the paper has a typo)
+    // r = H3(sigma_prime | m_prime) as a scalar
     let mut hash_function = Blake2b256::default();
     hash_function.update(b"HASH3 - ");
     hash_function.update(&sigma_prime);
     hash_function.update(&m_prime);
     let hash = hash_function.finalize().digest;
-    let r =
-        bls12381::Scalar::from_byte_array(&hash).expect("sigma was chosen above to guarantee this");
+    let r = bls12381::Scalar::from_byte_array(&hash).ok()?;
 
     // U ?= r*g2
-    let g2_r = bls12381::G2Element::generator() * r;
-    if enc.u == g2_r {
+    if enc.u == bls12381::G2Element::generator() * r {
         Some(m_prime)
     } else {
         None
@@ -162,15 +123,7 @@ fn decrypt(enc: Encryption, target_key: bls12381::G1Element) -> Option<Vec<u8>>
 }
 
 fn verify_signature(sig: bls12381::G1Element, round: u64) -> FastCryptoResult<()> {
-    let pk = drand_pk();
-    let target = encode_round(&round);
-    let lhs = bls12381::G1Element::hash_to_group_element(&target).pairing(&pk);
-    let rhs = sig.pairing(&bls12381::G2Element::generator());
-    if lhs == rhs {
-        Ok(())
-    } else {
-        Err(FastCryptoError::InvalidSignature)
-    }
+    ChainInfo::quicknet().verify_signature(round, &sig.to_byte_array())
 }
 
 fn execute(cmd: Command) -> Result<String, std::io::Error> {
@@ -186,7 +139,7 @@ fn execute(cmd: Command) -> Result<String, std::io::Error> {
                 ));
             }
 
-            let enc = encrypt(round, &plaintext);
+            let enc = encrypt(round, &plaintext).map_err(|_| Error::other("Encryption failed."))?;
             let enc = bcs::to_bytes(&enc).unwrap();
 
             let mut result = "Encryption: ".to_string();
@@ -197,7 +150,6 @@ fn execute(cmd: Command) -> Result<String, std::io::Error> {
         Command::Decrypt(arguments) => {
             let ciphertext = hex::decode(arguments.ciphertext)
                 .map_err(|_| Error::new(ErrorKind::InvalidInput, "Invalid ciphertext string."))?;
-            let enc = bcs::from_bytes(&ciphertext).unwrap();
             let round_signature: [u8; 48] = hex::decode(arguments.round_signature)
                 .map_err(|_| Error::new(ErrorKind::InvalidInput, "Invalid round_signature (hex)."))?
                 .try_into()
@@ -209,7 +161,16 @@ fn execute(cmd: Command) -> Result<String, std::io::Error> {
                     Error::new(ErrorKind::InvalidInput, "Invalid round_signature (value).")
                 })?;
 
-            let decrypted_msg = decrypt(enc, round_signature);
+            let decrypted_msg = if let Ok(enc) = bcs::from_bytes::<Encryption>(&ciphertext) {
+                decrypt(enc, round_signature)
+            } else if let Ok(enc) = bcs::from_bytes::<LegacyEncryption>(&ciphertext) {
+                legacy_decrypt(enc, round_signature)
+            } else {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "Invalid ciphertext (value).",
+                ));
+            };
 
             match decrypted_msg {
                 Some(msg) => {
@@ -282,3 +243,29 @@ fn test_e2e() {
 
     assert_eq!(msg, dec);
 }
+
+#[test]
+fn test_legacy_ciphertext() {
+    // Produced by an earlier version of this tool for round 1234 and the message below.
+    let enc = "9401e5069b8bf62c6a014589ea7fb2f0d5d060169f090e2348d20deb2ee71827fb2ba0c24d128f50b77a3819eae0a9c80a6883c9d65d9c358da46532207818bf81d0e4e3f6c612de3f2f16a946d2b3498b0e116097d4cfa6c55a3972d373c65083c186c44bdc180ac32320c20f314820f1c85e741849bf3a63f51b24c7030065b6c1b5b2ae8deaeb81fd430a3e97276a2a706b359ef3a5b3531f3f28075186ef".to_string();
+    let sig ="a81d4aad15461a0a02b43da857be1d782a2232a3c7bb370a2763e95ce1f2628460b24de2cee7453cd12e43c197ea2f23".to_string();
+    let msg = "0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF0123456789ABCDEF".to_string();
+
+    let dec = execute(Command::Decrypt(DecryptArguments {
+        ciphertext: enc.clone(),
+        round_signature: sig,
+    }))
+    .unwrap()["Decrypted message: ".len()..]
+        .to_string()
+        .to_uppercase();
+    assert_eq!(msg, dec);
+
+    // Tampering with the ciphertext makes decryption fail.
+    let mut tampered = hex::decode(enc).unwrap();
+    tampered[100] ^= 1;
+    assert!(execute(Command::Decrypt(DecryptArguments {
+        ciphertext: hex::encode(tampered),
+        round_signature: "a81d4aad15461a0a02b43da857be1d782a2232a3c7bb370a2763e95ce1f2628460b24de2cee7453cd12e43c197ea2f23".to_string(),
+    }))
+    .is_err());
+}
@@ -0,0 +1,389 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Implementation of the KZG commitments for blobs from EIP-4844 as specified in
+//! https://github.com/ethereum/consensus-specs/blob/dev/specs/deneb/polynomial-commitments.md.
+//!
+//! A blob consists of [FIELD_ELEMENTS_PER_BLOB] big-endian encoded scalars which are the
+//! evaluations of a polynomial over the roots of unity of order [FIELD_ELEMENTS_PER_BLOB] in
+//! bit-reversed order. Commitments and proofs are [G1Element]'s, and evaluation points and values
+//! are [Scalar]'s, which are validated as required by the specification when deserialized.
+
+use crate::kzg::EvaluationDomain;
+use fastcrypto::encoding::decode_bytes_hex;
+use fastcrypto::error::{FastCryptoError, FastCryptoResult};
+use fastcrypto::groups::bls12381::{G1Element, G2Element, Scalar};
+use fastcrypto::groups::{GroupElement, MultiScalarMul, Pairing, Scalar as ScalarTrait};
+use fastcrypto::hash::{HashFunction, Sha256};
+use fastcrypto::serde_helpers::ToFromByteArray;
+use std::sync::OnceLock;
+
+/// The number of bytes used to encode a scalar.
+pub const BYTES_PER_FIELD_ELEMENT: usize = 32;
+
+/// The number of scalars in a blob.
+pub const FIELD_ELEMENTS_PER_BLOB: usize = 4096;
+
+/// The number of bytes in a blob.
+pub const BYTES_PER_BLOB: usize = BYTES_PER_FIELD_ELEMENT * FIELD_ELEMENTS_PER_BLOB;
+
+/// The number of powers of tau in G2 in the trusted setup.
+pub const KZG_SETUP_G2_LENGTH: usize = 65;
+
+/// The version byte of versioned hashes of KZG commitments.
+pub const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+const FIAT_SHAMIR_PROTOCOL_DOMAIN: &[u8] = b"FSBLOBVERIFY_V1_";
+const RANDOM_CHALLENGE_KZG_BATCH_DOMAIN: &[u8] = b"RCKZGBATCH___V1_";
+
+/// The trusted setup from the KZG ceremony in the format of trusted_setup.txt from the reference
+/// implementation, c-kzg-4844.
+const MAINNET_TRUSTED_SETUP: &str = include_str!("trusted_setup.txt");
+
+/// The trusted setup from the KZG ceremony used for EIP-4844.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrustedSetup {
+    /// The Lagrange basis [L_i(τ)]_1 in bit-reversed order.
+    g1_lagrange_brp: Vec<G1Element>,
+    /// The powers [τ^i]_2.
+    g2_monomial: Vec<G2Element>,
+    /// The roots of unity of order [FIELD_ELEMENTS_PER_BLOB] in bit-reversed order.
+    roots_of_unity_brp: Vec<Scalar>,
+}
+
+impl TrustedSetup {
+    /// Create a new trusted setup from the Lagrange basis in G1 in natural order and the powers of
+    /// tau in G2. There must be [FIELD_ELEMENTS_PER_BLOB] points in G1 and [KZG_SETUP_G2_LENGTH]
+    /// points in G2.
+    pub fn new(g1_lagrange: Vec<G1Element>, g2_monomial: Vec<G2Element>) -> FastCryptoResult<Self> {
+        if g1_lagrange.len() != FIELD_ELEMENTS_PER_BLOB
+            || g2_monomial.len() != KZG_SETUP_G2_LENGTH
+            || g2_monomial[0] != G2Element::generator()
+        {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let roots_of_unity = EvaluationDomain::new(FIELD_ELEMENTS_PER_BLOB)
+            .expect("Valid domain size")
+            .elements();
+        Ok(Self {
+            g1_lagrange_brp: bit_reversal_permutation(&g1_lagrange),
+            g2_monomial,
+            roots_of_unity_brp: bit_reversal_permutation(&roots_of_unity),
+        })
+    }
+
+    /// Parse a trusted setup in the text format used by the reference implementation
+    /// (trusted_setup.txt): The number of points in G1 and G2 followed by the hex encoded,
+    /// compressed Lagrange basis in G1 and the powers of tau in G2, all separated by whitespace.
+    /// Any remaining G1 points in monomial form, included in newer versions of the file, are
+    /// ignored.
+    pub fn from_text(text: &str) -> FastCryptoResult<Self> {
+        let mut tokens = text.split_whitespace();
+        let mut next_length = || -> FastCryptoResult<usize> {
+            tokens
+                .next()
+                .and_then(|token| token.parse().ok())
+                .ok_or(FastCryptoError::InvalidInput)
+        };
+        let g1_length = next_length()?;
+        let g2_length = next_length()?;
+        if g1_length != FIELD_ELEMENTS_PER_BLOB || g2_length != KZG_SETUP_G2_LENGTH {
+            return Err(FastCryptoError::InvalidInput);
+        }
+
+        let g1_lagrange = tokens
+            .by_ref()
+            .take(g1_length)
+            .map(|token| G1Element::from_byte_array(&decode_bytes_hex(token)?))
+            .collect::<FastCryptoResult<Vec<_>>>()?;
+        let g2_monomial = tokens
+            .take(g2_length)
+            .map(|token| G2Element::from_byte_array(&decode_bytes_hex(token)?))
+            .collect::<FastCryptoResult<Vec<_>>>()?;
+        Self::new(g1_lagrange, g2_monomial)
+    }
+
+    /// The trusted setup used on Ethereum mainnet. It is parsed on first use.
+    pub fn mainnet() -> &'static Self {
+        static MAINNET: OnceLock<TrustedSetup> = OnceLock::new();
+        MAINNET.get_or_init(|| Self::from_text(MAINNET_TRUSTED_SETUP).expect("Valid trusted setup"))
+    }
+
+    /// Compute the commitment to a blob.
+    pub fn blob_to_kzg_commitment(&self, blob: &[u8]) -> FastCryptoResult<G1Element> {
+        let polynomial = blob_to_polynomial(blob)?;
+        G1Element::multi_scalar_mul(&polynomial, &self.g1_lagrange_brp)
+    }
+
+    /// Compute a proof for the evaluation of the polynomial represented by the blob at the given
+    /// point. Returns the proof and the value of the polynomial at the point.
+    pub fn compute_kzg_proof(
+        &self,
+        blob: &[u8],
+        z: &Scalar,
+    ) -> FastCryptoResult<(G1Element, Scalar)> {
+        let polynomial = blob_to_polynomial(blob)?;
+        self.compute_kzg_proof_impl(&polynomial, z)
+    }
+
+    /// Compute a proof for the evaluation of the polynomial represented by the blob at the
+    /// Fiat-Shamir challenge derived from the blob and its commitment.
+    pub fn compute_blob_kzg_proof(
+        &self,
+        blob: &[u8],
+        commitment: &G1Element,
+    ) -> FastCryptoResult<G1Element> {
+        let polynomial = blob_to_polynomial(blob)?;
+        let challenge = compute_challenge(blob, commitment);
+        Ok(self.compute_kzg_proof_impl(&polynomial, &challenge)?.0)
+    }
+
+    /// Verify a proof that the polynomial committed to has the value y at the point z.
+    pub fn verify_kzg_proof(
+        &self,
+        commitment: &G1Element,
+        z: &Scalar,
+        y: &Scalar,
+        proof: &G1Element,
+    ) -> FastCryptoResult<()> {
+        // Check that e(C - [y]_1, -[1]_2) * e(π, [τ - z]_2) = 1.
+        match G1Element::multi_pairing_is_one(
+            &[*commitment - G1Element::generator() * y, *proof],
+            &[
+                -G2Element::generator(),
+                self.g2_monomial[1] - G2Element::generator() * z,
+            ],
+        )? {
+            true => Ok(()),
+            false => Err(FastCryptoError::InvalidProof),
+        }
+    }
+
+    /// Verify a proof computed by [TrustedSetup::compute_blob_kzg_proof] for the given blob and
+    /// commitment.
+    pub fn verify_blob_kzg_proof(
+        &self,
+        blob: &[u8],
+        commitment: &G1Element,
+        proof: &G1Element,
+    ) -> FastCryptoResult<()> {
+        let polynomial = blob_to_polynomial(blob)?;
+        let challenge = compute_challenge(blob, commitment);
+        let y = self.evaluate_polynomial_in_evaluation_form(&polynomial, &challenge);
+        self.verify_kzg_proof(commitment, &challenge, &y, proof)
+    }
+
+    /// Verify a batch of proofs computed by [TrustedSetup::compute_blob_kzg_proof] for the given
+    /// blobs and commitments. This is faster than verifying the proofs one by one.
+    pub fn verify_blob_kzg_proof_batch<B: AsRef<[u8]>>(
+        &self,
+        blobs: &[B],
+        commitments: &[G1Element],
+        proofs: &[G1Element],
+    ) -> FastCryptoResult<()> {
+        if blobs.len() != commitments.len() || blobs.len() != proofs.len() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let mut challenges = Vec::with_capacity(blobs.len());
+        let mut values = Vec::with_capacity(blobs.len());
+        for (blob, commitment) in blobs.iter().zip(commitments) {
+            let polynomial = blob_to_polynomial(blob.as_ref())?;
+            let challenge = compute_challenge(blob.as_ref(), commitment);
+            values.push(self.evaluate_polynomial_in_evaluation_form(&polynomial, &challenge));
+            challenges.push(challenge);
+        }
+        self.verify_kzg_proof_batch(commitments, &challenges, &values, proofs)
+    }
+
+    /// Verify multiple proofs of evaluations using a random linear combination with the
+    /// coefficients derived from the inputs as in the specification.
+    fn verify_kzg_proof_batch(
+        &self,
+        commitments: &[G1Element],
+        zs: &[Scalar],
+        ys: &[Scalar],
+        proofs: &[G1Element],
+    ) -> FastCryptoResult<()> {
+        let n = commitments.len();
+        if n == 0 {
+            return Ok(());
+        }
+
+        let mut data = RANDOM_CHALLENGE_KZG_BATCH_DOMAIN.to_vec();
+        data.extend_from_slice(&(FIELD_ELEMENTS_PER_BLOB as u64).to_be_bytes());
+        data.extend_from_slice(&(n as u64).to_be_bytes());
+        for (((commitment, z), y), proof) in commitments.iter().zip(zs).zip(ys).zip(proofs) {
+            data.extend_from_slice(&commitment.to_byte_array());
+            data.extend_from_slice(&z.to_byte_array());
+            data.extend_from_slice(&y.to_byte_array());
+            data.extend_from_slice(&proof.to_byte_array());
+        }
+        let r = hash_to_bls_field(&data);
+        let r_powers = std::iter::successors(Some(Scalar::generator()), |p| Some(*p * r))
+            .take(n)
+            .collect::<Vec<_>>();
+
+        // Check that e(sum r^i π_i, -[τ]_2) * e(sum r^i (C_i - [y_i]_1 + z_i π_i), [1]_2) = 1.
+        let proof_lincomb = G1Element::multi_scalar_mul(&r_powers, proofs)?;
+        let proof_z_lincomb = G1Element::multi_scalar_mul(
+            &r_powers
+                .iter()
+                .zip(zs)
+                .map(|(r, z)| *r * z)
+                .collect::<Vec<_>>(),
+            proofs,
+        )?;
+        let c_minus_y_lincomb = G1Element::multi_scalar_mul(
+            &r_powers,
+            &commitments
+                .iter()
+                .zip(ys)
+                .map(|(c, y)| *c - G1Element::generator() * y)
+                .collect::<Vec<_>>(),
+        )?;
+        match G1Element::multi_pairing_is_one(
+            &[proof_lincomb, c_minus_y_lincomb + proof_z_lincomb],
+            &[-self.g2_monomial[1], G2Element::generator()],
+        )? {
+            true => Ok(()),
+            false => Err(FastCryptoError::InvalidProof),
+        }
+    }
+
+    /// Compute the proof for the evaluation of the polynomial in evaluation form at z and the
+    /// value of the polynomial at z.
+    fn compute_kzg_proof_impl(
+        &self,
+        polynomial: &[Scalar],
+        z: &Scalar,
+    ) -> FastCryptoResult<(G1Element, Scalar)> {
+        let y = self.evaluate_polynomial_in_evaluation_form(polynomial, z);
+
+        // The quotient (p(X) - y) / (X - z) in evaluation form. If z is in the domain, the
+        // evaluation of the quotient at z is computed separately.
+        let index = self.roots_of_unity_brp.iter().position(|w| w == z);
+        let mut denominators = self
+            .roots_of_unity_brp
+            .iter()
+            .map(|w| *w - z)
+            .collect::<Vec<_>>();
+        if let Some(i) = index {
+            denominators[i] = Scalar::generator();
+        }
+        let mut quotient = polynomial
+            .iter()
+            .zip(Scalar::batch_inverse(&denominators)?)
+            .map(|(p, inverse)| (*p - y) * inverse)
+            .collect::<Vec<_>>();
+        if let Some(i) = index {
+            quotient[i] = self.compute_quotient_eval_within_domain(i, polynomial, &y)?;
+        }
+
+        let proof = G1Element::multi_scalar_mul(&quotient, &self.g1_lagrange_brp)?;
+        Ok((proof, y))
+    }
+
+    /// Compute the evaluation of the quotient (p(X) - y) / (X - z) at z where z is the root of
+    /// unity with the given index and y = p(z).
+    fn compute_quotient_eval_within_domain(
+        &self,
+        index: usize,
+        polynomial: &[Scalar],
+        y: &Scalar,
+    ) -> FastCryptoResult<Scalar> {
+        let z = self.roots_of_unity_brp[index];
+        let mut denominators = self
+            .roots_of_unity_brp
+            .iter()
+            .map(|w| z * (z - w))
+            .collect::<Vec<_>>();
+        denominators[index] = Scalar::generator();
+        Ok(polynomial
+            .iter()
+            .zip(self.roots_of_unity_brp.iter())
+            .zip(Scalar::batch_inverse(&denominators)?)
+            .enumerate()
+            .filter(|(i, _)| *i != index)
+            .fold(Scalar::zero(), |acc, (_, ((p, w), inverse))| {
+                acc + (*p - y) * w * inverse
+            }))
+    }
+
+    /// Evaluate a polynomial in evaluation form at an arbitrary point using the barycentric
+    /// formula.
+    fn evaluate_polynomial_in_evaluation_form(&self, polynomial: &[Scalar], z: &Scalar) -> Scalar {
+        if let Some(i) = self.roots_of_unity_brp.iter().position(|w| w == z) {
+            return polynomial[i];
+        }
+
+        // p(z) = (z^n - 1) / n * sum_i p_i ω_i / (z - ω_i).
+        let denominators = self
+            .roots_of_unity_brp
+            .iter()
+            .map(|w| *z - w)
+            .collect::<Vec<_>>();
+        let sum = polynomial
+            .iter()
+            .zip(self.roots_of_unity_brp.iter())
+            .zip(Scalar::batch_inverse(&denominators).expect("z is not in the domain"))
+            .fold(Scalar::zero(), |acc, ((p, w), inverse)| {
+                acc + *p * w * inverse
+            });
+
+        let mut z_to_n = *z;
+        for _ in 0..FIELD_ELEMENTS_PER_BLOB.trailing_zeros() {
+            z_to_n = z_to_n * z_to_n;
+        }
+        let n_inverse = Scalar::from(FIELD_ELEMENTS_PER_BLOB as u128)
+            .inverse()
+            .expect("Non-zero");
+        sum * (z_to_n - Scalar::generator()) * n_inverse
+    }
+}
+
+/// Compute the versioned hash of a commitment which is used to reference blobs in transactions.
+pub fn kzg_to_versioned_hash(commitment: &G1Element) -> [u8; 32] {
+    let mut hash = Sha256::digest(commitment.to_byte_array()).digest;
+    hash[0] = VERSIONED_HASH_VERSION_KZG;
+    hash
+}
+
+/// Parse a blob as a polynomial in evaluation form. Fails if the blob does not have the right
+/// length or if any of the scalars are not canonically encoded.
+fn blob_to_polynomial(blob: &[u8]) -> FastCryptoResult<Vec<Scalar>> {
+    if blob.len() != BYTES_PER_BLOB {
+        return Err(FastCryptoError::InvalidInput);
+    }
+    blob.chunks_exact(BYTES_PER_FIELD_ELEMENT)
+        .map(|bytes| Scalar::from_byte_array(bytes.try_into().expect("Length is correct")))
+        .collect()
+}
+
+/// Compute the Fiat-Shamir challenge for a blob and its commitment.
+fn compute_challenge(blob: &[u8], commitment: &G1Element) -> Scalar {
+    let mut data = FIAT_SHAMIR_PROTOCOL_DOMAIN.to_vec();
+    data.extend_from_slice(&(FIELD_ELEMENTS_PER_BLOB as u128).to_be_bytes());
+    data.extend_from_slice(blob);
+    data.extend_from_slice(&commitment.to_byte_array());
+    hash_to_bls_field(&data)
+}
+
+/// Hash the data with SHA-256 and reduce the digest, interpreted as a big-endian integer, modulo
+/// the group order.
+fn hash_to_bls_field(data: &[u8]) -> Scalar {
+    let digest = Sha256::digest(data).digest;
+    let (high, low) = digest.split_at(16);
+    let high = u128::from_be_bytes(high.try_into().expect("Length is 16"));
+    let low = u128::from_be_bytes(low.try_into().expect("Length is 16"));
+    let two_to_128 = Scalar::from(u128::MAX) + Scalar::generator();
+    Scalar::from(high) * two_to_128 + Scalar::from(low)
+}
+
+/// Permute the values such that the value at index i is moved to the index given by reversing the
+/// bits of i. The number of values must be a power of two larger than one.
+fn bit_reversal_permutation<T: Copy>(values: &[T]) -> Vec<T> {
+    let bits = values.len().trailing_zeros();
+    (0..values.len())
+        .map(|i| values[i.reverse_bits() >> (usize::BITS - bits)])
+        .collect()
+}
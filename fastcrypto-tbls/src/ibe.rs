@@ -0,0 +1,311 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Implementation of the Boneh-Franklin identity-based encryption scheme over BLS12-381 with the
+//! Fujisaki-Okamoto transform for CCA security, following
+//! [Gailly, Melissaris and Romailler, "tlock: Practical Timelock Encryption from Threshold BLS"](https://eprint.iacr.org/2023/189.pdf).
+//!
+//! The key for an identity is a BLS signature on the identity by the master key, so the scheme
+//! comes in the same two orientations as BLS signatures: [Bls12381MinSig] with identity keys in G1
+//! and master public keys in G2, and [Bls12381MinPk] with identity keys in G2 and master public
+//! keys in G1. The hash functions and the encoding of GT follow drand's implementation, but
+//! interoperability with ciphertexts produced by drand's tools has not been tested.
+//!
+//! [Ciphertext] encrypts messages of 16 to 32 bytes. Longer messages can be encrypted with
+//! [HybridCiphertext] which encrypts a fresh AES-256-GCM key with the IBE.
+//!
+//! # Example
+//! ```rust
+//! # use fastcrypto_tbls::ibe::*;
+//! # use fastcrypto::groups::{bls12381::Scalar, GroupElement, Scalar as ScalarTrait};
+//! use rand::thread_rng;
+//! let master_sk = Scalar::rand(&mut thread_rng());
+//! let master_pk = <Bls12381MinSig as IbeScheme>::Public::generator() * master_sk;
+//! let ciphertext = HybridCiphertext::<Bls12381MinSig>::encrypt(
+//!     &master_pk,
+//!     b"alice",
+//!     b"Hello, world!",
+//!     &mut thread_rng(),
+//! )
+//! .unwrap();
+//! let identity_key = Bls12381MinSig::extract(&master_sk, b"alice");
+//! assert_eq!(ciphertext.decrypt(&identity_key).unwrap(), b"Hello, world!");
+//! ```
+
+use fastcrypto::aes::{Aes256Gcm, AesKey, AuthenticatedCipher, InitializationVector};
+use fastcrypto::error::{FastCryptoError, FastCryptoResult};
+use fastcrypto::groups::bls12381::{G1Element, G2Element, GTElement, Scalar};
+use fastcrypto::groups::{GroupElement, HashToGroupElement, Pairing};
+use fastcrypto::hash::{HashFunction, Sha256};
+use fastcrypto::serde_helpers::ToFromByteArray;
+use fastcrypto::traits::{AllowedRng, ToFromBytes};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use typenum::{U12, U32};
+
+/// The minimal length of a message encrypted with [Ciphertext]. Since U = H3(sigma, m) G is public
+/// and sigma has the same length as the message, shorter messages could be recovered by searching
+/// over all pairs (sigma, m).
+pub const MIN_MESSAGE_LENGTH: usize = 16;
+
+/// The maximal length of a message encrypted with [Ciphertext].
+pub const MAX_MESSAGE_LENGTH: usize = 32;
+
+const H2_TAG: &[u8] = b"IBE-H2";
+const H3_TAG: &[u8] = b"IBE-H3";
+const H4_TAG: &[u8] = b"IBE-H4";
+
+/// Trait [IbeScheme] defines the groups used by an orientation of the IBE scheme.
+pub trait IbeScheme {
+    /// `Public` represents the group over which the master public keys are represented.
+    type Public: GroupElement<ScalarType = Scalar> + Serialize + DeserializeOwned;
+    /// `Signature` represents the group over which identities and identity keys are represented.
+    type Signature: GroupElement<ScalarType = Scalar>
+        + HashToGroupElement
+        + Serialize
+        + DeserializeOwned;
+
+    /// The pairing of an element of each group.
+    fn pairing(signature: &Self::Signature, public: &Self::Public) -> GTElement;
+
+    /// Compute the key for an identity, which is a BLS signature on the identity.
+    fn extract(master_sk: &Scalar, identity: &[u8]) -> Self::Signature {
+        Self::Signature::hash_to_group_element(identity) * master_sk
+    }
+
+    /// Verify that the key for an identity is valid for the given master public key.
+    fn verify_identity_key(
+        master_pk: &Self::Public,
+        identity: &[u8],
+        identity_key: &Self::Signature,
+    ) -> FastCryptoResult<()> {
+        let hashed_identity = Self::Signature::hash_to_group_element(identity);
+        match Self::pairing(identity_key, &Self::Public::generator())
+            == Self::pairing(&hashed_identity, master_pk)
+        {
+            true => Ok(()),
+            false => Err(FastCryptoError::InvalidSignature),
+        }
+    }
+}
+
+/// Implementation of [IbeScheme] for BLS12-381-min-sig, which is used by e.g. drand's quicknet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bls12381MinSig {}
+
+impl IbeScheme for Bls12381MinSig {
+    type Public = G2Element;
+    type Signature = G1Element;
+
+    fn pairing(signature: &Self::Signature, public: &Self::Public) -> GTElement {
+        signature.pairing(public)
+    }
+}
+
+/// Implementation of [IbeScheme] for BLS12-381-min-pk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bls12381MinPk {}
+
+impl IbeScheme for Bls12381MinPk {
+    type Public = G1Element;
+    type Signature = G2Element;
+
+    fn pairing(signature: &Self::Signature, public: &Self::Public) -> GTElement {
+        public.pairing(signature)
+    }
+}
+
+/// An encryption of a message of [MIN_MESSAGE_LENGTH] to [MAX_MESSAGE_LENGTH] bytes for an identity.
+/// - Encryptor selects a random sigma of the same length as the message m and computes
+///   r = H3(sigma, m).
+/// - The ciphertext is U = r G, V = sigma XOR H2(e(H1(id), P)^r) and W = m XOR H4(sigma), where G
+///   is the generator and P is the master public key.
+/// - Decryptor with the identity key K = s H1(id) computes sigma = V XOR H2(e(K, U)),
+///   m = W XOR H4(sigma) and r = H3(sigma, m), and checks that U = r G.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Ciphertext<S: IbeScheme> {
+    u: S::Public,
+    v: Vec<u8>,
+    w: Vec<u8>,
+}
+
+impl<S: IbeScheme> Ciphertext<S> {
+    /// Encrypt a message of [MIN_MESSAGE_LENGTH] to [MAX_MESSAGE_LENGTH] bytes for the given
+    /// identity.
+    pub fn encrypt<R: AllowedRng>(
+        master_pk: &S::Public,
+        identity: &[u8],
+        message: &[u8],
+        rng: &mut R,
+    ) -> FastCryptoResult<Self> {
+        if message.len() < MIN_MESSAGE_LENGTH {
+            return Err(FastCryptoError::InputTooShort(MIN_MESSAGE_LENGTH));
+        }
+        if message.len() > MAX_MESSAGE_LENGTH {
+            return Err(FastCryptoError::InputTooLong(MAX_MESSAGE_LENGTH));
+        }
+
+        // Since r is derived from sigma by rejection sampling, a few sigmas may fail.
+        let (sigma, r) = loop {
+            let mut sigma = vec![0u8; message.len()];
+            rng.fill_bytes(&mut sigma);
+            if let Some(r) = h3(&sigma, message) {
+                break (sigma, r);
+            }
+        };
+
+        let hashed_identity = S::Signature::hash_to_group_element(identity);
+        let r_gid = S::pairing(&hashed_identity, master_pk) * r;
+        Ok(Self {
+            u: S::Public::generator() * r,
+            v: xor(&sigma, &h2(&r_gid)),
+            w: xor(message, &h4(&sigma)),
+        })
+    }
+
+    /// Decrypt this ciphertext using the key for the identity it was encrypted for.
+    pub fn decrypt(&self, identity_key: &S::Signature) -> FastCryptoResult<Vec<u8>> {
+        if self.v.len() != self.w.len()
+            || !(MIN_MESSAGE_LENGTH..=MAX_MESSAGE_LENGTH).contains(&self.v.len())
+        {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let r_gid = S::pairing(identity_key, &self.u);
+        let sigma = xor(&self.v, &h2(&r_gid));
+        let message = xor(&self.w, &h4(&sigma));
+        match h3(&sigma, &message) {
+            Some(r) if S::Public::generator() * r == self.u => Ok(message),
+            _ => Err(FastCryptoError::GeneralError(
+                "Decryption failed".to_string(),
+            )),
+        }
+    }
+
+    /// Serialize as U || V || W with U compressed. This is the layout drand's implementation uses,
+    /// but it has not been tested against ciphertexts produced by drand's tools.
+    pub fn to_drand_bytes(&self) -> Vec<u8> {
+        let mut bytes =
+            bcs::to_bytes(&self.u).expect("Serialization of group elements never fails");
+        bytes.extend_from_slice(&self.v);
+        bytes.extend_from_slice(&self.w);
+        bytes
+    }
+
+    /// Deserialize from U || V || W with U compressed, see [Ciphertext::to_drand_bytes].
+    pub fn from_drand_bytes(bytes: &[u8]) -> FastCryptoResult<Self> {
+        let u_length = bcs::to_bytes(&S::Public::generator())
+            .expect("Serialization of group elements never fails")
+            .len();
+        if bytes.len() < u_length {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let (u, vw) = bytes.split_at(u_length);
+        let (v, w) = vw.split_at(vw.len() / 2);
+        if v.len() != w.len() || !(MIN_MESSAGE_LENGTH..=MAX_MESSAGE_LENGTH).contains(&v.len()) {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(Self {
+            u: bcs::from_bytes(u).map_err(|_| FastCryptoError::InvalidInput)?,
+            v: v.to_vec(),
+            w: w.to_vec(),
+        })
+    }
+}
+
+/// An encryption of a message of arbitrary length for an identity. A fresh AES-256-GCM key is
+/// encrypted with [Ciphertext] and the message is encrypted with that key. Since the key is never
+/// reused, the nonce is fixed to zero.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct HybridCiphertext<S: IbeScheme> {
+    key: Ciphertext<S>,
+    ciphertext: Vec<u8>,
+}
+
+impl<S: IbeScheme> HybridCiphertext<S> {
+    /// Encrypt a message for the given identity.
+    pub fn encrypt<R: AllowedRng>(
+        master_pk: &S::Public,
+        identity: &[u8],
+        message: &[u8],
+        rng: &mut R,
+    ) -> FastCryptoResult<Self> {
+        let mut key = [0u8; 32];
+        rng.fill_bytes(&mut key);
+        Ok(Self {
+            key: Ciphertext::encrypt(master_pk, identity, &key, rng)?,
+            ciphertext: sym_cipher(&key).encrypt_authenticated(&zero_nonce(), &[], message),
+        })
+    }
+
+    /// Decrypt this ciphertext using the key for the identity it was encrypted for.
+    pub fn decrypt(&self, identity_key: &S::Signature) -> FastCryptoResult<Vec<u8>> {
+        let key = self.key.decrypt(identity_key)?;
+        let key: [u8; 32] = key.try_into().map_err(|_| FastCryptoError::InvalidInput)?;
+        sym_cipher(&key).decrypt_authenticated(&zero_nonce(), &[], &self.ciphertext)
+    }
+}
+
+fn sym_cipher(key: &[u8; 32]) -> Aes256Gcm<U12> {
+    Aes256Gcm::<U12>::new(AesKey::<U32>::from_bytes(key).expect("New shouldn't fail"))
+}
+
+fn zero_nonce() -> InitializationVector<U12> {
+    InitializationVector::<U12>::from_bytes(&[0u8; 12]).expect("New shouldn't fail")
+}
+
+/// H2(x) = SHA-256("IBE-H2" || x) where x in GT is encoded as by drand.
+fn h2(x: &GTElement) -> [u8; 32] {
+    let mut hash = Sha256::new();
+    hash.update(H2_TAG);
+    hash.update(gt_to_drand_bytes(x));
+    hash.finalize().digest
+}
+
+/// H3(sigma, m) is the first of SHA-256(i as little-endian u16 || SHA-256("IBE-H3" || sigma || m))
+/// for i = 1, 2, ... which, with the top bit cleared, is a canonical big-endian scalar.
+fn h3(sigma: &[u8], message: &[u8]) -> Option<Scalar> {
+    let mut hash = Sha256::new();
+    hash.update(H3_TAG);
+    hash.update(sigma);
+    hash.update(message);
+    let buffer = hash.finalize().digest;
+    (1..u16::MAX).find_map(|i| {
+        let mut hash = Sha256::new();
+        hash.update(i.to_le_bytes());
+        hash.update(buffer);
+        let mut bytes = hash.finalize().digest;
+        bytes[0] >>= 1;
+        Scalar::from_byte_array(&bytes).ok()
+    })
+}
+
+/// H4(sigma) = SHA-256("IBE-H4" || sigma).
+fn h4(sigma: &[u8]) -> [u8; 32] {
+    let mut hash = Sha256::new();
+    hash.update(H4_TAG);
+    hash.update(sigma);
+    hash.finalize().digest
+}
+
+/// The encoding of GT used by drand. With Fp12 = Fp6[w] and Fp6 = Fp2[v], this lists the twelve
+/// coefficients over Fp from the coefficient of w v^2 u down to the constant coefficient, whereas
+/// `GTElement::to_byte_array` interleaves the coefficients of 1 and w.
+pub(crate) fn gt_to_drand_bytes(x: &GTElement) -> Vec<u8> {
+    const FP_LENGTH: usize = 48;
+    let bytes = x.to_byte_array();
+    (0..12)
+        .rev()
+        .flat_map(|n| {
+            // The n'th coefficient is for w^j v^i u^c where n = 6j + 2i + c.
+            let (j, i, c) = (n / 6, (n % 6) / 2, n % 2);
+            let k = 4 * i + 2 * j + c;
+            bytes[k * FP_LENGTH..(k + 1) * FP_LENGTH].to_vec()
+        })
+        .collect()
+}
+
+fn xor(a: &[u8], b: &[u8]) -> Vec<u8> {
+    a.iter().zip(b.iter()).map(|(a, b)| a ^ b).collect()
+}
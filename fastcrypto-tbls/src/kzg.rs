@@ -0,0 +1,549 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Implementation of KZG polynomial commitments over BLS12-381, see
+//! https://www.iacr.org/archive/asiacrypt2010/6477178/6477178.pdf.
+//!
+//! A commitment to a polynomial p of degree at most n - 1 is [p(τ)]_1 where τ is the secret of a
+//! powers-of-tau structured reference string (SRS) ([τ^i]_1 for i < n, [τ^i]_2 for i < m). An
+//! opening proof for the value y = p(z) is a commitment to the quotient (p(X) - y) / (X - z), and
+//! an opening proof for values at k points is a commitment to (p(X) - I(X)) / Z(X) where I is the
+//! polynomial of degree k - 1 interpolating the values and Z is the vanishing polynomial of the
+//! points. The latter requires k < m.
+//!
+//! Polynomials are given in coefficient form as [Poly]'s or in Lagrange form as their evaluations
+//! over an [EvaluationDomain] of roots of unity.
+
+use crate::dl_verification::get_random_scalars;
+use crate::polynomial::Poly;
+use fastcrypto::error::{FastCryptoError, FastCryptoResult};
+use fastcrypto::groups::bls12381::{
+    G1Element, G2Element, Scalar, G1_ELEMENT_BYTE_LENGTH, G2_ELEMENT_BYTE_LENGTH,
+};
+use fastcrypto::groups::{GroupElement, MultiScalarMul, Pairing, Scalar as ScalarTrait};
+use fastcrypto::serde_helpers::ToFromByteArray;
+use fastcrypto::traits::AllowedRng;
+
+/// The largest power of two, 2^32, dividing the order of the multiplicative group of the scalars.
+const TWO_ADICITY: u32 = 32;
+
+/// A primitive 2^32'th root of unity, 7^((r - 1) / 2^32), in big-endian form.
+const TWO_ADIC_ROOT_OF_UNITY: [u8; 32] = [
+    0x16, 0xa2, 0xa1, 0x9e, 0xdf, 0xe8, 0x1f, 0x20, 0xd0, 0x9b, 0x68, 0x19, 0x22, 0xc8, 0x13, 0xb4,
+    0xb6, 0x36, 0x83, 0x50, 0x8c, 0x22, 0x80, 0xb9, 0x38, 0x29, 0x97, 0x1f, 0x43, 0x9f, 0x0d, 0x2b,
+];
+
+/// A powers-of-tau structured reference string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SRS {
+    /// [τ^i]_1 for i = 0, ..., n - 1.
+    g1_powers: Vec<G1Element>,
+    /// [τ^i]_2 for i = 0, ..., m - 1.
+    g2_powers: Vec<G2Element>,
+}
+
+impl SRS {
+    /// Create a new SRS from the powers of tau in G1 and G2. The first powers must be the
+    /// generators, and at least two powers in G2 are needed to verify openings. Use
+    /// [SRS::check_consistency] to verify that the powers are powers of the same tau.
+    pub fn new(g1_powers: Vec<G1Element>, g2_powers: Vec<G2Element>) -> FastCryptoResult<Self> {
+        if g1_powers.is_empty()
+            || g2_powers.len() < 2
+            || g1_powers[0] != G1Element::generator()
+            || g2_powers[0] != G2Element::generator()
+        {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(Self {
+            g1_powers,
+            g2_powers,
+        })
+    }
+
+    /// Create a new SRS from compressed serializations of the powers of tau, e.g. from the output
+    /// of a powers-of-tau ceremony. All points are checked to be in the right subgroups.
+    pub fn from_byte_arrays(
+        g1_powers: &[[u8; G1_ELEMENT_BYTE_LENGTH]],
+        g2_powers: &[[u8; G2_ELEMENT_BYTE_LENGTH]],
+    ) -> FastCryptoResult<Self> {
+        Self::new(
+            g1_powers
+                .iter()
+                .map(G1Element::from_byte_array)
+                .collect::<FastCryptoResult<_>>()?,
+            g2_powers
+                .iter()
+                .map(G2Element::from_byte_array)
+                .collect::<FastCryptoResult<_>>()?,
+        )
+    }
+
+    /// Create an SRS from a known tau. This is insecure since anyone knowing tau can create false
+    /// opening proofs, so it should only be used for testing. As for [SRS::new], `g1_size` must be
+    /// positive and `g2_size` must be at least two.
+    pub fn insecure_from_tau(
+        tau: &Scalar,
+        g1_size: usize,
+        g2_size: usize,
+    ) -> FastCryptoResult<Self> {
+        let powers = |size: usize| {
+            std::iter::successors(Some(Scalar::generator()), |p| Some(*p * tau))
+                .take(size)
+                .collect::<Vec<_>>()
+        };
+        Self::new(
+            powers(g1_size)
+                .iter()
+                .map(|p| G1Element::generator() * p)
+                .collect(),
+            powers(g2_size)
+                .iter()
+                .map(|p| G2Element::generator() * p)
+                .collect(),
+        )
+    }
+
+    /// Check that the powers in the SRS are consecutive powers of the same tau, using a random
+    /// linear combination of the powers.
+    pub fn check_consistency<R: AllowedRng>(&self, rng: &mut R) -> FastCryptoResult<()> {
+        // Check that e([τ^(i+1)]_1, [1]_2) = e([τ^i]_1, [τ]_2) for all i.
+        if self.g1_powers.len() > 1 {
+            let n = self.g1_powers.len() - 1;
+            let rs = get_random_scalars::<Scalar, R>(n, rng);
+            let lhs = G1Element::multi_scalar_mul(&rs, &self.g1_powers[1..])?;
+            let rhs = G1Element::multi_scalar_mul(&rs, &self.g1_powers[..n])?;
+            if !G1Element::multi_pairing_is_one(
+                &[lhs, -rhs],
+                &[self.g2_powers[0], self.g2_powers[1]],
+            )? {
+                return Err(FastCryptoError::InvalidInput);
+            }
+        }
+
+        // Check that e([1]_1, [τ^(i+1)]_2) = e([τ]_1, [τ^i]_2) for all i. This requires that
+        // there are at least two powers in G1.
+        let m = self.g2_powers.len() - 1;
+        if self.g1_powers.len() < 2 {
+            return match m {
+                1 => Ok(()),
+                _ => Err(FastCryptoError::InvalidInput),
+            };
+        }
+        let rs = get_random_scalars::<Scalar, R>(m, rng);
+        let lhs = G2Element::multi_scalar_mul(&rs, &self.g2_powers[1..])?;
+        let rhs = G2Element::multi_scalar_mul(&rs, &self.g2_powers[..m])?;
+        match G1Element::multi_pairing_is_one(
+            &[self.g1_powers[0], -self.g1_powers[1]],
+            &[lhs, rhs],
+        )? {
+            true => Ok(()),
+            false => Err(FastCryptoError::InvalidInput),
+        }
+    }
+
+    /// The powers of tau in G1.
+    pub fn g1_powers(&self) -> &[G1Element] {
+        &self.g1_powers
+    }
+
+    /// The powers of tau in G2.
+    pub fn g2_powers(&self) -> &[G2Element] {
+        &self.g2_powers
+    }
+
+    /// The maximal degree of polynomials that can be committed to.
+    pub fn max_degree(&self) -> usize {
+        self.g1_powers.len() - 1
+    }
+
+    /// The maximal number of points that can be opened at once.
+    pub fn max_points(&self) -> usize {
+        self.g2_powers.len() - 1
+    }
+
+    /// Commit to a polynomial in coefficient form.
+    pub fn commit(&self, poly: &Poly<Scalar>) -> FastCryptoResult<G1Element> {
+        self.commit_coefficients(poly.as_vec())
+    }
+
+    /// Compute the Lagrange basis [L_i(τ)]_1 for the given domain, which can be used to commit to
+    /// polynomials in Lagrange form using [commit_lagrange]. The size of the domain must be at
+    /// most the number of powers in G1.
+    pub fn lagrange_basis(&self, domain: &EvaluationDomain) -> FastCryptoResult<Vec<G1Element>> {
+        if domain.size() > self.g1_powers.len() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        // Since L_i(X) = 1/n * sum_j ω^(-ij) X^j, the basis is the inverse FFT of the powers.
+        let mut basis = self.g1_powers[..domain.size()].to_vec();
+        domain.ifft_in_place(&mut basis);
+        Ok(basis)
+    }
+
+    /// Open a polynomial in coefficient form at a single point. Returns the value of the polynomial
+    /// at the point and the opening proof.
+    pub fn open(
+        &self,
+        poly: &Poly<Scalar>,
+        point: &Scalar,
+    ) -> FastCryptoResult<(Scalar, G1Element)> {
+        let (quotient, value) = divide_by_linear(poly.as_vec(), point);
+        Ok((value, self.commit_coefficients(&quotient)?))
+    }
+
+    /// Open a polynomial in Lagrange form over the given domain at a single point. Returns the
+    /// value of the polynomial at the point and the opening proof.
+    pub fn open_lagrange(
+        &self,
+        domain: &EvaluationDomain,
+        evaluations: &[Scalar],
+        point: &Scalar,
+    ) -> FastCryptoResult<(Scalar, G1Element)> {
+        self.open(&domain.interpolate(evaluations)?, point)
+    }
+
+    /// Open a polynomial in coefficient form at multiple distinct points. Returns the values of the
+    /// polynomial at the points and a single opening proof for all of them.
+    pub fn open_multi(
+        &self,
+        poly: &Poly<Scalar>,
+        points: &[Scalar],
+    ) -> FastCryptoResult<(Vec<Scalar>, G1Element)> {
+        if points.is_empty() || points.len() > self.max_points() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let values = points
+            .iter()
+            .map(|z| evaluate(poly.as_vec(), z))
+            .collect::<Vec<_>>();
+        let interpolation = interpolate(points, &values)?;
+        let numerator = subtract(poly.as_vec(), &interpolation);
+        let quotient = divide_by_monic(&numerator, &vanishing_polynomial(points));
+        Ok((values, self.commit_coefficients(&quotient)?))
+    }
+
+    /// Verify an opening proof that the polynomial committed to has the given value at the point.
+    pub fn verify(
+        &self,
+        commitment: &G1Element,
+        point: &Scalar,
+        value: &Scalar,
+        proof: &G1Element,
+    ) -> FastCryptoResult<()> {
+        // Check that e(C - [y]_1, [1]_2) = e(π, [τ - z]_2), or equivalently that
+        // e(C - [y]_1 + z * π, [1]_2) - e(π, [τ]_2) = 0.
+        let lhs = *commitment - G1Element::generator() * value + *proof * point;
+        match G1Element::multi_pairing_is_one(
+            &[lhs, -*proof],
+            &[self.g2_powers[0], self.g2_powers[1]],
+        )? {
+            true => Ok(()),
+            false => Err(FastCryptoError::InvalidProof),
+        }
+    }
+
+    /// Verify an opening proof that the polynomial committed to has the given values at the
+    /// points.
+    pub fn verify_multi(
+        &self,
+        commitment: &G1Element,
+        points: &[Scalar],
+        values: &[Scalar],
+        proof: &G1Element,
+    ) -> FastCryptoResult<()> {
+        if points.is_empty() || points.len() != values.len() || points.len() > self.max_points() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let interpolation = interpolate(points, values)?;
+        if interpolation.len() > self.g1_powers.len() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let vanishing = vanishing_polynomial(points);
+
+        // Check that e(C - [I(τ)]_1, [1]_2) = e(π, [Z(τ)]_2).
+        let interpolation_commitment = self.commit_coefficients(&interpolation)?;
+        let vanishing_commitment =
+            G2Element::multi_scalar_mul(&vanishing, &self.g2_powers[..vanishing.len()])?;
+        match G1Element::multi_pairing_is_one(
+            &[*commitment - interpolation_commitment, -*proof],
+            &[self.g2_powers[0], vanishing_commitment],
+        )? {
+            true => Ok(()),
+            false => Err(FastCryptoError::InvalidProof),
+        }
+    }
+
+    /// Verify a batch of single point openings given as tuples of (commitment, point, value,
+    /// proof) using a random linear combination and a single multi-pairing.
+    pub fn batch_verify<R: AllowedRng>(
+        &self,
+        openings: &[(G1Element, Scalar, Scalar, G1Element)],
+        rng: &mut R,
+    ) -> FastCryptoResult<()> {
+        if openings.is_empty() {
+            return Ok(());
+        }
+
+        // With random r_i, check that
+        // e(sum r_i (C_i - [y_i]_1 + z_i * π_i), [1]_2) = e(sum r_i π_i, [τ]_2).
+        let rs = get_random_scalars::<Scalar, R>(openings.len(), rng);
+        let mut scalars = Vec::with_capacity(2 * openings.len() + 1);
+        let mut points = Vec::with_capacity(2 * openings.len() + 1);
+        let mut sum_of_values = Scalar::zero();
+        for (r, (commitment, point, value, proof)) in rs.iter().zip(openings) {
+            scalars.push(*r);
+            points.push(*commitment);
+            scalars.push(*r * point);
+            points.push(*proof);
+            sum_of_values += *r * value;
+        }
+        scalars.push(-sum_of_values);
+        points.push(G1Element::generator());
+
+        let lhs = G1Element::multi_scalar_mul(&scalars, &points)?;
+        let proofs = openings.iter().map(|o| o.3).collect::<Vec<_>>();
+        let rhs = G1Element::multi_scalar_mul(&rs, &proofs)?;
+        match G1Element::multi_pairing_is_one(
+            &[lhs, -rhs],
+            &[self.g2_powers[0], self.g2_powers[1]],
+        )? {
+            true => Ok(()),
+            false => Err(FastCryptoError::InvalidProof),
+        }
+    }
+
+    fn commit_coefficients(&self, coefficients: &[Scalar]) -> FastCryptoResult<G1Element> {
+        if coefficients.len() > self.g1_powers.len() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        if coefficients.is_empty() {
+            return Ok(G1Element::zero());
+        }
+        G1Element::multi_scalar_mul(coefficients, &self.g1_powers[..coefficients.len()])
+    }
+}
+
+/// Commit to a polynomial in Lagrange form using a Lagrange basis computed by
+/// [SRS::lagrange_basis].
+pub fn commit_lagrange(
+    lagrange_basis: &[G1Element],
+    evaluations: &[Scalar],
+) -> FastCryptoResult<G1Element> {
+    if lagrange_basis.len() != evaluations.len() {
+        return Err(FastCryptoError::InvalidInput);
+    }
+    G1Element::multi_scalar_mul(evaluations, lagrange_basis)
+}
+
+/// The multiplicative subgroup of the scalars of order n = 2^k, {1, ω, ω^2, ..., ω^(n-1)}.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EvaluationDomain {
+    size: usize,
+    omega: Scalar,
+    omega_inverse: Scalar,
+    size_inverse: Scalar,
+}
+
+impl EvaluationDomain {
+    /// Create a new domain of the given size, which must be a power of two smaller than or equal
+    /// to 2^32.
+    pub fn new(size: usize) -> FastCryptoResult<Self> {
+        if !size.is_power_of_two() || size.trailing_zeros() > TWO_ADICITY {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let mut omega =
+            Scalar::from_byte_array(&TWO_ADIC_ROOT_OF_UNITY).expect("Constant is a valid scalar");
+        for _ in size.trailing_zeros()..TWO_ADICITY {
+            omega = omega * omega;
+        }
+        Ok(Self {
+            size,
+            omega,
+            omega_inverse: omega.inverse().expect("Non-zero"),
+            size_inverse: Scalar::from(size as u128).inverse().expect("Non-zero"),
+        })
+    }
+
+    /// The number of elements in the domain.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// The generator ω of the domain.
+    pub fn generator(&self) -> Scalar {
+        self.omega
+    }
+
+    /// The elements of the domain, 1, ω, ω^2, ..., ω^(n-1).
+    pub fn elements(&self) -> Vec<Scalar> {
+        std::iter::successors(Some(Scalar::generator()), |x| Some(*x * self.omega))
+            .take(self.size)
+            .collect()
+    }
+
+    /// Compute the polynomial of degree at most n - 1 with the given evaluations over the domain.
+    pub fn interpolate(&self, evaluations: &[Scalar]) -> FastCryptoResult<Poly<Scalar>> {
+        if evaluations.len() != self.size {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let mut coefficients = evaluations.to_vec();
+        self.ifft_in_place(&mut coefficients);
+        Ok(Poly::from(coefficients))
+    }
+
+    /// Evaluate a polynomial of degree at most n - 1 over the domain.
+    pub fn evaluate(&self, poly: &Poly<Scalar>) -> FastCryptoResult<Vec<Scalar>> {
+        if poly.as_vec().len() > self.size {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let mut evaluations = poly.as_vec().clone();
+        evaluations.resize(self.size, Scalar::zero());
+        fft_in_place(&mut evaluations, &self.omega);
+        Ok(evaluations)
+    }
+
+    /// Compute the inverse FFT of the given values which should have the same length as the
+    /// size of the domain.
+    fn ifft_in_place<G: GroupElement<ScalarType = Scalar>>(&self, values: &mut [G]) {
+        fft_in_place(values, &self.omega_inverse);
+        values.iter_mut().for_each(|v| *v = *v * self.size_inverse);
+    }
+}
+
+/// Compute the FFT of the given values in place using the iterative Cooley-Tukey algorithm, i.e.,
+/// replace the values v_j with sum_j ω^(ij) v_j. The number of values must be a power of two and
+/// omega must be a primitive root of unity of that order.
+fn fft_in_place<G: GroupElement<ScalarType = Scalar>>(values: &mut [G], omega: &Scalar) {
+    let n = values.len();
+    if n <= 1 {
+        return;
+    }
+    let log_n = n.trailing_zeros();
+
+    // Bit-reversal permutation.
+    for i in 0..n {
+        let j = i.reverse_bits() >> (usize::BITS - log_n);
+        if i < j {
+            values.swap(i, j);
+        }
+    }
+
+    let mut m = 1;
+    while m < n {
+        // The twiddle factors are powers of a primitive (2m)'th root of unity.
+        let mut w_m = *omega;
+        for _ in 0..(log_n - (2 * m).trailing_zeros()) {
+            w_m = w_m * w_m;
+        }
+        let twiddles = std::iter::successors(Some(Scalar::generator()), |w| Some(*w * w_m))
+            .take(m)
+            .collect::<Vec<_>>();
+        for k in (0..n).step_by(2 * m) {
+            for j in 0..m {
+                let t = values[k + j + m] * twiddles[j];
+                let u = values[k + j];
+                values[k + j] = u + t;
+                values[k + j + m] = u - t;
+            }
+        }
+        m *= 2;
+    }
+}
+
+/// Evaluate the polynomial with the given coefficients at a point using Horner's method.
+fn evaluate(coefficients: &[Scalar], point: &Scalar) -> Scalar {
+    coefficients
+        .iter()
+        .rev()
+        .fold(Scalar::zero(), |acc, c| acc * point + c)
+}
+
+/// Divide the polynomial with the given coefficients by (X - z). Returns the quotient and the
+/// remainder which is equal to the value of the polynomial at z.
+fn divide_by_linear(coefficients: &[Scalar], z: &Scalar) -> (Vec<Scalar>, Scalar) {
+    let mut quotient = vec![Scalar::zero(); coefficients.len().saturating_sub(1)];
+    let mut remainder = Scalar::zero();
+    for (i, c) in coefficients.iter().enumerate().rev() {
+        let next = *c + remainder * z;
+        if i > 0 {
+            quotient[i - 1] = next;
+        }
+        remainder = next;
+    }
+    (quotient, remainder)
+}
+
+/// Divide a polynomial by a monic polynomial and return the quotient.
+fn divide_by_monic(numerator: &[Scalar], denominator: &[Scalar]) -> Vec<Scalar> {
+    let d = denominator.len() - 1;
+    if numerator.len() <= d {
+        return vec![];
+    }
+    let mut remainder = numerator.to_vec();
+    let mut quotient = vec![Scalar::zero(); numerator.len() - d];
+    for i in (0..quotient.len()).rev() {
+        let q = remainder[i + d];
+        quotient[i] = q;
+        for (j, c) in denominator.iter().enumerate() {
+            remainder[i + j] -= q * c;
+        }
+    }
+    quotient
+}
+
+/// Compute the coefficients of a - b.
+fn subtract(a: &[Scalar], b: &[Scalar]) -> Vec<Scalar> {
+    let mut result = a.to_vec();
+    if result.len() < b.len() {
+        result.resize(b.len(), Scalar::zero());
+    }
+    result.iter_mut().zip(b).for_each(|(r, b)| *r -= *b);
+    result
+}
+
+/// Compute the coefficients of the polynomial Z(X) = (X - z_1) ... (X - z_k).
+fn vanishing_polynomial(points: &[Scalar]) -> Vec<Scalar> {
+    let mut result = vec![Scalar::generator()];
+    for z in points {
+        // Multiply by (X - z).
+        let mut next = vec![Scalar::zero(); result.len() + 1];
+        for (i, c) in result.iter().enumerate() {
+            next[i + 1] += *c;
+            next[i] -= *c * z;
+        }
+        result = next;
+    }
+    result
+}
+
+/// Compute the coefficients of the polynomial of degree at most k - 1 which has the given values
+/// at the given k distinct points using Lagrange interpolation.
+fn interpolate(points: &[Scalar], values: &[Scalar]) -> FastCryptoResult<Vec<Scalar>> {
+    let vanishing = vanishing_polynomial(points);
+
+    // The denominators prod_{j != i} (z_i - z_j) are zero if and only if the points are not
+    // distinct.
+    let denominators = points
+        .iter()
+        .enumerate()
+        .map(|(i, z_i)| {
+            points
+                .iter()
+                .enumerate()
+                .filter(|(j, _)| *j != i)
+                .fold(Scalar::generator(), |acc, (_, z_j)| acc * (*z_i - z_j))
+        })
+        .collect::<Vec<_>>();
+    let inverses = Scalar::batch_inverse(&denominators)?;
+
+    let mut result = vec![Scalar::zero(); points.len()];
+    for ((z, y), inverse) in points.iter().zip(values).zip(inverses) {
+        // Compute prod_{j != i} (X - z_j) = Z(X) / (X - z_i).
+        let (basis, _) = divide_by_linear(&vanishing, z);
+        let factor = *y * inverse;
+        result
+            .iter_mut()
+            .zip(basis)
+            .for_each(|(r, b)| *r += b * factor);
+    }
+    Ok(result)
+}
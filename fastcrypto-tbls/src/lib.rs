@@ -14,12 +14,19 @@
 pub mod dkg_v1;
 pub mod dl_verification;
 pub mod ecies_v1;
+pub mod eip4844;
+pub mod ibe;
+pub mod kzg;
 pub mod mocked_dkg;
 pub mod nizk;
 pub mod nodes;
+pub mod pedersen;
 pub mod polynomial;
 pub mod random_oracle;
+pub mod sigma;
 pub mod tbls;
+pub mod tlock;
+pub mod transcript;
 pub mod types;
 
 // TODO: needs to use ecies_v1
@@ -42,14 +49,42 @@ pub mod ecies_v1_tests;
 #[path = "tests/random_oracle_tests.rs"]
 pub mod random_oracle_tests;
 
+#[cfg(test)]
+#[path = "tests/tlock_tests.rs"]
+pub mod tlock_tests;
+
+#[cfg(test)]
+#[path = "tests/transcript_tests.rs"]
+pub mod transcript_tests;
+
 #[cfg(test)]
 #[path = "tests/dkg_v1_tests.rs"]
 pub mod dkg_v1_tests;
 
+#[cfg(test)]
+#[path = "tests/eip4844_tests.rs"]
+pub mod eip4844_tests;
+
+#[cfg(test)]
+#[path = "tests/ibe_tests.rs"]
+pub mod ibe_tests;
+
+#[cfg(test)]
+#[path = "tests/kzg_tests.rs"]
+pub mod kzg_tests;
+
 #[cfg(test)]
 #[path = "tests/nodes_tests.rs"]
 pub mod nodes_tests;
 
+#[cfg(test)]
+#[path = "tests/pedersen_tests.rs"]
+pub mod pedersen_tests;
+
+#[cfg(test)]
+#[path = "tests/sigma_tests.rs"]
+pub mod sigma_tests;
+
 // TODO: needs to use ecies_v1
 // #[cfg(test)]
 // #[path = "tests/nidkg_tests.rs"]
@@ -0,0 +1,400 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pedersen and vector Pedersen commitments over any group, with NIZK proofs of knowledge of an
+//! opening, of equality of committed values and of linear relations between committed values.
+//!
+//! A commitment to values v_1, ..., v_k with blinding factor r is v_1 G_1 + ... + v_k G_k + r H
+//! where the generators are derived by hashing to the group, so no one knows the discrete
+//! logarithm of any of them with respect to the others.
+
+use crate::random_oracle::RandomOracle;
+use fastcrypto::error::{FastCryptoError, FastCryptoResult};
+use fastcrypto::groups::{FiatShamirChallenge, GroupElement, HashToGroupElement, Scalar};
+use fastcrypto::traits::AllowedRng;
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Mul, Sub};
+
+/// Generators G_1, ..., G_n for the values and H for the blinding factor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    try_from = "PedersenGeneratorsData<G>",
+    bound(deserialize = "G: GroupElement + Deserialize<'de>")
+)]
+pub struct PedersenGenerators<G> {
+    value_generators: Vec<G>,
+    blinding_generator: G,
+}
+
+/// The serialized form of [PedersenGenerators] which is validated using [PedersenGenerators::new]
+/// when deserialized.
+#[derive(Deserialize)]
+struct PedersenGeneratorsData<G> {
+    value_generators: Vec<G>,
+    blinding_generator: G,
+}
+
+impl<G: GroupElement> TryFrom<PedersenGeneratorsData<G>> for PedersenGenerators<G> {
+    type Error = FastCryptoError;
+
+    fn try_from(data: PedersenGeneratorsData<G>) -> FastCryptoResult<Self> {
+        Self::new(data.value_generators, data.blinding_generator)
+    }
+}
+
+/// A Pedersen commitment. Commitments using the same generators can be added and subtracted, and
+/// multiplied by scalars, which gives a commitment to the sum, difference or product of the
+/// values with the same operation applied to the blinding factors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PedersenCommitment<G>(pub G);
+
+impl<G: GroupElement + HashToGroupElement> PedersenGenerators<G> {
+    /// Derive generators for commitments to up to `n` values by hashing the domain separation tag
+    /// to the group. Different tags give independent generators.
+    pub fn from_domain(domain: &[u8], n: usize) -> FastCryptoResult<Self> {
+        let hash = |input: &(&[u8], &str, u64)| {
+            G::hash_to_group_element(&bcs::to_bytes(input).expect("serialize should never fail"))
+        };
+        Self::new(
+            (0..n as u64).map(|i| hash(&(domain, "value", i))).collect(),
+            hash(&(domain, "blinding", 0)),
+        )
+    }
+}
+
+impl<G: GroupElement> PedersenGenerators<G> {
+    /// Create generators from the given group elements. There must be at least one value
+    /// generator, and the caller must make sure that no one knows the discrete logarithm of any of
+    /// the generators with respect to the others.
+    pub fn new(value_generators: Vec<G>, blinding_generator: G) -> FastCryptoResult<Self> {
+        if value_generators.is_empty() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(Self {
+            value_generators,
+            blinding_generator,
+        })
+    }
+
+    /// The maximal number of values that can be committed to.
+    pub fn max_values(&self) -> usize {
+        self.value_generators.len()
+    }
+
+    /// The generators G_1, ..., G_n for the values.
+    pub fn value_generators(&self) -> &[G] {
+        &self.value_generators
+    }
+
+    /// The generator H for the blinding factor.
+    pub fn blinding_generator(&self) -> &G {
+        &self.blinding_generator
+    }
+
+    /// Commit to a single value using the first value generator.
+    pub fn commit(&self, value: &G::ScalarType, blinding: &G::ScalarType) -> PedersenCommitment<G> {
+        PedersenCommitment(self.value_generators[0] * value + self.blinding_generator * blinding)
+    }
+
+    /// Commit to a vector of at most [PedersenGenerators::max_values] values.
+    pub fn commit_vector(
+        &self,
+        values: &[G::ScalarType],
+        blinding: &G::ScalarType,
+    ) -> FastCryptoResult<PedersenCommitment<G>> {
+        Ok(PedersenCommitment(
+            self.linear_combination(values)? + self.blinding_generator * blinding,
+        ))
+    }
+
+    /// Check that the commitment is to the given values with the given blinding factor.
+    pub fn verify_opening(
+        &self,
+        commitment: &PedersenCommitment<G>,
+        values: &[G::ScalarType],
+        blinding: &G::ScalarType,
+    ) -> FastCryptoResult<()> {
+        match self.commit_vector(values, blinding)? == *commitment {
+            true => Ok(()),
+            false => Err(FastCryptoError::InvalidInput),
+        }
+    }
+
+    /// Compute sum v_i G_i.
+    fn linear_combination(&self, values: &[G::ScalarType]) -> FastCryptoResult<G> {
+        if values.len() > self.value_generators.len() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(values
+            .iter()
+            .zip(self.value_generators.iter())
+            .fold(G::zero(), |acc, (v, g)| acc + *g * v))
+    }
+}
+
+impl<G: GroupElement> Add for PedersenCommitment<G> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl<G: GroupElement> Sub for PedersenCommitment<G> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self(self.0 - rhs.0)
+    }
+}
+
+impl<G: GroupElement> Mul<G::ScalarType> for PedersenCommitment<G> {
+    type Output = Self;
+
+    fn mul(self, rhs: G::ScalarType) -> Self {
+        Self(self.0 * rhs)
+    }
+}
+
+/// NIZKPoK of an opening (v_1, ..., v_k, r) of a commitment C.
+/// - Prover selects random s_1, ..., s_k, t and sends A = s_1 G_1 + ... + s_k G_k + t H.
+/// - Prover computes challenge c and sends z_i = s_i + c v_i and z = t + c r.
+/// - Verifier checks that z_1 G_1 + ... + z_k G_k + z H = A + c C.
+///
+/// The NIZK is (A, [z_i], z) where c is implicitly computed using a random oracle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpeningNizk<G: GroupElement>(G, Vec<G::ScalarType>, G::ScalarType);
+
+impl<G> OpeningNizk<G>
+where
+    G: GroupElement + Serialize,
+    G::ScalarType: FiatShamirChallenge,
+{
+    /// Create a new NIZKPoK of the opening of the commitment using the given RNG and random
+    /// oracle.
+    pub fn create<R: AllowedRng>(
+        generators: &PedersenGenerators<G>,
+        commitment: &PedersenCommitment<G>,
+        values: &[G::ScalarType],
+        blinding: &G::ScalarType,
+        random_oracle: &RandomOracle,
+        rng: &mut R,
+    ) -> FastCryptoResult<Self> {
+        let s = (0..values.len())
+            .map(|_| G::ScalarType::rand(rng))
+            .collect::<Vec<_>>();
+        let t = G::ScalarType::rand(rng);
+        let a = generators.commit_vector(&s, &t)?.0;
+        let challenge = Self::fiat_shamir_challenge(generators, commitment, &a, random_oracle);
+        let z_values = s
+            .iter()
+            .zip(values)
+            .map(|(s_i, v_i)| *s_i + challenge * v_i)
+            .collect();
+        Ok(Self(a, z_values, t + challenge * blinding))
+    }
+
+    /// Verify this NIZKPoK.
+    pub fn verify(
+        &self,
+        generators: &PedersenGenerators<G>,
+        commitment: &PedersenCommitment<G>,
+        random_oracle: &RandomOracle,
+    ) -> FastCryptoResult<()> {
+        let challenge = Self::fiat_shamir_challenge(generators, commitment, &self.0, random_oracle);
+        match generators.commit_vector(&self.1, &self.2)?.0 == self.0 + commitment.0 * challenge {
+            true => Ok(()),
+            false => Err(FastCryptoError::InvalidProof),
+        }
+    }
+
+    /// Returns the challenge for Fiat-Shamir.
+    fn fiat_shamir_challenge(
+        generators: &PedersenGenerators<G>,
+        commitment: &PedersenCommitment<G>,
+        a: &G,
+        random_oracle: &RandomOracle,
+    ) -> G::ScalarType {
+        let output = random_oracle.evaluate(&(generators, commitment, a));
+        G::ScalarType::fiat_shamir_reduction_to_group_element(&output)
+    }
+}
+
+/// NIZKPoK that two commitments C_1 = v G_1 + r_1 H_1 and C_2 = v G_2 + r_2 H_2, possibly using
+/// different generators, are to the same value v.
+/// - Prover selects random s, t_1, t_2 and sends A_1 = s G_1 + t_1 H_1 and A_2 = s G_2 + t_2 H_2.
+/// - Prover computes challenge c and sends z = s + c v, z_1 = t_1 + c r_1 and z_2 = t_2 + c r_2.
+/// - Verifier checks that z G_1 + z_1 H_1 = A_1 + c C_1 and z G_2 + z_2 H_2 = A_2 + c C_2.
+///
+/// The NIZK is (A_1, A_2, z, z_1, z_2) where c is implicitly computed using a random oracle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EqualityNizk<G: GroupElement>(G, G, G::ScalarType, G::ScalarType, G::ScalarType);
+
+impl<G> EqualityNizk<G>
+where
+    G: GroupElement + Serialize,
+    G::ScalarType: FiatShamirChallenge,
+{
+    /// Create a new NIZKPoK that the two commitments are to the same value using the given RNG and
+    /// random oracle. The commitments are to single values, see [PedersenGenerators::commit].
+    pub fn create<R: AllowedRng>(
+        generators: (&PedersenGenerators<G>, &PedersenGenerators<G>),
+        commitments: (&PedersenCommitment<G>, &PedersenCommitment<G>),
+        value: &G::ScalarType,
+        blindings: (&G::ScalarType, &G::ScalarType),
+        random_oracle: &RandomOracle,
+        rng: &mut R,
+    ) -> Self {
+        let s = G::ScalarType::rand(rng);
+        let t_1 = G::ScalarType::rand(rng);
+        let t_2 = G::ScalarType::rand(rng);
+        let a_1 = generators.0.commit(&s, &t_1).0;
+        let a_2 = generators.1.commit(&s, &t_2).0;
+        let challenge =
+            Self::fiat_shamir_challenge(generators, commitments, &a_1, &a_2, random_oracle);
+        Self(
+            a_1,
+            a_2,
+            s + challenge * value,
+            t_1 + challenge * blindings.0,
+            t_2 + challenge * blindings.1,
+        )
+    }
+
+    /// Verify this NIZKPoK.
+    pub fn verify(
+        &self,
+        generators: (&PedersenGenerators<G>, &PedersenGenerators<G>),
+        commitments: (&PedersenCommitment<G>, &PedersenCommitment<G>),
+        random_oracle: &RandomOracle,
+    ) -> FastCryptoResult<()> {
+        let challenge =
+            Self::fiat_shamir_challenge(generators, commitments, &self.0, &self.1, random_oracle);
+        if generators.0.commit(&self.2, &self.3).0 != self.0 + commitments.0 .0 * challenge
+            || generators.1.commit(&self.2, &self.4).0 != self.1 + commitments.1 .0 * challenge
+        {
+            return Err(FastCryptoError::InvalidProof);
+        }
+        Ok(())
+    }
+
+    /// Returns the challenge for Fiat-Shamir.
+    fn fiat_shamir_challenge(
+        generators: (&PedersenGenerators<G>, &PedersenGenerators<G>),
+        commitments: (&PedersenCommitment<G>, &PedersenCommitment<G>),
+        a_1: &G,
+        a_2: &G,
+        random_oracle: &RandomOracle,
+    ) -> G::ScalarType {
+        let output = random_oracle.evaluate(&(generators, commitments, a_1, a_2));
+        G::ScalarType::fiat_shamir_reduction_to_group_element(&output)
+    }
+}
+
+/// NIZKPoK that the values v_1, ..., v_k of commitments C_j = v_j G + r_j H satisfy the linear
+/// relation a_1 v_1 + ... + a_k v_k = b for public coefficients a_j and b.
+/// - Both parties compute D = a_1 C_1 + ... + a_k C_k - b G which is equal to r H for
+///   r = a_1 r_1 + ... + a_k r_k if the relation holds.
+/// - Prover selects a random s and sends A = s H.
+/// - Prover computes challenge c and sends z = s + c r.
+/// - Verifier checks that z H = A + c D.
+///
+/// The NIZK is (A, z) where c is implicitly computed using a random oracle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinearRelationNizk<G: GroupElement>(G, G::ScalarType);
+
+impl<G> LinearRelationNizk<G>
+where
+    G: GroupElement + Serialize,
+    G::ScalarType: FiatShamirChallenge + Serialize,
+{
+    /// Create a new NIZKPoK that the committed values satisfy the linear relation given by the
+    /// coefficients and the constant using the given RNG and random oracle. The commitments are to
+    /// single values, see [PedersenGenerators::commit], and the blinding factors are those used
+    /// for the commitments.
+    pub fn create<R: AllowedRng>(
+        generators: &PedersenGenerators<G>,
+        commitments: &[PedersenCommitment<G>],
+        coefficients: &[G::ScalarType],
+        constant: &G::ScalarType,
+        blindings: &[G::ScalarType],
+        random_oracle: &RandomOracle,
+        rng: &mut R,
+    ) -> FastCryptoResult<Self> {
+        if commitments.is_empty()
+            || commitments.len() != coefficients.len()
+            || commitments.len() != blindings.len()
+        {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let r = coefficients
+            .iter()
+            .zip(blindings)
+            .fold(G::ScalarType::zero(), |acc, (a, r)| acc + *a * r);
+        let s = G::ScalarType::rand(rng);
+        let a = generators.blinding_generator * s;
+        let challenge = Self::fiat_shamir_challenge(
+            generators,
+            commitments,
+            coefficients,
+            constant,
+            &a,
+            random_oracle,
+        );
+        Ok(Self(a, s + challenge * r))
+    }
+
+    /// Verify this NIZKPoK.
+    pub fn verify(
+        &self,
+        generators: &PedersenGenerators<G>,
+        commitments: &[PedersenCommitment<G>],
+        coefficients: &[G::ScalarType],
+        constant: &G::ScalarType,
+        random_oracle: &RandomOracle,
+    ) -> FastCryptoResult<()> {
+        let d = Self::combined_commitment(generators, commitments, coefficients, constant)?;
+        let challenge = Self::fiat_shamir_challenge(
+            generators,
+            commitments,
+            coefficients,
+            constant,
+            &self.0,
+            random_oracle,
+        );
+        match generators.blinding_generator * self.1 == self.0 + d * challenge {
+            true => Ok(()),
+            false => Err(FastCryptoError::InvalidProof),
+        }
+    }
+
+    /// Compute a_1 C_1 + ... + a_k C_k - b G.
+    fn combined_commitment(
+        generators: &PedersenGenerators<G>,
+        commitments: &[PedersenCommitment<G>],
+        coefficients: &[G::ScalarType],
+        constant: &G::ScalarType,
+    ) -> FastCryptoResult<G> {
+        if commitments.is_empty() || commitments.len() != coefficients.len() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(commitments
+            .iter()
+            .zip(coefficients)
+            .fold(G::zero(), |acc, (c, a)| acc + c.0 * a)
+            - generators.value_generators[0] * constant)
+    }
+
+    /// Returns the challenge for Fiat-Shamir.
+    fn fiat_shamir_challenge(
+        generators: &PedersenGenerators<G>,
+        commitments: &[PedersenCommitment<G>],
+        coefficients: &[G::ScalarType],
+        constant: &G::ScalarType,
+        a: &G,
+        random_oracle: &RandomOracle,
+    ) -> G::ScalarType {
+        let output = random_oracle.evaluate(&(generators, commitments, coefficients, constant, a));
+        G::ScalarType::fiat_shamir_reduction_to_group_element(&output)
+    }
+}
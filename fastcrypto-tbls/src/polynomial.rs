@@ -117,7 +117,7 @@ impl<C: GroupElement> Poly<C> {
             acc * C::ScalarType::from(*i)
         });
 
-        let mut coeffs = Vec::new();
+        let mut denominators = Vec::with_capacity(indices.len());
         for i in &indices {
             let mut negative = false;
             let (mut denominator, remaining) = indices.iter().filter(|j| *j != i).fold(
@@ -145,10 +145,15 @@ impl<C: GroupElement> Poly<C> {
             if negative {
                 denominator = -denominator;
             }
-            // TODO: Consider returning full_numerator and dividing once outside instead of here per iteration.
-            let coeff = full_numerator / denominator;
-            coeffs.push(coeff.expect("safe since i != j"));
+            denominators.push(denominator);
         }
+
+        // Invert all denominators at once.
+        let coeffs = C::ScalarType::batch_inverse(&denominators)
+            .expect("safe since i != j")
+            .into_iter()
+            .map(|inverse| full_numerator * inverse)
+            .collect();
         Ok(coeffs)
     }
 
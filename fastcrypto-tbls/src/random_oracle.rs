@@ -1,6 +1,7 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::transcript::Transcript;
 use digest::Digest;
 use serde::{Deserialize, Serialize};
 use sha3::Sha3_512;
@@ -59,4 +60,11 @@ impl RandomOracle {
             prefix: self.prefix.clone() + "-" + extension,
         }
     }
+
+    /// Create a transcript with the prefix of this random oracle as domain. Its outputs are
+    /// independent of the outputs of this random oracle, so protocols can migrate to transcripts
+    /// without reusing challenges.
+    pub fn to_transcript(&self) -> Transcript {
+        Transcript::new(&self.prefix)
+    }
 }
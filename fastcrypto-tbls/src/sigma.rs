@@ -0,0 +1,422 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! A framework for Schnorr-style NIZK proofs of knowledge of a preimage of a group homomorphism,
+//! see Maurer, "Unifying Zero-Knowledge Proofs of Knowledge" (AFRICACRYPT 2009).
+//!
+//! A homomorphism from vectors of scalars to vectors of group elements is given by a [LinearMap],
+//! where each output is a linear combination of the inputs with fixed bases. For example, with
+//! witness x:
+//! - Knowledge of a discrete logarithm X = xG is the map x -> xG.
+//! - Equality of discrete logarithms X = xG, Y = xH is the map x -> (xG, xH).
+//! - Knowledge of an opening C = vG + rH of a Pedersen commitment is the map (v, r) -> vG + rH.
+//!
+//! Statements can be combined with AND composition using [Statement::and], and proven with a
+//! [SigmaProof] which can be verified in batches. Knowledge of a preimage for one out of a number
+//! of statements is proven with an [OrProof].
+
+use crate::dl_verification::get_random_scalars;
+use crate::random_oracle::RandomOracle;
+use fastcrypto::error::{FastCryptoError, FastCryptoResult};
+use fastcrypto::groups::{FiatShamirChallenge, GroupElement, MultiScalarMul, Scalar};
+use fastcrypto::traits::AllowedRng;
+use serde::{Deserialize, Serialize};
+
+/// A group homomorphism from n scalars x_1, ..., x_n to m group elements where the i'th output
+/// is sum_j x_j B_{i,j}. Each row is given by the non-zero terms as pairs (j, B_{i,j}).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    try_from = "LinearMapData<G>",
+    bound(deserialize = "G: GroupElement + Deserialize<'de>")
+)]
+pub struct LinearMap<G> {
+    preimage_length: usize,
+    rows: Vec<Vec<(usize, G)>>,
+}
+
+/// A statement that the prover knows a preimage of the image under the map.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(
+    try_from = "StatementData<G>",
+    bound(deserialize = "G: GroupElement + Deserialize<'de>")
+)]
+pub struct Statement<G> {
+    map: LinearMap<G>,
+    image: Vec<G>,
+}
+
+/// The serialized form of a [LinearMap] which is validated using [LinearMap::new] when deserialized.
+#[derive(Deserialize)]
+struct LinearMapData<G> {
+    preimage_length: usize,
+    rows: Vec<Vec<(usize, G)>>,
+}
+
+impl<G: GroupElement> TryFrom<LinearMapData<G>> for LinearMap<G> {
+    type Error = FastCryptoError;
+
+    fn try_from(data: LinearMapData<G>) -> FastCryptoResult<Self> {
+        Self::new(data.preimage_length, data.rows)
+    }
+}
+
+/// The serialized form of a [Statement] which is validated using [Statement::new] when deserialized.
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "G: GroupElement + Deserialize<'de>"))]
+struct StatementData<G> {
+    map: LinearMap<G>,
+    image: Vec<G>,
+}
+
+impl<G: GroupElement> TryFrom<StatementData<G>> for Statement<G> {
+    type Error = FastCryptoError;
+
+    fn try_from(data: StatementData<G>) -> FastCryptoResult<Self> {
+        Self::new(data.map, data.image)
+    }
+}
+
+impl<G: GroupElement> LinearMap<G> {
+    /// Create a new linear map from preimages of the given length. Each row must be non-empty and
+    /// only refer to indices smaller than the preimage length.
+    pub fn new(preimage_length: usize, rows: Vec<Vec<(usize, G)>>) -> FastCryptoResult<Self> {
+        if rows.is_empty()
+            || rows
+                .iter()
+                .any(|row| row.is_empty() || row.iter().any(|(j, _)| *j >= preimage_length))
+        {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(Self {
+            preimage_length,
+            rows,
+        })
+    }
+
+    /// The number of scalars in a preimage.
+    pub fn preimage_length(&self) -> usize {
+        self.preimage_length
+    }
+
+    /// The number of group elements in an image.
+    pub fn image_length(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Evaluate the map on the given preimage.
+    pub fn apply(&self, preimage: &[G::ScalarType]) -> FastCryptoResult<Vec<G>> {
+        if preimage.len() != self.preimage_length {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(self
+            .rows
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .fold(G::zero(), |acc, (j, base)| acc + *base * preimage[*j])
+            })
+            .collect())
+    }
+}
+
+impl<G: GroupElement> Statement<G> {
+    /// Create a new statement. The image must have the same length as the output of the map.
+    pub fn new(map: LinearMap<G>, image: Vec<G>) -> FastCryptoResult<Self> {
+        if image.len() != map.image_length() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(Self { map, image })
+    }
+
+    /// The map of this statement.
+    pub fn map(&self) -> &LinearMap<G> {
+        &self.map
+    }
+
+    /// The image for which the prover knows a preimage under the map.
+    pub fn image(&self) -> &[G] {
+        &self.image
+    }
+
+    /// The AND composition of the given statements, which is the statement for the direct sum of
+    /// the maps. A witness for the composed statement is the concatenation of the witnesses.
+    pub fn and(statements: &[Statement<G>]) -> FastCryptoResult<Self> {
+        if statements.is_empty() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let mut offset = 0;
+        let mut rows = Vec::new();
+        let mut image = Vec::new();
+        for statement in statements {
+            rows.extend(statement.map.rows.iter().map(|row| {
+                row.iter()
+                    .map(|(j, base)| (j + offset, *base))
+                    .collect::<Vec<_>>()
+            }));
+            image.extend_from_slice(&statement.image);
+            offset += statement.map.preimage_length;
+        }
+        Self::new(LinearMap::new(offset, rows)?, image)
+    }
+
+    /// Check if the witness is a preimage of the image.
+    pub fn is_satisfied_by(&self, witness: &[G::ScalarType]) -> bool {
+        self.map
+            .apply(witness)
+            .is_ok_and(|image| image == self.image)
+    }
+}
+
+/// NIZKPoK of a preimage x of Y under the map φ.
+/// - Prover selects random k and sends T = φ(k).
+/// - Prover computes challenge c and sends s = k + c x.
+/// - Verifier checks that φ(s) = T + c Y.
+///
+/// The NIZK is (T, s) where c is implicitly computed using a random oracle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SigmaProof<G: GroupElement> {
+    commitment: Vec<G>,
+    response: Vec<G::ScalarType>,
+}
+
+impl<G> SigmaProof<G>
+where
+    G: GroupElement + Serialize,
+    G::ScalarType: FiatShamirChallenge,
+{
+    /// Create a new NIZKPoK of the witness for the statement using the given RNG and random
+    /// oracle. Fails if the witness does not satisfy the statement.
+    pub fn create<R: AllowedRng>(
+        statement: &Statement<G>,
+        witness: &[G::ScalarType],
+        random_oracle: &RandomOracle,
+        rng: &mut R,
+    ) -> FastCryptoResult<Self> {
+        if !statement.is_satisfied_by(witness) {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let k = get_random_scalars::<G::ScalarType, R>(witness.len(), rng);
+        let commitment = statement.map.apply(&k)?;
+        let challenge = Self::fiat_shamir_challenge(statement, &commitment, random_oracle);
+        Ok(Self {
+            commitment,
+            response: respond(&k, witness, &challenge),
+        })
+    }
+
+    /// Verify this NIZKPoK.
+    pub fn verify(
+        &self,
+        statement: &Statement<G>,
+        random_oracle: &RandomOracle,
+    ) -> FastCryptoResult<()> {
+        let challenge = Self::fiat_shamir_challenge(statement, &self.commitment, random_oracle);
+        verify_response(statement, &self.commitment, &challenge, &self.response)
+    }
+
+    /// Verify a batch of proofs for the given statements using a random linear combination of the
+    /// verification equations. This is faster than verifying the proofs one by one.
+    pub fn batch_verify<R: AllowedRng>(
+        proofs: &[(&Statement<G>, &Self)],
+        random_oracle: &RandomOracle,
+        rng: &mut R,
+    ) -> FastCryptoResult<()>
+    where
+        G: MultiScalarMul,
+    {
+        let mut scalars = Vec::new();
+        let mut points = Vec::new();
+        for (statement, proof) in proofs {
+            if proof.commitment.len() != statement.map.image_length()
+                || proof.response.len() != statement.map.preimage_length()
+            {
+                return Err(FastCryptoError::InvalidProof);
+            }
+            let challenge =
+                Self::fiat_shamir_challenge(statement, &proof.commitment, random_oracle);
+
+            // With a random weight r for each row, add r (φ(s)_i - T_i - c Y_i).
+            let weights = get_random_scalars::<G::ScalarType, R>(statement.image.len(), rng);
+            for (((row, t), y), r) in statement
+                .map
+                .rows
+                .iter()
+                .zip(&proof.commitment)
+                .zip(&statement.image)
+                .zip(weights)
+            {
+                for (j, base) in row {
+                    scalars.push(r * proof.response[*j]);
+                    points.push(*base);
+                }
+                scalars.push(-r);
+                points.push(*t);
+                scalars.push(-(r * challenge));
+                points.push(*y);
+            }
+        }
+        if scalars.is_empty() {
+            return Ok(());
+        }
+        match G::multi_scalar_mul(&scalars, &points)? == G::zero() {
+            true => Ok(()),
+            false => Err(FastCryptoError::InvalidProof),
+        }
+    }
+
+    /// Returns the challenge for Fiat-Shamir.
+    fn fiat_shamir_challenge(
+        statement: &Statement<G>,
+        commitment: &[G],
+        random_oracle: &RandomOracle,
+    ) -> G::ScalarType {
+        let output = random_oracle.evaluate(&(statement, commitment));
+        G::ScalarType::fiat_shamir_reduction_to_group_element(&output)
+    }
+}
+
+/// NIZKPoK of a preimage for one of the statements φ_1(x) = Y_1, ..., φ_n(x) = Y_n without revealing
+/// which, see Cramer, Damgård and Schoenmakers, "Proofs of Partial Knowledge and Simplified Design
+/// of Witness Hiding Protocols" (CRYPTO 1994).
+/// - For all statements j except the one with the witness, the prover selects random c_j, s_j and
+///   computes T_j = φ_j(s_j) - c_j Y_j.
+/// - For the statement i with the witness, the prover selects random k and computes T_i = φ_i(k).
+/// - Prover computes challenge c from all T_j, sets c_i = c - sum_{j != i} c_j and s_i = k + c_i x,
+///   and sends all T_j, c_j and s_j.
+/// - Verifier checks that c = sum_j c_j and that φ_j(s_j) = T_j + c_j Y_j for all j.
+///
+/// The NIZK is ([T_j], [c_j], [s_j]) where c is implicitly computed using a random oracle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrProof<G: GroupElement> {
+    commitments: Vec<Vec<G>>,
+    challenges: Vec<G::ScalarType>,
+    responses: Vec<Vec<G::ScalarType>>,
+}
+
+impl<G> OrProof<G>
+where
+    G: GroupElement + Serialize,
+    G::ScalarType: FiatShamirChallenge,
+{
+    /// Create a new NIZKPoK that the prover knows a witness for one of the statements, here the
+    /// statement with the given index, using the given RNG and random oracle. Fails if the witness
+    /// does not satisfy the statement.
+    pub fn create<R: AllowedRng>(
+        statements: &[Statement<G>],
+        index: usize,
+        witness: &[G::ScalarType],
+        random_oracle: &RandomOracle,
+        rng: &mut R,
+    ) -> FastCryptoResult<Self> {
+        if index >= statements.len() || !statements[index].is_satisfied_by(witness) {
+            return Err(FastCryptoError::InvalidInput);
+        }
+
+        // Simulate proofs for all other statements.
+        let mut commitments = Vec::with_capacity(statements.len());
+        let mut challenges = Vec::with_capacity(statements.len());
+        let mut responses = Vec::with_capacity(statements.len());
+        let k = get_random_scalars::<G::ScalarType, R>(witness.len(), rng);
+        for (j, statement) in statements.iter().enumerate() {
+            if j == index {
+                commitments.push(statement.map.apply(&k)?);
+                challenges.push(G::ScalarType::zero());
+                responses.push(vec![]);
+                continue;
+            }
+            let c = G::ScalarType::rand(rng);
+            let s = get_random_scalars::<G::ScalarType, R>(statement.map.preimage_length(), rng);
+            let commitment = statement
+                .map
+                .apply(&s)?
+                .iter()
+                .zip(&statement.image)
+                .map(|(phi_s, y)| *phi_s - *y * c)
+                .collect();
+            commitments.push(commitment);
+            challenges.push(c);
+            responses.push(s);
+        }
+
+        let challenge = Self::fiat_shamir_challenge(statements, &commitments, random_oracle);
+        let c_i = challenges.iter().fold(challenge, |acc, c_j| acc - *c_j);
+        challenges[index] = c_i;
+        responses[index] = respond(&k, witness, &c_i);
+        Ok(Self {
+            commitments,
+            challenges,
+            responses,
+        })
+    }
+
+    /// Verify this NIZKPoK.
+    pub fn verify(
+        &self,
+        statements: &[Statement<G>],
+        random_oracle: &RandomOracle,
+    ) -> FastCryptoResult<()> {
+        if statements.is_empty()
+            || self.commitments.len() != statements.len()
+            || self.challenges.len() != statements.len()
+            || self.responses.len() != statements.len()
+        {
+            return Err(FastCryptoError::InvalidProof);
+        }
+        let challenge = Self::fiat_shamir_challenge(statements, &self.commitments, random_oracle);
+        let sum = self
+            .challenges
+            .iter()
+            .fold(G::ScalarType::zero(), |acc, c_j| acc + *c_j);
+        if sum != challenge {
+            return Err(FastCryptoError::InvalidProof);
+        }
+        statements
+            .iter()
+            .zip(&self.commitments)
+            .zip(&self.challenges)
+            .zip(&self.responses)
+            .try_for_each(|(((statement, t), c), s)| verify_response(statement, t, c, s))
+    }
+
+    /// Returns the challenge for Fiat-Shamir.
+    fn fiat_shamir_challenge(
+        statements: &[Statement<G>],
+        commitments: &[Vec<G>],
+        random_oracle: &RandomOracle,
+    ) -> G::ScalarType {
+        let output = random_oracle.evaluate(&(statements, commitments));
+        G::ScalarType::fiat_shamir_reduction_to_group_element(&output)
+    }
+}
+
+/// Compute the response s = k + c x.
+fn respond<S: Scalar>(k: &[S], witness: &[S], challenge: &S) -> Vec<S> {
+    k.iter()
+        .zip(witness)
+        .map(|(k_j, x_j)| *k_j + *challenge * x_j)
+        .collect()
+}
+
+/// Checks that φ(s) = T + c Y.
+fn verify_response<G: GroupElement>(
+    statement: &Statement<G>,
+    commitment: &[G],
+    challenge: &G::ScalarType,
+    response: &[G::ScalarType],
+) -> FastCryptoResult<()> {
+    if commitment.len() != statement.image.len() {
+        return Err(FastCryptoError::InvalidProof);
+    }
+    let lhs = statement
+        .map
+        .apply(response)
+        .map_err(|_| FastCryptoError::InvalidProof)?;
+    let rhs = commitment
+        .iter()
+        .zip(&statement.image)
+        .map(|(t, y)| *t + *y * challenge);
+    match lhs.into_iter().eq(rhs) {
+        true => Ok(()),
+        false => Err(FastCryptoError::InvalidProof),
+    }
+}
@@ -6,6 +6,9 @@ use crate::ecies_v1::{PrivateKey, PublicKey};
 use crate::random_oracle::RandomOracle;
 use fastcrypto::bls12381::min_sig::BLS12381KeyPair;
 use fastcrypto::groups::bls12381::{G1Element, G2Element, Scalar, SCALAR_LENGTH};
+use fastcrypto::groups::bn254::G1Element as Bn254G1Element;
+use fastcrypto::groups::secp256k1::ProjectivePoint as Secp256k1Point;
+use fastcrypto::groups::secp256r1::ProjectivePoint;
 use fastcrypto::groups::{FiatShamirChallenge, GroupElement};
 use fastcrypto::traits::KeyPair;
 use rand::prelude::StdRng;
@@ -104,6 +107,15 @@ mod point_tests {
 
     #[instantiate_tests(<G2Element>)]
     mod g2_element {}
+
+    #[instantiate_tests(<ProjectivePoint>)]
+    mod secp256r1_point {}
+
+    #[instantiate_tests(<Secp256k1Point>)]
+    mod secp256k1_point {}
+
+    #[instantiate_tests(<Bn254G1Element>)]
+    mod bn254_g1_element {}
 }
 
 #[test]
@@ -0,0 +1,409 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::eip4844::*;
+use crate::kzg::EvaluationDomain;
+use fastcrypto::encoding::{Encoding, Hex};
+use fastcrypto::error::{FastCryptoError, FastCryptoResult};
+use fastcrypto::groups::bls12381::{G1Element, G2Element, Scalar};
+use fastcrypto::groups::{GroupElement, Scalar as ScalarTrait};
+use fastcrypto::hash::{HashFunction, Sha256};
+use fastcrypto::serde_helpers::ToFromByteArray;
+use rand::thread_rng;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// An insecure setup with a known tau given as the Lagrange basis in G1 in natural order and the
+/// powers of tau in G2.
+fn insecure_setup_points() -> &'static (Vec<G1Element>, Vec<G2Element>) {
+    static POINTS: OnceLock<(Vec<G1Element>, Vec<G2Element>)> = OnceLock::new();
+    POINTS.get_or_init(|| {
+        let tau = Scalar::rand(&mut thread_rng());
+        let domain = EvaluationDomain::new(FIELD_ELEMENTS_PER_BLOB).unwrap();
+
+        // L_i(τ) = (τ^n - 1) / n * ω^i / (τ - ω^i).
+        let mut tau_to_n = tau;
+        for _ in 0..FIELD_ELEMENTS_PER_BLOB.trailing_zeros() {
+            tau_to_n = tau_to_n * tau_to_n;
+        }
+        let factor = (tau_to_n - Scalar::generator())
+            * Scalar::from(FIELD_ELEMENTS_PER_BLOB as u128)
+                .inverse()
+                .unwrap();
+        let g1_lagrange = domain
+            .elements()
+            .iter()
+            .map(|w| G1Element::generator() * (factor * w * (tau - w).inverse().unwrap()))
+            .collect();
+
+        let g2_monomial = std::iter::successors(Some(G2Element::generator()), |p| Some(*p * tau))
+            .take(KZG_SETUP_G2_LENGTH)
+            .collect();
+        (g1_lagrange, g2_monomial)
+    })
+}
+
+fn insecure_setup() -> TrustedSetup {
+    let (g1_lagrange, g2_monomial) = insecure_setup_points();
+    TrustedSetup::new(g1_lagrange.clone(), g2_monomial.clone()).unwrap()
+}
+
+fn random_polynomial() -> Vec<Scalar> {
+    (0..FIELD_ELEMENTS_PER_BLOB)
+        .map(|_| Scalar::rand(&mut thread_rng()))
+        .collect()
+}
+
+fn to_blob(polynomial: &[Scalar]) -> Vec<u8> {
+    polynomial.iter().flat_map(|s| s.to_byte_array()).collect()
+}
+
+fn random_blob() -> Vec<u8> {
+    to_blob(&random_polynomial())
+}
+
+#[test]
+fn test_from_text() {
+    let (g1_lagrange, g2_monomial) = insecure_setup_points();
+    let mut text = format!("{}\n{}\n", FIELD_ELEMENTS_PER_BLOB, KZG_SETUP_G2_LENGTH);
+    for p in g1_lagrange {
+        text += &format!("{}\n", Hex::encode(p.to_byte_array()));
+    }
+    for p in g2_monomial {
+        text += &format!("{}\n", Hex::encode(p.to_byte_array()));
+    }
+    assert_eq!(TrustedSetup::from_text(&text).unwrap(), insecure_setup());
+
+    // Trailing points in monomial form are ignored.
+    let with_monomial = text.clone() + &Hex::encode(G1Element::generator().to_byte_array());
+    assert_eq!(
+        TrustedSetup::from_text(&with_monomial).unwrap(),
+        insecure_setup()
+    );
+
+    // Wrong lengths or missing points.
+    assert!(TrustedSetup::from_text(&text.replacen("4096", "4095", 1)).is_err());
+    assert!(TrustedSetup::from_text(&text.replacen("\n65\n", "\n64\n", 1)).is_err());
+    let truncated = text.lines().take(100).collect::<Vec<_>>().join("\n");
+    assert!(TrustedSetup::from_text(&truncated).is_err());
+    assert!(TrustedSetup::from_text("").is_err());
+}
+
+#[test]
+fn test_compute_and_verify_kzg_proof() {
+    let setup = insecure_setup();
+    let polynomial = random_polynomial();
+    let blob = to_blob(&polynomial);
+    let commitment = setup.blob_to_kzg_commitment(&blob).unwrap();
+
+    // The blob holds the evaluations in bit-reversed order, so the value at a random point should
+    // match the interpolation of the evaluations in natural order.
+    let z = Scalar::rand(&mut thread_rng());
+    let (proof, y) = setup.compute_kzg_proof(&blob, &z).unwrap();
+    let domain = EvaluationDomain::new(FIELD_ELEMENTS_PER_BLOB).unwrap();
+    let natural_order = (0..FIELD_ELEMENTS_PER_BLOB)
+        .map(|i| {
+            polynomial[i.reverse_bits() >> (usize::BITS - FIELD_ELEMENTS_PER_BLOB.trailing_zeros())]
+        })
+        .collect::<Vec<_>>();
+    let expected = domain
+        .interpolate(&natural_order)
+        .unwrap()
+        .as_vec()
+        .iter()
+        .rev()
+        .fold(Scalar::zero(), |acc, c| acc * z + c);
+    assert_eq!(y, expected);
+    assert!(setup.verify_kzg_proof(&commitment, &z, &y, &proof).is_ok());
+
+    let one = Scalar::generator();
+    assert!(setup
+        .verify_kzg_proof(&commitment, &z, &(y + one), &proof)
+        .is_err());
+    assert!(setup
+        .verify_kzg_proof(&commitment, &(z + one), &y, &proof)
+        .is_err());
+
+    // Points in the domain.
+    for i in [0, 1, 17, FIELD_ELEMENTS_PER_BLOB - 1] {
+        let z = domain.elements()[i];
+        let (proof, y) = setup.compute_kzg_proof(&blob, &z).unwrap();
+        assert_eq!(y, natural_order[i]);
+        assert!(setup.verify_kzg_proof(&commitment, &z, &y, &proof).is_ok());
+    }
+}
+
+#[test]
+fn test_blob_kzg_proof() {
+    let setup = insecure_setup();
+    let blob = random_blob();
+    let commitment = setup.blob_to_kzg_commitment(&blob).unwrap();
+    let proof = setup.compute_blob_kzg_proof(&blob, &commitment).unwrap();
+    assert!(setup
+        .verify_blob_kzg_proof(&blob, &commitment, &proof)
+        .is_ok());
+
+    let other_blob = random_blob();
+    assert!(setup
+        .verify_blob_kzg_proof(&other_blob, &commitment, &proof)
+        .is_err());
+    assert!(setup
+        .verify_blob_kzg_proof(&blob, &commitment, &(proof + G1Element::generator()))
+        .is_err());
+
+    // The zero blob has the zero commitment and proof.
+    let zero_blob = vec![0u8; BYTES_PER_BLOB];
+    let commitment = setup.blob_to_kzg_commitment(&zero_blob).unwrap();
+    assert_eq!(commitment, G1Element::zero());
+    let proof = setup
+        .compute_blob_kzg_proof(&zero_blob, &commitment)
+        .unwrap();
+    assert_eq!(proof, G1Element::zero());
+    assert!(setup
+        .verify_blob_kzg_proof(&zero_blob, &commitment, &proof)
+        .is_ok());
+}
+
+#[test]
+fn test_verify_blob_kzg_proof_batch() {
+    let setup = insecure_setup();
+    let blobs = (0..3).map(|_| random_blob()).collect::<Vec<_>>();
+    let commitments = blobs
+        .iter()
+        .map(|b| setup.blob_to_kzg_commitment(b).unwrap())
+        .collect::<Vec<_>>();
+    let mut proofs = blobs
+        .iter()
+        .zip(commitments.iter())
+        .map(|(b, c)| setup.compute_blob_kzg_proof(b, c).unwrap())
+        .collect::<Vec<_>>();
+    assert!(setup
+        .verify_blob_kzg_proof_batch(&blobs, &commitments, &proofs)
+        .is_ok());
+    assert!(setup
+        .verify_blob_kzg_proof_batch::<Vec<u8>>(&[], &[], &[])
+        .is_ok());
+    assert!(setup
+        .verify_blob_kzg_proof_batch(&blobs[..2], &commitments, &proofs)
+        .is_err());
+
+    proofs.swap(0, 1);
+    assert!(setup
+        .verify_blob_kzg_proof_batch(&blobs, &commitments, &proofs)
+        .is_err());
+}
+
+#[test]
+fn test_invalid_blobs() {
+    let setup = insecure_setup();
+    assert!(setup
+        .blob_to_kzg_commitment(&vec![0u8; BYTES_PER_BLOB - 1])
+        .is_err());
+
+    // Scalars must be canonically encoded.
+    let mut blob = random_blob();
+    blob[BYTES_PER_FIELD_ELEMENT..2 * BYTES_PER_FIELD_ELEMENT].fill(0xff);
+    assert!(setup.blob_to_kzg_commitment(&blob).is_err());
+    assert!(setup
+        .compute_kzg_proof(&blob, &Scalar::generator())
+        .is_err());
+}
+
+#[test]
+fn test_versioned_hash() {
+    let commitment = G1Element::generator();
+    let hash = kzg_to_versioned_hash(&commitment);
+    let digest = Sha256::digest(commitment.to_byte_array()).digest;
+    assert_eq!(hash[0], VERSIONED_HASH_VERSION_KZG);
+    assert_eq!(hash[1..], digest[1..]);
+}
+
+/// Run the test vectors for the given handler, i.e. the files `<handler>/<case>.yaml` in
+/// `src/tests/eip4844_ckzg_vectors`. The expected output is `null` if the inputs are invalid.
+///
+/// These are not the official consensus-spec test vectors. They use the same YAML format but were
+/// generated with c-kzg 1.0.3 and the mainnet trusted setup, and include edge cases such as the
+/// point at infinity, non-canonical encodings, wrong lengths and mismatched batches.
+fn run_vectors<I: DeserializeOwned, O: DeserializeOwned + PartialEq + std::fmt::Debug>(
+    handler: &str,
+    run: impl Fn(&I) -> FastCryptoResult<O>,
+) {
+    #[derive(Deserialize)]
+    struct Vector<I, O> {
+        input: I,
+        output: Option<O>,
+    }
+
+    let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("src/tests/eip4844_ckzg_vectors")
+        .join(handler);
+    let mut cases = 0;
+    for case in std::fs::read_dir(directory).unwrap() {
+        let path = case.unwrap().path();
+        let vector: Vector<I, O> =
+            serde_yaml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(run(&vector.input).ok(), vector.output, "{}", path.display());
+        cases += 1;
+    }
+    assert!(cases > 0);
+}
+
+fn bytes(hex: &str) -> FastCryptoResult<Vec<u8>> {
+    Hex::decode(
+        hex.strip_prefix("0x")
+            .ok_or(FastCryptoError::InvalidInput)?,
+    )
+}
+
+fn scalar(hex: &str) -> FastCryptoResult<Scalar> {
+    Scalar::from_byte_array(
+        &bytes(hex)?
+            .try_into()
+            .map_err(|_| FastCryptoError::InvalidInput)?,
+    )
+}
+
+fn g1(hex: &str) -> FastCryptoResult<G1Element> {
+    G1Element::from_byte_array(
+        &bytes(hex)?
+            .try_into()
+            .map_err(|_| FastCryptoError::InvalidInput)?,
+    )
+}
+
+fn g1_hex(element: &G1Element) -> String {
+    format!("0x{}", Hex::encode(element.to_byte_array()))
+}
+
+/// Verification functions output false if the proof is invalid and null if the inputs are.
+fn verification_result(result: FastCryptoResult<()>) -> FastCryptoResult<bool> {
+    match result {
+        Ok(()) => Ok(true),
+        Err(FastCryptoError::InvalidProof) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+#[test]
+fn test_mainnet_trusted_setup() {
+    let setup = TrustedSetup::mainnet();
+    let blob = random_blob();
+    let commitment = setup.blob_to_kzg_commitment(&blob).unwrap();
+    let proof = setup.compute_blob_kzg_proof(&blob, &commitment).unwrap();
+    assert!(setup
+        .verify_blob_kzg_proof(&blob, &commitment, &proof)
+        .is_ok());
+}
+
+#[test]
+fn test_blob_to_kzg_commitment_vectors() {
+    #[derive(Deserialize)]
+    struct Input {
+        blob: String,
+    }
+    run_vectors("blob_to_kzg_commitment", |input: &Input| {
+        let commitment = TrustedSetup::mainnet().blob_to_kzg_commitment(&bytes(&input.blob)?)?;
+        Ok(g1_hex(&commitment))
+    });
+}
+
+#[test]
+fn test_compute_kzg_proof_vectors() {
+    #[derive(Deserialize)]
+    struct Input {
+        blob: String,
+        z: String,
+    }
+    run_vectors("compute_kzg_proof", |input: &Input| {
+        let (proof, y) =
+            TrustedSetup::mainnet().compute_kzg_proof(&bytes(&input.blob)?, &scalar(&input.z)?)?;
+        Ok((
+            g1_hex(&proof),
+            format!("0x{}", Hex::encode(y.to_byte_array())),
+        ))
+    });
+}
+
+#[test]
+fn test_compute_blob_kzg_proof_vectors() {
+    #[derive(Deserialize)]
+    struct Input {
+        blob: String,
+        commitment: String,
+    }
+    run_vectors("compute_blob_kzg_proof", |input: &Input| {
+        let proof = TrustedSetup::mainnet()
+            .compute_blob_kzg_proof(&bytes(&input.blob)?, &g1(&input.commitment)?)?;
+        Ok(g1_hex(&proof))
+    });
+}
+
+#[test]
+fn test_verify_kzg_proof_vectors() {
+    #[derive(Deserialize)]
+    struct Input {
+        commitment: String,
+        z: String,
+        y: String,
+        proof: String,
+    }
+    run_vectors("verify_kzg_proof", |input: &Input| {
+        verification_result(TrustedSetup::mainnet().verify_kzg_proof(
+            &g1(&input.commitment)?,
+            &scalar(&input.z)?,
+            &scalar(&input.y)?,
+            &g1(&input.proof)?,
+        ))
+    });
+}
+
+#[test]
+fn test_verify_blob_kzg_proof_vectors() {
+    #[derive(Deserialize)]
+    struct Input {
+        blob: String,
+        commitment: String,
+        proof: String,
+    }
+    run_vectors("verify_blob_kzg_proof", |input: &Input| {
+        verification_result(TrustedSetup::mainnet().verify_blob_kzg_proof(
+            &bytes(&input.blob)?,
+            &g1(&input.commitment)?,
+            &g1(&input.proof)?,
+        ))
+    });
+}
+
+#[test]
+fn test_verify_blob_kzg_proof_batch_vectors() {
+    #[derive(Deserialize)]
+    struct Input {
+        blobs: Vec<String>,
+        commitments: Vec<String>,
+        proofs: Vec<String>,
+    }
+    run_vectors("verify_blob_kzg_proof_batch", |input: &Input| {
+        let blobs = input
+            .blobs
+            .iter()
+            .map(|blob| bytes(blob))
+            .collect::<FastCryptoResult<Vec<_>>>()?;
+        let commitments = input
+            .commitments
+            .iter()
+            .map(|commitment| g1(commitment))
+            .collect::<FastCryptoResult<Vec<_>>>()?;
+        let proofs = input
+            .proofs
+            .iter()
+            .map(|proof| g1(proof))
+            .collect::<FastCryptoResult<Vec<_>>>()?;
+        verification_result(TrustedSetup::mainnet().verify_blob_kzg_proof_batch(
+            &blobs,
+            &commitments,
+            &proofs,
+        ))
+    });
+}
@@ -0,0 +1,144 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ibe::*;
+use ark_bls12_381::{Bls12_381, Fq, G1Affine, G2Affine};
+use ark_ec::pairing::Pairing;
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField};
+use fastcrypto::groups::bls12381::{G1Element, G2Element, GTElement, Scalar};
+use fastcrypto::groups::{GroupElement, Scalar as ScalarTrait};
+use fastcrypto::hash::{HashFunction, Sha256};
+use fastcrypto::serde_helpers::ToFromByteArray;
+use rand::thread_rng;
+
+fn keys<S: IbeScheme>() -> (Scalar, S::Public) {
+    let master_sk = Scalar::rand(&mut thread_rng());
+    (master_sk, S::Public::generator() * master_sk)
+}
+
+fn test_encrypt_and_decrypt<S: IbeScheme>() {
+    let (master_sk, master_pk) = keys::<S>();
+    let identity_key = S::extract(&master_sk, b"alice");
+    assert!(S::verify_identity_key(&master_pk, b"alice", &identity_key).is_ok());
+    assert!(S::verify_identity_key(&master_pk, b"bob", &identity_key).is_err());
+
+    for length in [MIN_MESSAGE_LENGTH, 17, MAX_MESSAGE_LENGTH] {
+        let message = vec![7u8; length];
+        let ciphertext =
+            Ciphertext::<S>::encrypt(&master_pk, b"alice", &message, &mut thread_rng()).unwrap();
+        assert_eq!(ciphertext.decrypt(&identity_key).unwrap(), message);
+        let bytes = ciphertext.to_drand_bytes();
+        let deserialized = Ciphertext::<S>::from_drand_bytes(&bytes).unwrap();
+        assert_eq!(deserialized.decrypt(&identity_key).unwrap(), message);
+    }
+
+    // Short messages could be recovered from U by brute force, so they are rejected.
+    for length in [0, 1, 4, MIN_MESSAGE_LENGTH - 1, MAX_MESSAGE_LENGTH + 1] {
+        assert!(Ciphertext::<S>::encrypt(
+            &master_pk,
+            b"alice",
+            &vec![0u8; length],
+            &mut thread_rng()
+        )
+        .is_err());
+    }
+
+    // Other identities cannot decrypt.
+    let ciphertext =
+        Ciphertext::<S>::encrypt(&master_pk, b"alice", &[7u8; 16], &mut thread_rng()).unwrap();
+    let other_key = S::extract(&master_sk, b"bob");
+    assert!(ciphertext.decrypt(&other_key).is_err());
+}
+
+fn test_hybrid_encrypt_and_decrypt<S: IbeScheme>() {
+    let (master_sk, master_pk) = keys::<S>();
+    let identity_key = S::extract(&master_sk, b"alice");
+    for length in [0, 1, 32, 33, 1000] {
+        let message = vec![7u8; length];
+        let ciphertext =
+            HybridCiphertext::<S>::encrypt(&master_pk, b"alice", &message, &mut thread_rng())
+                .unwrap();
+        assert_eq!(ciphertext.decrypt(&identity_key).unwrap(), message);
+    }
+
+    let ciphertext =
+        HybridCiphertext::<S>::encrypt(&master_pk, b"alice", b"message", &mut thread_rng())
+            .unwrap();
+    assert!(ciphertext.decrypt(&S::extract(&master_sk, b"bob")).is_err());
+    let (other_sk, _) = keys::<S>();
+    assert!(ciphertext
+        .decrypt(&S::extract(&other_sk, b"alice"))
+        .is_err());
+}
+
+fn test_tampering<S: IbeScheme>() {
+    let (master_sk, master_pk) = keys::<S>();
+    let identity_key = S::extract(&master_sk, b"alice");
+    let ciphertext =
+        HybridCiphertext::<S>::encrypt(&master_pk, b"alice", b"message", &mut thread_rng())
+            .unwrap();
+    let bytes = bcs::to_bytes(&ciphertext).unwrap();
+    let deserialized: HybridCiphertext<S> = bcs::from_bytes(&bytes).unwrap();
+    assert_eq!(bcs::to_bytes(&deserialized).unwrap(), bytes);
+    assert_eq!(deserialized.decrypt(&identity_key).unwrap(), b"message");
+
+    // Flipping any bit after the group element makes decryption fail.
+    let start = bcs::to_bytes(&S::Public::generator()).unwrap().len();
+    for i in start..bytes.len() {
+        let mut tampered = bytes.clone();
+        tampered[i] ^= 1;
+        if let Ok(tampered) = bcs::from_bytes::<HybridCiphertext<S>>(&tampered) {
+            assert!(tampered.decrypt(&identity_key).is_err());
+        }
+    }
+}
+
+#[test]
+fn test_encrypt_and_decrypt_min_sig() {
+    test_encrypt_and_decrypt::<Bls12381MinSig>();
+    test_hybrid_encrypt_and_decrypt::<Bls12381MinSig>();
+    test_tampering::<Bls12381MinSig>();
+}
+
+#[test]
+fn test_encrypt_and_decrypt_min_pk() {
+    test_encrypt_and_decrypt::<Bls12381MinPk>();
+    test_hybrid_encrypt_and_decrypt::<Bls12381MinPk>();
+    test_tampering::<Bls12381MinPk>();
+}
+
+#[test]
+fn test_drand_quicknet() {
+    // The public key from 'curl https://api.drand.sh/52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971/info'
+    // and the signature from '.../public/1234'.
+    let master_pk = G2Element::from_byte_array(&hex::decode("83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a").unwrap().try_into().unwrap()).unwrap();
+    let signature = G1Element::from_byte_array(&hex::decode("a81d4aad15461a0a02b43da857be1d782a2232a3c7bb370a2763e95ce1f2628460b24de2cee7453cd12e43c197ea2f23").unwrap().try_into().unwrap()).unwrap();
+    let identity = Sha256::digest(1234u64.to_be_bytes()).digest;
+
+    assert!(Bls12381MinSig::verify_identity_key(&master_pk, &identity, &signature).is_ok());
+    let ciphertext = HybridCiphertext::<Bls12381MinSig>::encrypt(
+        &master_pk,
+        &identity,
+        b"Hello, world!",
+        &mut thread_rng(),
+    )
+    .unwrap();
+    assert_eq!(ciphertext.decrypt(&signature).unwrap(), b"Hello, world!");
+}
+
+#[test]
+fn test_gt_drand_encoding() {
+    // Drand encodes GT as kilic/bls12-381 does: Fp12 = Fp6[w] as the coefficients of w and 1, Fp6 =
+    // Fp2[v] as the coefficients of v^2, v and 1, and Fp2 = Fp[u] as the coefficients of u and 1,
+    // each in big-endian. Compare with the pairing of the generators computed by arkworks.
+    let gt = Bls12_381::pairing(G1Affine::generator(), G2Affine::generator()).0;
+    let fp_to_bytes = |x: Fq| x.into_bigint().to_bytes_be();
+    let expected = [gt.c1, gt.c0]
+        .into_iter()
+        .flat_map(|x| [x.c2, x.c1, x.c0])
+        .flat_map(|x| [x.c1, x.c0])
+        .flat_map(fp_to_bytes)
+        .collect::<Vec<_>>();
+    assert_eq!(gt_to_drand_bytes(&GTElement::generator()), expected);
+}
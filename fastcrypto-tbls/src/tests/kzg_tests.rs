@@ -0,0 +1,171 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::kzg::*;
+use crate::polynomial::Poly;
+use fastcrypto::groups::bls12381::{G1Element, Scalar};
+use fastcrypto::groups::{GroupElement, Scalar as ScalarTrait};
+use rand::thread_rng;
+
+const DEGREE: u16 = 15;
+
+fn setup() -> SRS {
+    let tau = Scalar::rand(&mut thread_rng());
+    SRS::insecure_from_tau(&tau, DEGREE as usize + 1, 5).unwrap()
+}
+
+#[test]
+fn test_srs() {
+    let srs = setup();
+    assert_eq!(srs.max_degree(), DEGREE as usize);
+    assert_eq!(srs.max_points(), 4);
+    assert!(srs.check_consistency(&mut thread_rng()).is_ok());
+
+    // Mixing powers of different taus is detected.
+    let other = SRS::insecure_from_tau(&Scalar::rand(&mut thread_rng()), 3, 3).unwrap();
+    let mixed = SRS::new(srs.g1_powers().to_vec(), other.g2_powers().to_vec()).unwrap();
+    assert!(mixed.check_consistency(&mut thread_rng()).is_err());
+
+    // The first powers must be the generators.
+    assert!(SRS::new(vec![G1Element::zero()], vec![]).is_err());
+
+    // At least one power in G1 and two powers in G2 are needed.
+    let tau = Scalar::rand(&mut thread_rng());
+    assert!(SRS::insecure_from_tau(&tau, 1, 2).is_ok());
+    assert!(SRS::insecure_from_tau(&tau, 0, 2).is_err());
+    assert!(SRS::insecure_from_tau(&tau, 1, 1).is_err());
+}
+
+#[test]
+fn test_commit_open_verify() {
+    let srs = setup();
+    let poly = Poly::<Scalar>::rand(DEGREE, &mut thread_rng());
+    let commitment = srs.commit(&poly).unwrap();
+    let point = Scalar::rand(&mut thread_rng());
+    let (value, proof) = srs.open(&poly, &point).unwrap();
+    assert!(srs.verify(&commitment, &point, &value, &proof).is_ok());
+
+    // Wrong value, point or proof.
+    let one = Scalar::generator();
+    assert!(srs
+        .verify(&commitment, &point, &(value + one), &proof)
+        .is_err());
+    assert!(srs
+        .verify(&commitment, &(point + one), &value, &proof)
+        .is_err());
+    assert!(srs
+        .verify(
+            &commitment,
+            &point,
+            &value,
+            &(proof + G1Element::generator())
+        )
+        .is_err());
+
+    // Polynomials of too high degree cannot be committed to.
+    let poly = Poly::<Scalar>::rand(DEGREE + 1, &mut thread_rng());
+    assert!(srs.commit(&poly).is_err());
+}
+
+#[test]
+fn test_open_multi() {
+    let srs = setup();
+    let poly = Poly::<Scalar>::rand(DEGREE, &mut thread_rng());
+    let commitment = srs.commit(&poly).unwrap();
+    let points = (0..4)
+        .map(|_| Scalar::rand(&mut thread_rng()))
+        .collect::<Vec<_>>();
+    let (values, proof) = srs.open_multi(&poly, &points).unwrap();
+    assert!(srs
+        .verify_multi(&commitment, &points, &values, &proof)
+        .is_ok());
+
+    // A single point opening is consistent with the single point verification.
+    let (single_values, single_proof) = srs.open_multi(&poly, &points[..1]).unwrap();
+    assert!(srs
+        .verify(&commitment, &points[0], &single_values[0], &single_proof)
+        .is_ok());
+
+    let mut wrong_values = values.clone();
+    wrong_values[2] += Scalar::generator();
+    assert!(srs
+        .verify_multi(&commitment, &points, &wrong_values, &proof)
+        .is_err());
+
+    // Too many or duplicate points.
+    let too_many = (0..5)
+        .map(|_| Scalar::rand(&mut thread_rng()))
+        .collect::<Vec<_>>();
+    assert!(srs.open_multi(&poly, &too_many).is_err());
+    assert!(srs.open_multi(&poly, &[points[0], points[0]]).is_err());
+}
+
+#[test]
+fn test_lagrange() {
+    let srs = setup();
+    let domain = EvaluationDomain::new(DEGREE as usize + 1).unwrap();
+    let basis = srs.lagrange_basis(&domain).unwrap();
+
+    let poly = Poly::<Scalar>::rand(DEGREE, &mut thread_rng());
+    let evaluations = domain.evaluate(&poly).unwrap();
+    for (x, y) in domain.elements().iter().zip(evaluations.iter()) {
+        let expected = poly
+            .as_vec()
+            .iter()
+            .rev()
+            .fold(Scalar::zero(), |acc, c| acc * x + c);
+        assert_eq!(expected, *y);
+    }
+    assert_eq!(
+        commit_lagrange(&basis, &evaluations).unwrap(),
+        srs.commit(&poly).unwrap()
+    );
+
+    let point = Scalar::rand(&mut thread_rng());
+    let (value, proof) = srs.open_lagrange(&domain, &evaluations, &point).unwrap();
+    assert_eq!(srs.open(&poly, &point).unwrap(), (value, proof));
+
+    // The domain must fit in the SRS.
+    let domain = EvaluationDomain::new(2 * (DEGREE as usize + 1)).unwrap();
+    assert!(srs.lagrange_basis(&domain).is_err());
+}
+
+#[test]
+fn test_batch_verify() {
+    let srs = setup();
+    let mut openings = (0..10)
+        .map(|_| {
+            let poly = Poly::<Scalar>::rand(DEGREE, &mut thread_rng());
+            let point = Scalar::rand(&mut thread_rng());
+            let (value, proof) = srs.open(&poly, &point).unwrap();
+            (srs.commit(&poly).unwrap(), point, value, proof)
+        })
+        .collect::<Vec<_>>();
+    assert!(srs.batch_verify(&openings, &mut thread_rng()).is_ok());
+    assert!(srs.batch_verify(&[], &mut thread_rng()).is_ok());
+
+    openings[3].2 += Scalar::generator();
+    assert!(srs.batch_verify(&openings, &mut thread_rng()).is_err());
+}
+
+#[test]
+fn test_evaluation_domain() {
+    assert!(EvaluationDomain::new(0).is_err());
+    assert!(EvaluationDomain::new(3).is_err());
+
+    // The generator of the largest domain has order 2^32.
+    let domain = EvaluationDomain::new(1 << 32).unwrap();
+    let mut omega = domain.generator();
+    for _ in 0..31 {
+        omega = omega * omega;
+    }
+    assert_eq!(omega, -Scalar::generator());
+
+    // Interpolation inverts evaluation.
+    let domain = EvaluationDomain::new(8).unwrap();
+    let evaluations = (0..8)
+        .map(|_| Scalar::rand(&mut thread_rng()))
+        .collect::<Vec<_>>();
+    let poly = domain.interpolate(&evaluations).unwrap();
+    assert_eq!(domain.evaluate(&poly).unwrap(), evaluations);
+}
@@ -4,7 +4,10 @@
 use crate::nizk::{DLNizk, DdhTupleNizk};
 use crate::random_oracle::RandomOracle;
 use fastcrypto::groups::bls12381::{G1Element, G2Element};
+use fastcrypto::groups::bn254::G1Element as Bn254G1Element;
 use fastcrypto::groups::ristretto255::RistrettoPoint;
+use fastcrypto::groups::secp256k1::ProjectivePoint as Secp256k1Point;
+use fastcrypto::groups::secp256r1::ProjectivePoint;
 use fastcrypto::groups::{FiatShamirChallenge, GroupElement, Scalar};
 use rand::thread_rng;
 use serde::de::DeserializeOwned;
@@ -147,4 +150,13 @@ mod point_tests {
 
     #[instantiate_tests(<G2Element>)]
     mod g2_element {}
+
+    #[instantiate_tests(<ProjectivePoint>)]
+    mod secp256r1_point {}
+
+    #[instantiate_tests(<Secp256k1Point>)]
+    mod secp256k1_point {}
+
+    #[instantiate_tests(<Bn254G1Element>)]
+    mod bn254_g1_element {}
 }
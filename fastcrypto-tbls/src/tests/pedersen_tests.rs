@@ -0,0 +1,258 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::pedersen::*;
+use crate::random_oracle::RandomOracle;
+use fastcrypto::groups::bls12381::{G1Element, G2Element};
+use fastcrypto::groups::ristretto255::RistrettoPoint;
+use fastcrypto::groups::secp256r1::ProjectivePoint;
+use fastcrypto::groups::{FiatShamirChallenge, GroupElement, HashToGroupElement, Scalar};
+use rand::thread_rng;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+fn rand_scalars<S: Scalar>(n: usize) -> Vec<S> {
+    (0..n).map(|_| S::rand(&mut thread_rng())).collect()
+}
+
+fn test_commitments<G: GroupElement + HashToGroupElement + Serialize + DeserializeOwned>() {
+    let generators = PedersenGenerators::<G>::from_domain(b"test", 3).unwrap();
+    assert_eq!(generators.max_values(), 3);
+    assert_eq!(
+        generators,
+        PedersenGenerators::<G>::from_domain(b"test", 3).unwrap()
+    );
+    assert_ne!(
+        generators,
+        PedersenGenerators::<G>::from_domain(b"other", 3).unwrap()
+    );
+    assert!(PedersenGenerators::<G>::from_domain(b"test", 0).is_err());
+
+    // Deserialization requires at least one value generator.
+    let bytes = bcs::to_bytes(&generators).unwrap();
+    assert_eq!(
+        bcs::from_bytes::<PedersenGenerators<G>>(&bytes).unwrap(),
+        generators
+    );
+    let empty = bcs::to_bytes(&(Vec::<G>::new(), generators.blinding_generator())).unwrap();
+    assert!(bcs::from_bytes::<PedersenGenerators<G>>(&empty).is_err());
+
+    // Vector commitments.
+    let values = rand_scalars::<G::ScalarType>(3);
+    let blinding = G::ScalarType::rand(&mut thread_rng());
+    let commitment = generators.commit_vector(&values, &blinding).unwrap();
+    assert!(generators
+        .verify_opening(&commitment, &values, &blinding)
+        .is_ok());
+    assert!(generators
+        .verify_opening(&commitment, &values[..2], &blinding)
+        .is_err());
+    assert!(generators
+        .commit_vector(&rand_scalars(4), &blinding)
+        .is_err());
+    assert_eq!(
+        generators.commit_vector(&values[..1], &blinding).unwrap(),
+        generators.commit(&values[0], &blinding)
+    );
+
+    // Homomorphic operations.
+    let (v1, v2, r1, r2, k) = (values[0], values[1], values[2], blinding, blinding);
+    let c1 = generators.commit(&v1, &r1);
+    let c2 = generators.commit(&v2, &r2);
+    assert_eq!(c1 + c2, generators.commit(&(v1 + v2), &(r1 + r2)));
+    assert_eq!(c1 - c2, generators.commit(&(v1 - v2), &(r1 - r2)));
+    assert_eq!(c1 * k, generators.commit(&(v1 * k), &(r1 * k)));
+}
+
+fn test_opening_nizk<G>()
+where
+    G: GroupElement + HashToGroupElement + Serialize + DeserializeOwned,
+    G::ScalarType: FiatShamirChallenge + Serialize + DeserializeOwned,
+{
+    let generators = PedersenGenerators::<G>::from_domain(b"test", 4).unwrap();
+    let ro = RandomOracle::new("test");
+    let values = rand_scalars::<G::ScalarType>(3);
+    let blinding = G::ScalarType::rand(&mut thread_rng());
+    let commitment = generators.commit_vector(&values, &blinding).unwrap();
+    let nizk = OpeningNizk::create(
+        &generators,
+        &commitment,
+        &values,
+        &blinding,
+        &ro,
+        &mut thread_rng(),
+    )
+    .unwrap();
+    assert!(nizk.verify(&generators, &commitment, &ro).is_ok());
+    assert!(nizk
+        .verify(&generators, &commitment, &RandomOracle::new("test2"))
+        .is_err());
+    let other = generators.commit(&values[0], &blinding);
+    assert!(nizk.verify(&generators, &other, &ro).is_err());
+
+    // A proof with a wrong opening does not verify.
+    let invalid = OpeningNizk::create(
+        &generators,
+        &commitment,
+        &values[..2],
+        &blinding,
+        &ro,
+        &mut thread_rng(),
+    )
+    .unwrap();
+    assert!(invalid.verify(&generators, &commitment, &ro).is_err());
+
+    // serde
+    let as_bytes = bcs::to_bytes(&nizk).unwrap();
+    let nizk2: OpeningNizk<G> = bcs::from_bytes(&as_bytes).unwrap();
+    assert_eq!(nizk, nizk2);
+}
+
+fn test_equality_nizk<G>()
+where
+    G: GroupElement + HashToGroupElement + Serialize,
+    G::ScalarType: FiatShamirChallenge,
+{
+    let generators_1 = PedersenGenerators::<G>::from_domain(b"first", 1).unwrap();
+    let generators_2 = PedersenGenerators::<G>::from_domain(b"second", 1).unwrap();
+    let ro = RandomOracle::new("test");
+    let value = G::ScalarType::rand(&mut thread_rng());
+    let r_1 = G::ScalarType::rand(&mut thread_rng());
+    let r_2 = G::ScalarType::rand(&mut thread_rng());
+    let c_1 = generators_1.commit(&value, &r_1);
+    let c_2 = generators_2.commit(&value, &r_2);
+    let nizk = EqualityNizk::create(
+        (&generators_1, &generators_2),
+        (&c_1, &c_2),
+        &value,
+        (&r_1, &r_2),
+        &ro,
+        &mut thread_rng(),
+    );
+    assert!(nizk
+        .verify((&generators_1, &generators_2), (&c_1, &c_2), &ro)
+        .is_ok());
+    assert!(nizk
+        .verify((&generators_2, &generators_1), (&c_1, &c_2), &ro)
+        .is_err());
+
+    // Commitments to different values.
+    let other_value = value + G::ScalarType::generator();
+    let c_3 = generators_2.commit(&other_value, &r_2);
+    let invalid = EqualityNizk::create(
+        (&generators_1, &generators_2),
+        (&c_1, &c_3),
+        &value,
+        (&r_1, &r_2),
+        &ro,
+        &mut thread_rng(),
+    );
+    assert!(invalid
+        .verify((&generators_1, &generators_2), (&c_1, &c_3), &ro)
+        .is_err());
+}
+
+fn test_linear_relation_nizk<G>()
+where
+    G: GroupElement + HashToGroupElement + Serialize,
+    G::ScalarType: FiatShamirChallenge + Serialize,
+{
+    let generators = PedersenGenerators::<G>::from_domain(b"test", 1).unwrap();
+    let ro = RandomOracle::new("test");
+
+    // Prove that 2 * v_1 + 3 * v_2 - v_3 = b.
+    let values = rand_scalars::<G::ScalarType>(3);
+    let blindings = rand_scalars::<G::ScalarType>(3);
+    let coefficients = [
+        G::ScalarType::from(2),
+        G::ScalarType::from(3),
+        -G::ScalarType::generator(),
+    ];
+    let constant = coefficients
+        .iter()
+        .zip(values.iter())
+        .fold(G::ScalarType::zero(), |acc, (a, v)| acc + *a * v);
+    let commitments = values
+        .iter()
+        .zip(blindings.iter())
+        .map(|(v, r)| generators.commit(v, r))
+        .collect::<Vec<_>>();
+    let nizk = LinearRelationNizk::create(
+        &generators,
+        &commitments,
+        &coefficients,
+        &constant,
+        &blindings,
+        &ro,
+        &mut thread_rng(),
+    )
+    .unwrap();
+    assert!(nizk
+        .verify(&generators, &commitments, &coefficients, &constant, &ro)
+        .is_ok());
+
+    let wrong_constant = constant + G::ScalarType::generator();
+    assert!(nizk
+        .verify(
+            &generators,
+            &commitments,
+            &coefficients,
+            &wrong_constant,
+            &ro
+        )
+        .is_err());
+    let invalid = LinearRelationNizk::create(
+        &generators,
+        &commitments,
+        &coefficients,
+        &wrong_constant,
+        &blindings,
+        &ro,
+        &mut thread_rng(),
+    )
+    .unwrap();
+    assert!(invalid
+        .verify(
+            &generators,
+            &commitments,
+            &coefficients,
+            &wrong_constant,
+            &ro
+        )
+        .is_err());
+    assert!(nizk
+        .verify(
+            &generators,
+            &commitments,
+            &coefficients[..2],
+            &constant,
+            &ro
+        )
+        .is_err());
+}
+
+#[test]
+fn test_pedersen_ristretto() {
+    test_commitments::<RistrettoPoint>();
+    test_opening_nizk::<RistrettoPoint>();
+    test_equality_nizk::<RistrettoPoint>();
+    test_linear_relation_nizk::<RistrettoPoint>();
+}
+
+#[test]
+fn test_pedersen_bls12381() {
+    test_commitments::<G1Element>();
+    test_opening_nizk::<G1Element>();
+    test_equality_nizk::<G1Element>();
+    test_linear_relation_nizk::<G1Element>();
+    test_commitments::<G2Element>();
+    test_opening_nizk::<G2Element>();
+}
+
+#[test]
+fn test_pedersen_secp256r1() {
+    test_commitments::<ProjectivePoint>();
+    test_opening_nizk::<ProjectivePoint>();
+    test_equality_nizk::<ProjectivePoint>();
+    test_linear_relation_nizk::<ProjectivePoint>();
+}
@@ -0,0 +1,267 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::random_oracle::RandomOracle;
+use crate::sigma::*;
+use fastcrypto::groups::bls12381::{G1Element, G2Element};
+use fastcrypto::groups::ristretto255::RistrettoPoint;
+use fastcrypto::groups::secp256r1::ProjectivePoint;
+use fastcrypto::groups::{FiatShamirChallenge, GroupElement, MultiScalarMul, Scalar};
+use rand::thread_rng;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+fn random_point<G: GroupElement>() -> G {
+    G::generator() * G::ScalarType::rand(&mut thread_rng())
+}
+
+/// X = xG.
+fn dl_statement<G: GroupElement>(g: G, x: &G::ScalarType) -> Statement<G> {
+    let map = LinearMap::new(1, vec![vec![(0, g)]]).unwrap();
+    let image = map.apply(&[*x]).unwrap();
+    Statement::new(map, image).unwrap()
+}
+
+/// X = xG, Y = xH.
+fn ddh_statement<G: GroupElement>(g: G, h: G, x: &G::ScalarType) -> Statement<G> {
+    let map = LinearMap::new(1, vec![vec![(0, g)], vec![(0, h)]]).unwrap();
+    let image = map.apply(&[*x]).unwrap();
+    Statement::new(map, image).unwrap()
+}
+
+/// C = vG + rH.
+fn opening_statement<G: GroupElement>(
+    g: G,
+    h: G,
+    v: &G::ScalarType,
+    r: &G::ScalarType,
+) -> Statement<G> {
+    let map = LinearMap::new(2, vec![vec![(0, g), (1, h)]]).unwrap();
+    let image = map.apply(&[*v, *r]).unwrap();
+    Statement::new(map, image).unwrap()
+}
+
+fn test_linear_map<G: GroupElement + Serialize + DeserializeOwned>() {
+    let (g, h) = (random_point::<G>(), random_point::<G>());
+    assert!(LinearMap::<G>::new(1, vec![]).is_err());
+    assert!(LinearMap::<G>::new(1, vec![vec![]]).is_err());
+    assert!(LinearMap::new(1, vec![vec![(1, g)]]).is_err());
+
+    let map = LinearMap::new(2, vec![vec![(0, g), (1, h)], vec![(1, g)]]).unwrap();
+    assert_eq!(map.preimage_length(), 2);
+    assert_eq!(map.image_length(), 2);
+    let x = [
+        G::ScalarType::rand(&mut thread_rng()),
+        G::ScalarType::rand(&mut thread_rng()),
+    ];
+    assert_eq!(map.apply(&x).unwrap(), vec![g * x[0] + h * x[1], g * x[1]]);
+    assert!(map.apply(&x[..1]).is_err());
+
+    let image = map.apply(&x).unwrap();
+    assert!(Statement::new(map.clone(), image[..1].to_vec()).is_err());
+    let statement = Statement::new(map, image).unwrap();
+    assert!(statement.is_satisfied_by(&x));
+    assert!(!statement.is_satisfied_by(&[x[1], x[0]]));
+    assert!(!statement.is_satisfied_by(&x[..1]));
+
+    // Deserialization validates the map and the statement.
+    let bytes = bcs::to_bytes(&statement).unwrap();
+    assert_eq!(bcs::from_bytes::<Statement<G>>(&bytes).unwrap(), statement);
+    let out_of_bounds = bcs::to_bytes(&(1usize, vec![vec![(1usize, g)]])).unwrap();
+    assert!(bcs::from_bytes::<LinearMap<G>>(&out_of_bounds).is_err());
+    let empty_row = bcs::to_bytes(&(1usize, vec![Vec::<(usize, G)>::new()])).unwrap();
+    assert!(bcs::from_bytes::<LinearMap<G>>(&empty_row).is_err());
+    let short_image = bcs::to_bytes(&(statement.map(), &statement.image()[..1])).unwrap();
+    assert!(bcs::from_bytes::<Statement<G>>(&short_image).is_err());
+}
+
+fn test_sigma_proof<G>()
+where
+    G: GroupElement + Serialize + DeserializeOwned,
+    G::ScalarType: FiatShamirChallenge,
+{
+    let ro = RandomOracle::new("test");
+    let (g, h) = (random_point::<G>(), random_point::<G>());
+    let x = G::ScalarType::rand(&mut thread_rng());
+    let r = G::ScalarType::rand(&mut thread_rng());
+
+    for (statement, witness) in [
+        (dl_statement(g, &x), vec![x]),
+        (ddh_statement(g, h, &x), vec![x]),
+        (opening_statement(g, h, &x, &r), vec![x, r]),
+    ] {
+        let proof = SigmaProof::create(&statement, &witness, &ro, &mut thread_rng()).unwrap();
+        assert!(proof.verify(&statement, &ro).is_ok());
+        assert!(proof
+            .verify(&statement, &RandomOracle::new("other"))
+            .is_err());
+
+        let serialized = bcs::to_bytes(&proof).unwrap();
+        let deserialized: SigmaProof<G> = bcs::from_bytes(&serialized).unwrap();
+        assert_eq!(proof, deserialized);
+
+        // Wrong witness.
+        let mut wrong = witness.clone();
+        wrong[0] += G::ScalarType::generator();
+        assert!(SigmaProof::create(&statement, &wrong, &ro, &mut thread_rng()).is_err());
+    }
+
+    // Proof for a different statement.
+    let proof = SigmaProof::create(&dl_statement(g, &x), &[x], &ro, &mut thread_rng()).unwrap();
+    assert!(proof.verify(&dl_statement(g, &r), &ro).is_err());
+    assert!(proof.verify(&dl_statement(h, &x), &ro).is_err());
+    assert!(proof.verify(&ddh_statement(g, h, &x), &ro).is_err());
+}
+
+fn test_and_composition<G>()
+where
+    G: GroupElement + Serialize,
+    G::ScalarType: FiatShamirChallenge,
+{
+    let ro = RandomOracle::new("test");
+    let (g, h) = (random_point::<G>(), random_point::<G>());
+    let (x, v, r) = (
+        G::ScalarType::rand(&mut thread_rng()),
+        G::ScalarType::rand(&mut thread_rng()),
+        G::ScalarType::rand(&mut thread_rng()),
+    );
+    let statements = [dl_statement(g, &x), opening_statement(g, h, &v, &r)];
+    assert!(Statement::<G>::and(&[]).is_err());
+    let and = Statement::and(&statements).unwrap();
+    assert_eq!(and.map().preimage_length(), 3);
+    assert_eq!(and.image().len(), 2);
+
+    let witness = [x, v, r];
+    let proof = SigmaProof::create(&and, &witness, &ro, &mut thread_rng()).unwrap();
+    assert!(proof.verify(&and, &ro).is_ok());
+
+    // Knowing only one of the witnesses is not enough.
+    assert!(SigmaProof::create(&and, &[x, v, x], &ro, &mut thread_rng()).is_err());
+
+    // The order of the statements matters.
+    let swapped = Statement::and(&[statements[1].clone(), statements[0].clone()]).unwrap();
+    assert!(proof.verify(&swapped, &ro).is_err());
+}
+
+fn test_or_proof<G>()
+where
+    G: GroupElement + Serialize + DeserializeOwned,
+    G::ScalarType: FiatShamirChallenge,
+{
+    let ro = RandomOracle::new("test");
+    let (g, h) = (random_point::<G>(), random_point::<G>());
+    let x = G::ScalarType::rand(&mut thread_rng());
+    let statements = [
+        dl_statement(g, &G::ScalarType::rand(&mut thread_rng())),
+        ddh_statement(g, h, &x),
+        opening_statement(g, h, &x, &x),
+    ];
+
+    // A proof for each branch verifies.
+    for (index, witness) in [(1, vec![x]), (2, vec![x, x])] {
+        let proof = OrProof::create(&statements, index, &witness, &ro, &mut thread_rng()).unwrap();
+        assert!(proof.verify(&statements, &ro).is_ok());
+        assert!(proof.verify(&statements[..2], &ro).is_err());
+        assert!(proof
+            .verify(&statements, &RandomOracle::new("other"))
+            .is_err());
+
+        let serialized = bcs::to_bytes(&proof).unwrap();
+        let deserialized: OrProof<G> = bcs::from_bytes(&serialized).unwrap();
+        assert_eq!(proof, deserialized);
+    }
+
+    // The witness must match the chosen statement.
+    assert!(OrProof::create(&statements, 0, &[x], &ro, &mut thread_rng()).is_err());
+    assert!(OrProof::create(&statements, 3, &[x], &ro, &mut thread_rng()).is_err());
+
+    // A single statement reduces to a plain proof of knowledge.
+    let proof = OrProof::create(&statements[1..2], 0, &[x], &ro, &mut thread_rng()).unwrap();
+    assert!(proof.verify(&statements[1..2], &ro).is_ok());
+    assert!(proof.verify(&[], &ro).is_err());
+}
+
+fn test_batch_verify<G>()
+where
+    G: GroupElement + Serialize + MultiScalarMul,
+    G::ScalarType: FiatShamirChallenge,
+{
+    let ro = RandomOracle::new("test");
+    let (g, h) = (random_point::<G>(), random_point::<G>());
+    let witnesses = (0..4)
+        .map(|_| G::ScalarType::rand(&mut thread_rng()))
+        .collect::<Vec<_>>();
+    let statements = [
+        (dl_statement(g, &witnesses[0]), vec![witnesses[0]]),
+        (ddh_statement(g, h, &witnesses[1]), vec![witnesses[1]]),
+        (
+            opening_statement(g, h, &witnesses[2], &witnesses[3]),
+            vec![witnesses[2], witnesses[3]],
+        ),
+    ];
+    let proofs = statements
+        .iter()
+        .map(|(s, w)| SigmaProof::create(s, w, &ro, &mut thread_rng()).unwrap())
+        .collect::<Vec<_>>();
+    let batch = statements
+        .iter()
+        .map(|(s, _)| s)
+        .zip(proofs.iter())
+        .collect::<Vec<_>>();
+    assert!(SigmaProof::batch_verify(&batch, &ro, &mut thread_rng()).is_ok());
+    assert!(SigmaProof::<G>::batch_verify(&[], &ro, &mut thread_rng()).is_ok());
+    assert!(
+        SigmaProof::batch_verify(&batch, &RandomOracle::new("other"), &mut thread_rng()).is_err()
+    );
+
+    // A proof for a different statement should fail.
+    let other = dl_statement(g, &witnesses[3]);
+    let mut invalid = batch.clone();
+    invalid[0].0 = &other;
+    assert!(SigmaProof::batch_verify(&invalid, &ro, &mut thread_rng()).is_err());
+
+    // Proofs of mismatching shapes should fail.
+    let mut invalid = batch.clone();
+    invalid[0].1 = &proofs[2];
+    assert!(SigmaProof::batch_verify(&invalid, &ro, &mut thread_rng()).is_err());
+}
+
+#[test]
+fn test_linear_map_all_groups() {
+    test_linear_map::<RistrettoPoint>();
+    test_linear_map::<G1Element>();
+    test_linear_map::<G2Element>();
+    test_linear_map::<ProjectivePoint>();
+}
+
+#[test]
+fn test_sigma_proof_all_groups() {
+    test_sigma_proof::<RistrettoPoint>();
+    test_sigma_proof::<G1Element>();
+    test_sigma_proof::<G2Element>();
+    test_sigma_proof::<ProjectivePoint>();
+}
+
+#[test]
+fn test_and_composition_all_groups() {
+    test_and_composition::<RistrettoPoint>();
+    test_and_composition::<G1Element>();
+    test_and_composition::<G2Element>();
+    test_and_composition::<ProjectivePoint>();
+}
+
+#[test]
+fn test_or_proof_all_groups() {
+    test_or_proof::<RistrettoPoint>();
+    test_or_proof::<G1Element>();
+    test_or_proof::<G2Element>();
+    test_or_proof::<ProjectivePoint>();
+}
+
+#[test]
+fn test_batch_verify_all_groups() {
+    test_batch_verify::<RistrettoPoint>();
+    test_batch_verify::<G1Element>();
+    test_batch_verify::<G2Element>();
+    test_batch_verify::<ProjectivePoint>();
+}
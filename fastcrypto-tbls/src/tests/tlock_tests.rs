@@ -0,0 +1,362 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ibe::{Bls12381MinPk, Bls12381MinSig, Ciphertext, IbeScheme};
+use crate::tlock::*;
+use age::secrecy::ExposeSecret;
+use age_core::format::{FileKey, Stanza};
+use fastcrypto::groups::bls12381::{G1Element, G2Element, Scalar};
+use fastcrypto::groups::{GroupElement, Scalar as ScalarTrait};
+use rand::thread_rng;
+use std::collections::HashSet;
+
+// Retrieved with 'curl https://api.drand.sh/52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971/public/1234'.
+const QUICKNET_ROUND: u64 = 1234;
+const QUICKNET_SIGNATURE: &str = "a81d4aad15461a0a02b43da857be1d782a2232a3c7bb370a2763e95ce1f2628460b24de2cee7453cd12e43c197ea2f23";
+
+fn quicknet_signature() -> Vec<u8> {
+    hex::decode(QUICKNET_SIGNATURE).unwrap()
+}
+
+#[test]
+fn test_chain_info() {
+    let quicknet = ChainInfo::quicknet();
+    assert_eq!(quicknet.scheme(), Scheme::BlsUnchainedG1Rfc9380);
+    assert_eq!(quicknet.period(), 3);
+    assert_eq!(quicknet.public_key().len(), 96);
+    assert_eq!(
+        ChainInfo::new(
+            &quicknet.public_key(),
+            quicknet.genesis_time(),
+            quicknet.period(),
+            "bls-unchained-g1-rfc9380",
+            quicknet.hash(),
+        )
+        .unwrap(),
+        quicknet
+    );
+
+    let default_chain = ChainInfo::default_chain();
+    assert_eq!(default_chain.scheme(), Scheme::PedersenBlsChained);
+    assert_eq!(default_chain.public_key().len(), 48);
+
+    for scheme in [
+        Scheme::PedersenBlsChained,
+        Scheme::PedersenBlsUnchained,
+        Scheme::BlsUnchainedG1Rfc9380,
+    ] {
+        assert_eq!(Scheme::from_id(scheme.id()).unwrap(), scheme);
+    }
+    assert!(Scheme::from_id("bls-unchained-on-g1").is_err());
+
+    // Invalid parameters.
+    let new = |public_key: &[u8], period: u64, scheme_id: &str, hash: &[u8]| {
+        ChainInfo::new(public_key, 0, period, scheme_id, hash)
+    };
+    let hash = [0u8; 32];
+    let public_key = quicknet.public_key();
+    assert!(new(&public_key, 3, "bls-unchained-g1-rfc9380", &hash).is_ok());
+    assert!(new(&public_key, 0, "bls-unchained-g1-rfc9380", &hash).is_err());
+    assert!(new(&public_key, 3, "pedersen-bls-unchained", &hash).is_err());
+    assert!(new(&public_key, 3, "unknown", &hash).is_err());
+    assert!(new(&public_key[..95], 3, "bls-unchained-g1-rfc9380", &hash).is_err());
+    assert!(new(&public_key, 3, "bls-unchained-g1-rfc9380", &hash[..31]).is_err());
+}
+
+#[test]
+fn test_rounds_and_times() {
+    let chain = ChainInfo::quicknet();
+    let genesis_time = chain.genesis_time();
+    assert_eq!(chain.round_at(0), 1);
+    assert_eq!(chain.round_at(genesis_time - 1), 1);
+    assert_eq!(chain.round_at(genesis_time), 1);
+    assert_eq!(chain.round_at(genesis_time + 2), 1);
+    assert_eq!(chain.round_at(genesis_time + 3), 2);
+    assert_eq!(chain.time_of_round(1), genesis_time);
+    assert_eq!(chain.time_of_round(QUICKNET_ROUND), genesis_time + 3 * 1233);
+
+    let chain = ChainInfo::default_chain();
+    for round in [1, 2, 1000, 10_000_000] {
+        let time = chain.time_of_round(round);
+        assert_eq!(chain.round_at(time), round);
+        assert_eq!(chain.round_at(time + chain.period() - 1), round);
+        assert_eq!(chain.round_at(time - 1), (round - 1).max(1));
+    }
+    assert_eq!(chain.time_of_round(u64::MAX), u64::MAX);
+}
+
+#[test]
+fn test_verify_signature() {
+    let chain = ChainInfo::quicknet();
+    let signature = quicknet_signature();
+    assert!(chain.verify_signature(QUICKNET_ROUND, &signature).is_ok());
+    assert!(chain
+        .verify_signature(QUICKNET_ROUND + 1, &signature)
+        .is_err());
+    assert!(chain
+        .verify_signature(QUICKNET_ROUND, &signature[..47])
+        .is_err());
+
+    // Chained schemes also sign the previous signature.
+    assert!(ChainInfo::default_chain()
+        .verify_signature(QUICKNET_ROUND, &signature)
+        .is_err());
+}
+
+#[test]
+fn test_encrypt_and_decrypt_quicknet() {
+    let chain = ChainInfo::quicknet();
+    let signature = quicknet_signature();
+    for armor in [false, true] {
+        for length in [0, 1, 1000, 65535, 65536, 65537, 3 * 65536, 200_000] {
+            let message = (0..length).map(|i| i as u8).collect::<Vec<_>>();
+            let ciphertext = chain
+                .encrypt(QUICKNET_ROUND, &message, armor, &mut thread_rng())
+                .unwrap();
+            assert_eq!(required_round(&ciphertext).unwrap(), QUICKNET_ROUND);
+            assert_eq!(chain.decrypt(&ciphertext, &signature).unwrap(), message);
+        }
+    }
+
+    // A signature of another round cannot decrypt.
+    let ciphertext = chain
+        .encrypt(QUICKNET_ROUND + 1, b"message", false, &mut thread_rng())
+        .unwrap();
+    assert!(chain.decrypt(&ciphertext, &signature).is_err());
+}
+
+#[test]
+fn test_encrypt_and_decrypt_unchained() {
+    let master_sk = Scalar::rand(&mut thread_rng());
+    let public_key = bcs::to_bytes(&(G1Element::generator() * master_sk)).unwrap();
+    let chain =
+        ChainInfo::new(&public_key, 1000, 30, "pedersen-bls-unchained", &[1u8; 32]).unwrap();
+    let round = chain.round_at(2000);
+    assert_eq!(round, 34);
+    let signature =
+        bcs::to_bytes(&Bls12381MinPk::extract(&master_sk, &round_identity(round))).unwrap();
+    assert!(chain.verify_signature(round, &signature).is_ok());
+
+    for armor in [false, true] {
+        let ciphertext = chain
+            .encrypt(round, b"Hello, world!", armor, &mut thread_rng())
+            .unwrap();
+        assert_eq!(
+            chain.decrypt(&ciphertext, &signature).unwrap(),
+            b"Hello, world!"
+        );
+    }
+
+    // The chain hash is checked.
+    let ciphertext = chain
+        .encrypt(round, b"Hello, world!", false, &mut thread_rng())
+        .unwrap();
+    let other_chain =
+        ChainInfo::new(&public_key, 1000, 30, "pedersen-bls-unchained", &[2u8; 32]).unwrap();
+    assert!(other_chain.decrypt(&ciphertext, &signature).is_err());
+}
+
+#[test]
+fn test_chained_scheme() {
+    let chain = ChainInfo::default_chain();
+    assert!(chain
+        .encrypt(1000, b"message", false, &mut thread_rng())
+        .is_err());
+
+    let ciphertext = ChainInfo::quicknet()
+        .encrypt(QUICKNET_ROUND, b"message", false, &mut thread_rng())
+        .unwrap();
+    assert!(chain.decrypt(&ciphertext, &quicknet_signature()).is_err());
+}
+
+#[test]
+fn test_format() {
+    let chain = ChainInfo::quicknet();
+    let signature = quicknet_signature();
+    let ciphertext = chain
+        .encrypt(QUICKNET_ROUND, b"Hello, world!", false, &mut thread_rng())
+        .unwrap();
+    let expected_start = format!(
+        "age-encryption.org/v1\n-> tlock 1234 {}\n",
+        hex::encode(chain.hash())
+    );
+    assert!(ciphertext.starts_with(expected_start.as_bytes()));
+
+    let armored = chain
+        .encrypt(QUICKNET_ROUND, b"Hello, world!", true, &mut thread_rng())
+        .unwrap();
+    let armored = String::from_utf8(armored).unwrap();
+    assert!(armored.starts_with("-----BEGIN AGE ENCRYPTED FILE-----\n"));
+    assert!(armored.ends_with("\n-----END AGE ENCRYPTED FILE-----\n"));
+    assert!(armored.lines().all(|line| line.len() <= 64));
+
+    // Surrounding whitespace is ignored.
+    let padded = format!("\n  {armored}\n\n");
+    assert_eq!(
+        chain.decrypt(padded.as_bytes(), &signature).unwrap(),
+        b"Hello, world!"
+    );
+
+    // Flipping a bit in any byte makes parsing or decryption fail.
+    for i in 0..ciphertext.len() {
+        let mut tampered = ciphertext.clone();
+        tampered[i] ^= 1;
+        assert!(chain.decrypt(&tampered, &signature).is_err());
+    }
+
+    // Truncation.
+    for length in [0, 10, expected_start.len(), ciphertext.len() - 1] {
+        assert!(chain.decrypt(&ciphertext[..length], &signature).is_err());
+    }
+    assert!(required_round(b"age-encryption.org/v1\n").is_err());
+}
+
+#[test]
+fn test_other_stanzas() {
+    // Stanzas of other recipients are skipped. This one has a body of exactly one full line, so it
+    // is followed by an empty line.
+    let chain = ChainInfo::quicknet();
+    let ciphertext = chain
+        .encrypt(QUICKNET_ROUND, b"Hello, world!", false, &mut thread_rng())
+        .unwrap();
+    let intro = "age-encryption.org/v1\n";
+    let other = format!("-> X25519 abc\n{}\n\n", "A".repeat(64));
+    let mut with_other = intro.as_bytes().to_vec();
+    with_other.extend_from_slice(other.as_bytes());
+    with_other.extend_from_slice(&ciphertext[intro.len()..]);
+    assert_eq!(required_round(&with_other).unwrap(), QUICKNET_ROUND);
+
+    // The MAC covers the whole header.
+    assert!(chain.decrypt(&with_other, &quicknet_signature()).is_err());
+}
+
+// The ciphertexts in the regression tests below were produced by this implementation and not by
+// drand's tools, so they only guard against unintended changes of the format.
+
+#[test]
+fn test_regression() {
+    let ciphertext = "-----BEGIN AGE ENCRYPTED FILE-----
+YWdlLWVuY3J5cHRpb24ub3JnL3YxCi0+IHRsb2NrIDEyMzQgNTJkYjliYTcwZTBj
+YzBmNmVhZjc4MDNkZDA3NDQ3YTFmNTQ3NzczNWZkM2Y2NjE3OTJiYTk0NjAwYzg0
+ZTk3MQpnTGgzcWhhbjUxdmUxMlFwMDJrT3dMNWkzMFZaakFIK3FxUHpoYWtVLzlm
+K1ppL1paUnNPcUpSN01JZ2Q5Y2dzCkVaajg2QXBYSkNPc0xQeWdIbWFZUVlMVlp3
+L241ZlMwNXJsdmVTY2Rab3VtNDFKRThUSG1SbUJhSDY4cXEvOUIKeC85eW40dEJL
+dWJ4aXZxSVdOM3ZtUTFiOHhINTNBblVpSWlnUU9CR3NKZwotLS0gR08wZ0QwNllP
+RXd2OFlFTi9LNHQ3My9pc3BQSWdWVmx0dnpXeEZHNUVQMArHXSj+Ou7ewq7a+kVa
+0ha+y2zKztbcKsPPV0kYq2g+VKOziVsPo937TNfL2Kc=
+-----END AGE ENCRYPTED FILE-----
+";
+    assert_eq!(
+        required_round(ciphertext.as_bytes()).unwrap(),
+        QUICKNET_ROUND
+    );
+    assert_eq!(
+        ChainInfo::quicknet()
+            .decrypt(ciphertext.as_bytes(), &quicknet_signature())
+            .unwrap(),
+        b"Hello, world!"
+    );
+}
+
+#[test]
+fn test_regression_unchained() {
+    // A pedersen-bls-unchained chain (min-pk) with master secret key 123456789.
+    let public_key = hex::decode("af95b8218cbee2f4fa48e6b6f1df4e8ee46fee73c270dba395dad523d10c9b35295ccfc92cf0a9db8a065e16dafbfaad").unwrap();
+    let signature = hex::decode("8b7a8e6e54ed3ebe3be031eea8692bc76e396bc2760441f4c4eb5edf4f89a99129673f02510514194ba60df7fccfc4da040afa37f379414283d9a6eca0923c8f4c3d9aff23a8e54884665cb135a6465aab0b30eb9101949110b619a5cfc3647c").unwrap();
+    let chain =
+        ChainInfo::new(&public_key, 1000, 30, "pedersen-bls-unchained", &[1u8; 32]).unwrap();
+    assert!(chain.verify_signature(34, &signature).is_ok());
+
+    let ciphertext = "-----BEGIN AGE ENCRYPTED FILE-----
+YWdlLWVuY3J5cHRpb24ub3JnL3YxCi0+IHRsb2NrIDM0IDAxMDEwMTAxMDEwMTAx
+MDEwMTAxMDEwMTAxMDEwMTAxMDEwMTAxMDEwMTAxMDEwMTAxMDEwMTAxMDEwMTAx
+MDEKclJpRGhFVlVsb2l4WVFzSGpJQnhTR3VzMjRaeHIvdHlOZkpGSmNrVzVLQlc4
+bWRxQ3NCOVhwOWxxMVVEMGxIRApkS1ZKdHB2WVV1UkNJWW11eXJ0dHMvTUZpc1d6
+NGd5YXVWUGt2OStNRG40Ci0tLSBmZ0gwNjNVTEcrSTZ2dFZYSzcyeTNWTHFQY3p1
+eWVzblpzT1VNWkUzd29vCppi4Q4KTZ7xmkmVmruMD8KFHGlffxrTOLdgp4ozCARk
+rs8JA7TlU5R1OmSMJQ==
+-----END AGE ENCRYPTED FILE-----
+";
+    assert_eq!(required_round(ciphertext.as_bytes()).unwrap(), 34);
+    assert_eq!(
+        chain.decrypt(ciphertext.as_bytes(), &signature).unwrap(),
+        b"Hello, world!"
+    );
+}
+
+/// An age identity for quicknet which unwraps `tlock` stanzas using [Ciphertext], so the age
+/// framing of [ChainInfo::decrypt] can be checked against the age crate.
+struct QuicknetIdentity(G1Element);
+
+impl age::Identity for QuicknetIdentity {
+    fn unwrap_stanza(&self, stanza: &Stanza) -> Option<Result<FileKey, age::DecryptError>> {
+        if stanza.tag != "tlock" {
+            return None;
+        }
+        let file_key = Ciphertext::<Bls12381MinSig>::from_drand_bytes(&stanza.body)
+            .and_then(|ciphertext| ciphertext.decrypt(&self.0))
+            .ok()
+            .and_then(|file_key| file_key.try_into().ok())
+            .ok_or(age::DecryptError::DecryptionFailed);
+        Some(file_key.map(|file_key| FileKey::new(Box::new(file_key))))
+    }
+}
+
+/// An age recipient for a quicknet round which wraps the file key using [Ciphertext], so the age
+/// framing of [ChainInfo::encrypt] can be checked against the age crate.
+struct QuicknetRecipient(u64);
+
+impl age::Recipient for QuicknetRecipient {
+    fn wrap_file_key(
+        &self,
+        file_key: &FileKey,
+    ) -> Result<(Vec<Stanza>, HashSet<String>), age::EncryptError> {
+        let chain = ChainInfo::quicknet();
+        let public_key: G2Element = bcs::from_bytes(&chain.public_key()).unwrap();
+        let body = Ciphertext::<Bls12381MinSig>::encrypt(
+            &public_key,
+            &round_identity(self.0),
+            file_key.expose_secret(),
+            &mut thread_rng(),
+        )
+        .unwrap()
+        .to_drand_bytes();
+        let stanza = Stanza {
+            tag: "tlock".to_string(),
+            args: vec![self.0.to_string(), hex::encode(chain.hash())],
+            body,
+        };
+        Ok((vec![stanza], HashSet::new()))
+    }
+}
+
+#[test]
+fn test_age_interoperability() {
+    let chain = ChainInfo::quicknet();
+    let message = b"Hello, world!".repeat(10000);
+    let identity = QuicknetIdentity(bcs::from_bytes(&quicknet_signature()).unwrap());
+
+    // Files produced by ChainInfo::encrypt can be decrypted by the age crate.
+    for armor in [false, true] {
+        let ciphertext = chain
+            .encrypt(QUICKNET_ROUND, &message, armor, &mut thread_rng())
+            .unwrap();
+        assert_eq!(age::decrypt(&identity, &ciphertext).unwrap(), message);
+    }
+
+    // Files produced by the age crate can be decrypted by ChainInfo::decrypt.
+    let recipient = QuicknetRecipient(QUICKNET_ROUND);
+    let ciphertext = age::encrypt(&recipient, &message).unwrap();
+    assert_eq!(
+        chain.decrypt(&ciphertext, &quicknet_signature()).unwrap(),
+        message
+    );
+    let armored = age::encrypt_and_armor(&recipient, &message).unwrap();
+    assert_eq!(required_round(armored.as_bytes()).unwrap(), QUICKNET_ROUND);
+    assert_eq!(
+        chain
+            .decrypt(armored.as_bytes(), &quicknet_signature())
+            .unwrap(),
+        message
+    );
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::random_oracle::RandomOracle;
+use crate::transcript::Transcript;
+use fastcrypto::groups::bls12381::{G1Element, Scalar};
+use fastcrypto::groups::GroupElement;
+
+#[test]
+fn test_transcript() {
+    let mut t1 = Transcript::new("abc");
+    let mut t2 = Transcript::new("abc");
+    t1.append_message("m", b"alice");
+    t2.append_message("m", b"alice");
+    assert_eq!(t1.challenge_bytes("c"), t2.challenge_bytes("c"));
+
+    // Consecutive challenges differ.
+    assert_ne!(t1.challenge_bytes("c"), t1.clone().challenge_bytes("c"));
+
+    // Domains, labels, messages and challenge labels are all bound.
+    let challenge = |domain: &str, label: &str, message: &[u8], challenge_label: &str| {
+        let mut t = Transcript::new(domain);
+        t.append_message(label, message);
+        t.challenge_bytes(challenge_label)
+    };
+    let expected = challenge("abc", "m", b"alice", "c");
+    assert_ne!(expected, challenge("def", "m", b"alice", "c"));
+    assert_ne!(expected, challenge("abc", "n", b"alice", "c"));
+    assert_ne!(expected, challenge("abc", "m", b"bob", "c"));
+    assert_ne!(expected, challenge("abc", "m", b"alice", "d"));
+
+    // Moving bytes between the label and the message changes the output.
+    assert_ne!(
+        challenge("abc", "ma", b"lice", "c"),
+        challenge("abc", "m", b"alice", "c")
+    );
+}
+
+#[test]
+fn test_incremental_absorption() {
+    // Two messages are not the same as their concatenation.
+    let mut t1 = Transcript::new("abc");
+    t1.append_message("m", b"ali");
+    t1.append_message("m", b"ce");
+    let mut t2 = Transcript::new("abc");
+    t2.append_message("m", b"alice");
+    assert_ne!(t1.challenge_bytes("c"), t2.challenge_bytes("c"));
+
+    // Challenges depend on earlier challenges.
+    let mut t1 = Transcript::new("abc");
+    t1.append_message("m", b"alice");
+    t1.challenge_bytes("c");
+    t1.append_message("m", b"bob");
+    let mut t2 = Transcript::new("abc");
+    t2.append_message("m", b"alice");
+    t2.append_message("m", b"bob");
+    assert_ne!(t1.challenge_bytes("c"), t2.challenge_bytes("c"));
+}
+
+#[test]
+fn test_elements_and_scalars() {
+    let x = Scalar::from(7u128);
+    let g = G1Element::generator() * x;
+    let mut t1 = Transcript::new("abc");
+    t1.append_element("g", &g);
+    t1.append_scalar("x", &x);
+    let mut t2 = Transcript::new("abc");
+    t2.append("g", &g);
+    t2.append("x", &x);
+    let c1: Scalar = t1.challenge_scalar("c");
+    let c2: Scalar = t2.challenge_scalar("c");
+    assert_eq!(c1, c2);
+
+    let mut t3 = Transcript::new("abc");
+    t3.append_element("g", &G1Element::generator());
+    t3.append_scalar("x", &x);
+    assert_ne!(c1, t3.challenge_scalar::<Scalar>("c"));
+
+    let challenges: Vec<Scalar> = t1.challenge_scalars("c", 3);
+    assert_eq!(challenges.len(), 3);
+    assert_ne!(challenges[0], challenges[1]);
+    assert_ne!(challenges[1], challenges[2]);
+}
+
+#[test]
+fn test_fork() {
+    let mut t = Transcript::new("abc");
+    t.append_message("m", b"alice");
+    let mut parent = t.clone();
+
+    let mut f1 = t.fork("sub");
+    let mut f2 = t.fork("sub");
+    let mut f3 = t.fork("other");
+    let c = f1.challenge_bytes("c");
+    assert_eq!(c, f2.challenge_bytes("c"));
+    assert_ne!(c, f3.challenge_bytes("c"));
+
+    // Forking does not change the parent, and the forks differ from the parent.
+    let parent_challenge = parent.challenge_bytes("c");
+    assert_eq!(parent_challenge, t.challenge_bytes("c"));
+    assert_ne!(parent_challenge, c);
+
+    // Forks are bound to the state of the parent.
+    let mut other = Transcript::new("abc");
+    other.append_message("m", b"bob");
+    assert_ne!(c, other.fork("sub").challenge_bytes("c"));
+}
+
+#[test]
+fn test_random_oracle_to_transcript() {
+    let ro = RandomOracle::new("abc");
+    assert_eq!(
+        ro.to_transcript().challenge_bytes("c"),
+        Transcript::new("abc").challenge_bytes("c")
+    );
+    assert_ne!(
+        ro.extend("def").to_transcript().challenge_bytes("c"),
+        Transcript::new("abc").challenge_bytes("c")
+    );
+}
+
+#[test]
+fn test_regression() {
+    let mut t = Transcript::new("abc");
+    t.append_message("m", b"alice");
+    assert_eq!(
+        t.challenge_bytes("c").to_vec(),
+        hex::decode("0250b8c393e220c622ff137ee4f789f7d7459d6a8f29fb2a70bee3914e01fc26e3692b49a7560b2a1fcdb9da34a615a9d7c12b669e923a1b74dcd7f0d76acc1e").unwrap()
+    );
+}
@@ -0,0 +1,547 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Timelock encryption following drand's [tlock](https://github.com/drand/tlock).
+//!
+//! A message is encrypted towards a future round of a drand chain and can be decrypted by anyone
+//! once the chain has published its signature for that round, see [ibe](crate::ibe). Ciphertexts
+//! are files in the [age v1 format](https://age-encryption.org/v1) with a single `tlock` stanza,
+//! optionally ASCII-armored. The age framing is tested against the `age` crate, but
+//! interoperability with files produced by the `tle` tool and the tlock libraries has not been
+//! tested.
+//!
+//! Only chains with unchained schemes can be used since the signatures of chained schemes also
+//! sign the previous signature, which is not known in advance. [ChainInfo::default_chain] still
+//! supports the round and time computations.
+//!
+//! # Example
+//! ```rust
+//! # use fastcrypto_tbls::tlock::*;
+//! use rand::thread_rng;
+//! let chain = ChainInfo::quicknet();
+//! let round = chain.round_at(1692803367 + 3 * 1233);
+//! let ciphertext = chain
+//!     .encrypt(round, b"Hello, world!", true, &mut thread_rng())
+//!     .unwrap();
+//! assert_eq!(required_round(&ciphertext).unwrap(), 1234);
+//!
+//! // The signature of round 1234 from https://api.drand.sh/52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971/public/1234.
+//! let signature = hex::decode("a81d4aad15461a0a02b43da857be1d782a2232a3c7bb370a2763e95ce1f2628460b24de2cee7453cd12e43c197ea2f23").unwrap();
+//! assert_eq!(chain.decrypt(&ciphertext, &signature).unwrap(), b"Hello, world!");
+//! ```
+
+use crate::ibe::{Bls12381MinPk, Bls12381MinSig, Ciphertext, IbeScheme};
+use base64ct::{Base64, Base64Unpadded, Encoding};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use fastcrypto::error::{FastCryptoError, FastCryptoResult};
+use fastcrypto::groups::bls12381::{G1Element, G2Element};
+use fastcrypto::hash::{HashFunction, Sha256};
+use fastcrypto::traits::AllowedRng;
+use hkdf::hmac::{Hmac, Mac};
+use hkdf::Hkdf;
+
+const QUICKNET_PUBLIC_KEY: &str = "83cf0f2896adee7eb8b5f01fcad3912212c437e0073e911fb90022d3e760183c8c4b450b6a0a6c3ac6a5776a2d1064510d1fec758c921cc22b0e17e63aaf4bcb5ed66304de9cf809bd274ca73bab4af5a6e9c76a4bc09e76eae8991ef5ece45a";
+const QUICKNET_GENESIS_TIME: u64 = 1692803367;
+const QUICKNET_PERIOD: u64 = 3;
+const QUICKNET_HASH: &str = "52db9ba70e0cc0f6eaf7803dd07447a1f5477735fd3f661792ba94600c84e971";
+
+const DEFAULT_CHAIN_PUBLIC_KEY: &str = "868f005eb8e6e4ca0a47c8a77ceaa5309a47978a7c71bc5cce96366b5d7a569937c529eeda66c7293784a9402801af31";
+const DEFAULT_CHAIN_GENESIS_TIME: u64 = 1595431050;
+const DEFAULT_CHAIN_PERIOD: u64 = 30;
+const DEFAULT_CHAIN_HASH: &str = "8990e7a9aaed2ffed73dbd7092123d6f289930540d7651336225dc172e51b2ce";
+
+const AGE_INTRO: &str = "age-encryption.org/v1";
+const STANZA_PREFIX: &str = "-> ";
+const STANZA_TYPE: &str = "tlock";
+const MAC_PREFIX: &str = "---";
+const ARMOR_BEGIN: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+const ARMOR_END: &str = "-----END AGE ENCRYPTED FILE-----";
+const COLUMNS_PER_LINE: usize = 64;
+
+const FILE_KEY_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 16;
+const CHUNK_LENGTH: usize = 64 * 1024;
+const TAG_LENGTH: usize = 16;
+
+/// The signature schemes used by drand chains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scheme {
+    /// Public keys in G1 and signatures in G2 on the previous signature and the round.
+    PedersenBlsChained,
+    /// Public keys in G1 and signatures in G2 on the round.
+    PedersenBlsUnchained,
+    /// Public keys in G2 and signatures in G1 on the round, hashed as in RFC 9380. Used by quicknet.
+    BlsUnchainedG1Rfc9380,
+}
+
+impl Scheme {
+    /// The scheme ID used by drand.
+    pub fn id(&self) -> &'static str {
+        match self {
+            Scheme::PedersenBlsChained => "pedersen-bls-chained",
+            Scheme::PedersenBlsUnchained => "pedersen-bls-unchained",
+            Scheme::BlsUnchainedG1Rfc9380 => "bls-unchained-g1-rfc9380",
+        }
+    }
+
+    /// Get the scheme with the given drand scheme ID.
+    pub fn from_id(id: &str) -> FastCryptoResult<Self> {
+        [
+            Scheme::PedersenBlsChained,
+            Scheme::PedersenBlsUnchained,
+            Scheme::BlsUnchainedG1Rfc9380,
+        ]
+        .into_iter()
+        .find(|scheme| scheme.id() == id)
+        .ok_or(FastCryptoError::InvalidInput)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum PublicKey {
+    G1(G1Element),
+    G2(G2Element),
+}
+
+/// The parameters of a drand chain, as returned by the `/info` endpoint of a drand node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainInfo {
+    public_key: PublicKey,
+    genesis_time: u64,
+    period: u64,
+    scheme: Scheme,
+    hash: [u8; 32],
+}
+
+impl ChainInfo {
+    /// Create a new chain from a compressed public key, the genesis time in seconds since the UNIX
+    /// epoch, the period in seconds, the scheme ID and the chain hash.
+    pub fn new(
+        public_key: &[u8],
+        genesis_time: u64,
+        period: u64,
+        scheme_id: &str,
+        hash: &[u8],
+    ) -> FastCryptoResult<Self> {
+        if period == 0 {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let scheme = Scheme::from_id(scheme_id)?;
+        let public_key = match scheme {
+            Scheme::PedersenBlsChained | Scheme::PedersenBlsUnchained => PublicKey::G1(
+                bcs::from_bytes(public_key).map_err(|_| FastCryptoError::InvalidInput)?,
+            ),
+            Scheme::BlsUnchainedG1Rfc9380 => PublicKey::G2(
+                bcs::from_bytes(public_key).map_err(|_| FastCryptoError::InvalidInput)?,
+            ),
+        };
+        Ok(Self {
+            public_key,
+            genesis_time,
+            period,
+            scheme,
+            hash: hash
+                .try_into()
+                .map_err(|_| FastCryptoError::InputLengthWrong(32))?,
+        })
+    }
+
+    /// drand's quicknet chain.
+    pub fn quicknet() -> Self {
+        Self::new(
+            &hex::decode(QUICKNET_PUBLIC_KEY).expect("Valid hex"),
+            QUICKNET_GENESIS_TIME,
+            QUICKNET_PERIOD,
+            Scheme::BlsUnchainedG1Rfc9380.id(),
+            &hex::decode(QUICKNET_HASH).expect("Valid hex"),
+        )
+        .expect("Valid chain info")
+    }
+
+    /// drand's default chain. It uses a chained scheme, so it cannot be used for encryption.
+    pub fn default_chain() -> Self {
+        Self::new(
+            &hex::decode(DEFAULT_CHAIN_PUBLIC_KEY).expect("Valid hex"),
+            DEFAULT_CHAIN_GENESIS_TIME,
+            DEFAULT_CHAIN_PERIOD,
+            Scheme::PedersenBlsChained.id(),
+            &hex::decode(DEFAULT_CHAIN_HASH).expect("Valid hex"),
+        )
+        .expect("Valid chain info")
+    }
+
+    /// The compressed public key of the chain.
+    pub fn public_key(&self) -> Vec<u8> {
+        match &self.public_key {
+            PublicKey::G1(public_key) => bcs::to_bytes(public_key),
+            PublicKey::G2(public_key) => bcs::to_bytes(public_key),
+        }
+        .expect("Serialization of group elements never fails")
+    }
+
+    /// The genesis time in seconds since the UNIX epoch.
+    pub fn genesis_time(&self) -> u64 {
+        self.genesis_time
+    }
+
+    /// The period between rounds in seconds.
+    pub fn period(&self) -> u64 {
+        self.period
+    }
+
+    /// The signature scheme of the chain.
+    pub fn scheme(&self) -> Scheme {
+        self.scheme
+    }
+
+    /// The chain hash which identifies the chain.
+    pub fn hash(&self) -> &[u8; 32] {
+        &self.hash
+    }
+
+    /// The latest round published at the given time in seconds since the UNIX epoch. As in drand,
+    /// times before the genesis map to the first round.
+    pub fn round_at(&self, time: u64) -> u64 {
+        time.saturating_sub(self.genesis_time) / self.period + 1
+    }
+
+    /// The time in seconds since the UNIX epoch at which the given round is published.
+    pub fn time_of_round(&self, round: u64) -> u64 {
+        self.genesis_time
+            .saturating_add(round.saturating_sub(1).saturating_mul(self.period))
+    }
+
+    /// Verify a compressed signature of the given round. Fails for chained schemes since their
+    /// signatures cannot be verified without the previous signature.
+    pub fn verify_signature(&self, round: u64, signature: &[u8]) -> FastCryptoResult<()> {
+        match &self.unchained_public_key()? {
+            PublicKey::G2(public_key) => {
+                deserialize_and_verify::<Bls12381MinSig>(public_key, round, signature).map(|_| ())
+            }
+            PublicKey::G1(public_key) => {
+                deserialize_and_verify::<Bls12381MinPk>(public_key, round, signature).map(|_| ())
+            }
+        }
+    }
+
+    /// Encrypt a message such that it can be decrypted with the signature of the given round. The
+    /// result is an age file which is ASCII-armored if `armor` is true.
+    pub fn encrypt<R: AllowedRng>(
+        &self,
+        round: u64,
+        message: &[u8],
+        armor: bool,
+        rng: &mut R,
+    ) -> FastCryptoResult<Vec<u8>> {
+        let mut file_key = [0u8; FILE_KEY_LENGTH];
+        rng.fill_bytes(&mut file_key);
+        let identity = round_identity(round);
+        let body = match &self.unchained_public_key()? {
+            PublicKey::G2(public_key) => {
+                Ciphertext::<Bls12381MinSig>::encrypt(public_key, &identity, &file_key, rng)?
+                    .to_drand_bytes()
+            }
+            PublicKey::G1(public_key) => {
+                Ciphertext::<Bls12381MinPk>::encrypt(public_key, &identity, &file_key, rng)?
+                    .to_drand_bytes()
+            }
+        };
+
+        let mut header = format!(
+            "{AGE_INTRO}\n{STANZA_PREFIX}{STANZA_TYPE} {round} {}\n",
+            hex::encode(self.hash)
+        );
+        header.push_str(&wrap_lines(&Base64Unpadded::encode_string(&body)));
+        if body.len() % 48 == 0 {
+            // The last line of a stanza body is shorter than a full line, so it may be empty.
+            header.push('\n');
+        }
+        header.push_str(MAC_PREFIX);
+        let mac = header_mac(&file_key, header.as_bytes())
+            .finalize()
+            .into_bytes();
+        header.push(' ');
+        header.push_str(&Base64Unpadded::encode_string(&mac));
+        header.push('\n');
+
+        let mut nonce = [0u8; NONCE_LENGTH];
+        rng.fill_bytes(&mut nonce);
+        let mut result = header.into_bytes();
+        result.extend_from_slice(&nonce);
+        result.extend(stream_encrypt(&payload_key(&file_key, &nonce), message));
+
+        if armor {
+            let mut armored = format!("{ARMOR_BEGIN}\n");
+            armored.push_str(&wrap_lines(&Base64::encode_string(&result)));
+            armored.push_str(ARMOR_END);
+            armored.push('\n');
+            result = armored.into_bytes();
+        }
+        Ok(result)
+    }
+
+    /// Decrypt an age file, armored or not, using the compressed signature of the round it was
+    /// encrypted for. The round can be found using [required_round].
+    pub fn decrypt(&self, ciphertext: &[u8], signature: &[u8]) -> FastCryptoResult<Vec<u8>> {
+        let ciphertext = dearmor(ciphertext)?;
+        let header = Header::parse(&ciphertext)?;
+        if header.chain_hash != self.hash {
+            return Err(FastCryptoError::GeneralError(
+                "Ciphertext is for another chain".to_string(),
+            ));
+        }
+
+        let file_key = match &self.unchained_public_key()? {
+            PublicKey::G2(public_key) => {
+                unwrap_file_key::<Bls12381MinSig>(public_key, &header, signature)
+            }
+            PublicKey::G1(public_key) => {
+                unwrap_file_key::<Bls12381MinPk>(public_key, &header, signature)
+            }
+        }?;
+        header_mac(&file_key, header.mac_input)
+            .verify_slice(&header.mac)
+            .map_err(|_| FastCryptoError::GeneralError("Invalid header".to_string()))?;
+
+        if header.payload.len() < NONCE_LENGTH {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let (nonce, payload) = header.payload.split_at(NONCE_LENGTH);
+        stream_decrypt(&payload_key(&file_key, nonce), payload)
+    }
+
+    fn unchained_public_key(&self) -> FastCryptoResult<&PublicKey> {
+        match self.scheme {
+            Scheme::PedersenBlsChained => Err(FastCryptoError::GeneralError(
+                "Chained schemes are not supported".to_string(),
+            )),
+            Scheme::PedersenBlsUnchained | Scheme::BlsUnchainedG1Rfc9380 => Ok(&self.public_key),
+        }
+    }
+}
+
+/// The round an age file, armored or not, was encrypted for.
+pub fn required_round(ciphertext: &[u8]) -> FastCryptoResult<u64> {
+    Ok(Header::parse(&dearmor(ciphertext)?)?.round)
+}
+
+/// The message signed by unchained drand schemes for a round, which is the identity used for
+/// encryption.
+pub fn round_identity(round: u64) -> [u8; 32] {
+    Sha256::digest(round.to_be_bytes()).digest
+}
+
+fn deserialize_and_verify<S: IbeScheme>(
+    public_key: &S::Public,
+    round: u64,
+    signature: &[u8],
+) -> FastCryptoResult<S::Signature> {
+    let signature: S::Signature =
+        bcs::from_bytes(signature).map_err(|_| FastCryptoError::InvalidSignature)?;
+    S::verify_identity_key(public_key, &round_identity(round), &signature)?;
+    Ok(signature)
+}
+
+fn unwrap_file_key<S: IbeScheme>(
+    public_key: &S::Public,
+    header: &Header<'_>,
+    signature: &[u8],
+) -> FastCryptoResult<Vec<u8>> {
+    let signature = deserialize_and_verify::<S>(public_key, header.round, signature)?;
+    let file_key = Ciphertext::<S>::from_drand_bytes(&header.body)?.decrypt(&signature)?;
+    match file_key.len() {
+        FILE_KEY_LENGTH => Ok(file_key),
+        _ => Err(FastCryptoError::InvalidInput),
+    }
+}
+
+/// The parts of an age header needed for decryption with a tlock stanza.
+struct Header<'a> {
+    round: u64,
+    chain_hash: Vec<u8>,
+    body: Vec<u8>,
+    mac: Vec<u8>,
+    /// The header up to and including the "---" before the MAC.
+    mac_input: &'a [u8],
+    payload: &'a [u8],
+}
+
+impl<'a> Header<'a> {
+    fn parse(input: &'a [u8]) -> FastCryptoResult<Self> {
+        let mut position = 0;
+        if read_line(input, &mut position)? != AGE_INTRO {
+            return Err(FastCryptoError::InvalidInput);
+        }
+
+        let mut stanza = None;
+        loop {
+            let line_start = position;
+            let line = read_line(input, &mut position)?;
+            if let Some(mac) = line.strip_prefix(MAC_PREFIX) {
+                let (round, chain_hash, body) = stanza.ok_or(FastCryptoError::InvalidInput)?;
+                return Ok(Self {
+                    round,
+                    chain_hash,
+                    body,
+                    mac: decode_unpadded(
+                        mac.strip_prefix(' ').ok_or(FastCryptoError::InvalidInput)?,
+                    )?,
+                    mac_input: &input[..line_start + MAC_PREFIX.len()],
+                    payload: &input[position..],
+                });
+            }
+
+            let arguments = line
+                .strip_prefix(STANZA_PREFIX)
+                .ok_or(FastCryptoError::InvalidInput)?
+                .split(' ')
+                .collect::<Vec<_>>();
+            let mut body = String::new();
+            loop {
+                let line = read_line(input, &mut position)?;
+                body.push_str(line);
+                if line.len() < COLUMNS_PER_LINE {
+                    break;
+                }
+            }
+
+            // Other recipients are ignored, as age does if the identity does not match them.
+            if arguments[0] == STANZA_TYPE && stanza.is_none() {
+                if arguments.len() != 3 {
+                    return Err(FastCryptoError::InvalidInput);
+                }
+                stanza = Some((
+                    arguments[1]
+                        .parse()
+                        .map_err(|_| FastCryptoError::InvalidInput)?,
+                    hex::decode(arguments[2]).map_err(|_| FastCryptoError::InvalidInput)?,
+                    decode_unpadded(&body)?,
+                ));
+            }
+        }
+    }
+}
+
+/// Read a line terminated by a newline starting at the given position and advance the position to
+/// the start of the next line.
+fn read_line<'a>(input: &'a [u8], position: &mut usize) -> FastCryptoResult<&'a str> {
+    let length = input[*position..]
+        .iter()
+        .position(|&b| b == b'\n')
+        .ok_or(FastCryptoError::InvalidInput)?;
+    let line = std::str::from_utf8(&input[*position..*position + length])
+        .map_err(|_| FastCryptoError::InvalidInput)?;
+    *position += length + 1;
+    Ok(line)
+}
+
+fn decode_unpadded(encoded: &str) -> FastCryptoResult<Vec<u8>> {
+    Base64Unpadded::decode_vec(encoded).map_err(|_| FastCryptoError::InvalidInput)
+}
+
+/// Split a string into lines of [COLUMNS_PER_LINE] characters, each terminated by a newline.
+fn wrap_lines(encoded: &str) -> String {
+    encoded
+        .as_bytes()
+        .chunks(COLUMNS_PER_LINE)
+        .flat_map(|line| {
+            std::str::from_utf8(line)
+                .expect("Base64 is ASCII")
+                .chars()
+                .chain(std::iter::once('\n'))
+        })
+        .collect()
+}
+
+/// Remove the ASCII armor if the input is armored.
+fn dearmor(input: &[u8]) -> FastCryptoResult<Vec<u8>> {
+    let start = input
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(input.len());
+    if !input[start..].starts_with(ARMOR_BEGIN.as_bytes()) {
+        return Ok(input.to_vec());
+    }
+    let encoded = std::str::from_utf8(input)
+        .map_err(|_| FastCryptoError::InvalidInput)?
+        .trim()
+        .strip_prefix(ARMOR_BEGIN)
+        .and_then(|armored| armored.strip_suffix(ARMOR_END))
+        .ok_or(FastCryptoError::InvalidInput)?
+        .lines()
+        .map(str::trim)
+        .collect::<String>();
+    Base64::decode_vec(&encoded).map_err(|_| FastCryptoError::InvalidInput)
+}
+
+fn hkdf_sha256(ikm: &[u8], salt: &[u8], info: &[u8]) -> [u8; 32] {
+    let mut okm = [0u8; 32];
+    Hkdf::<sha2::Sha256>::new(Some(salt), ikm)
+        .expand(info, &mut okm)
+        .expect("32 bytes is a valid output length");
+    okm
+}
+
+fn header_mac(file_key: &[u8], header: &[u8]) -> Hmac<sha2::Sha256> {
+    let mut mac =
+        <Hmac<sha2::Sha256> as Mac>::new_from_slice(&hkdf_sha256(file_key, &[], b"header"))
+            .expect("HMAC accepts keys of any length");
+    mac.update(header);
+    mac
+}
+
+fn payload_key(file_key: &[u8], nonce: &[u8]) -> [u8; 32] {
+    hkdf_sha256(file_key, nonce, b"payload")
+}
+
+/// The nonce for a chunk in age's STREAM construction: an 11 byte big-endian counter followed by a
+/// byte which is one only for the last chunk.
+fn stream_nonce(counter: usize, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[3..11].copy_from_slice(&(counter as u64).to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+fn stream_encrypt(key: &[u8; 32], message: &[u8]) -> Vec<u8> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    // An empty message is encrypted as a single empty chunk.
+    let chunks = match message.is_empty() {
+        true => vec![message],
+        false => message.chunks(CHUNK_LENGTH).collect(),
+    };
+    chunks
+        .iter()
+        .enumerate()
+        .flat_map(|(i, chunk)| {
+            let nonce = stream_nonce(i, i == chunks.len() - 1);
+            cipher
+                .encrypt(Nonce::from_slice(&nonce), *chunk)
+                .expect("Encryption never fails")
+        })
+        .collect()
+}
+
+fn stream_decrypt(key: &[u8; 32], ciphertext: &[u8]) -> FastCryptoResult<Vec<u8>> {
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(key));
+    let chunks = ciphertext
+        .chunks(CHUNK_LENGTH + TAG_LENGTH)
+        .collect::<Vec<_>>();
+    let mut message = Vec::with_capacity(ciphertext.len());
+    for (i, chunk) in chunks.iter().enumerate() {
+        let nonce = stream_nonce(i, i == chunks.len() - 1);
+        let plaintext = cipher
+            .decrypt(Nonce::from_slice(&nonce), *chunk)
+            .map_err(|_| FastCryptoError::GeneralError("Decryption failed".to_string()))?;
+        // Only the first chunk may be empty.
+        if plaintext.is_empty() && i > 0 {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        message.extend(plaintext);
+    }
+    match chunks.is_empty() {
+        true => Err(FastCryptoError::InvalidInput),
+        false => Ok(message),
+    }
+}
@@ -0,0 +1,105 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use digest::Digest;
+use fastcrypto::groups::{FiatShamirChallenge, GroupElement, Scalar};
+use serde::Serialize;
+use sha3::Sha3_512;
+
+/// Domain separator for all transcripts, which also separates them from [crate::random_oracle::RandomOracle].
+const PROTOCOL_LABEL: &[u8] = b"fastcrypto-transcript-v1";
+
+// Operation tags.
+const DOMAIN: u8 = 0;
+const APPEND: u8 = 1;
+const CHALLENGE: u8 = 2;
+const FORK: u8 = 3;
+
+/// Fiat-Shamir transcript from SHA3-512 in the style of Merlin.
+/// - The state is a running SHA3-512 hash which absorbs every operation as
+///   tag | label_len as big-endian u32 | label | data_len as big-endian u32 | data.
+/// - A challenge is the SHA3-512 digest of the state after absorbing the challenge label. The
+///   challenge is absorbed afterwards, so consecutive challenges are independent.
+/// - Subprotocols may fork the transcript with a label, which gives a new transcript bound to
+///   everything absorbed so far without changing the parent transcript.
+///
+/// The caller must make sure to:
+/// - Choose a globally unique domain and distinct labels for the messages of a protocol.
+/// - Ensure that the labels and messages are small enough for their length to fit in u32.
+///   Violating this constraint will cause a panic.
+#[derive(Clone)]
+pub struct Transcript {
+    hasher: Sha3_512,
+}
+
+impl Transcript {
+    /// Create a fresh transcript with a given "session id"/domain.
+    pub fn new(domain: &str) -> Self {
+        let mut transcript = Self {
+            hasher: Sha3_512::default(),
+        };
+        transcript.absorb(DOMAIN, PROTOCOL_LABEL, domain.as_bytes());
+        transcript
+    }
+
+    /// Append a labeled message to the transcript.
+    pub fn append_message(&mut self, label: &str, message: &[u8]) {
+        self.absorb(APPEND, label.as_bytes(), message);
+    }
+
+    /// Append a labeled object to the transcript using its BCS serialization.
+    pub fn append<T: Serialize>(&mut self, label: &str, obj: &T) {
+        let serialized = bcs::to_bytes(obj).expect("serialize should never fail");
+        self.append_message(label, &serialized);
+    }
+
+    /// Append a labeled group element to the transcript.
+    pub fn append_element<G: GroupElement + Serialize>(&mut self, label: &str, element: &G) {
+        self.append(label, element);
+    }
+
+    /// Append a labeled scalar to the transcript.
+    pub fn append_scalar<S: Scalar>(&mut self, label: &str, scalar: &S) {
+        self.append(label, scalar);
+    }
+
+    /// Squeeze 64 bytes of labeled challenge from the transcript.
+    pub fn challenge_bytes(&mut self, label: &str) -> [u8; 64] {
+        self.absorb(CHALLENGE, label.as_bytes(), &[]);
+        let output: [u8; 64] = self.hasher.clone().finalize().into();
+        self.hasher.update(output);
+        output
+    }
+
+    /// Squeeze a labeled challenge scalar from the transcript.
+    pub fn challenge_scalar<S: FiatShamirChallenge>(&mut self, label: &str) -> S {
+        S::fiat_shamir_reduction_to_group_element(&self.challenge_bytes(label))
+    }
+
+    /// Squeeze n labeled challenge scalars from the transcript.
+    pub fn challenge_scalars<S: FiatShamirChallenge>(&mut self, label: &str, n: usize) -> Vec<S> {
+        (0..n).map(|_| self.challenge_scalar(label)).collect()
+    }
+
+    /// Derive a new transcript for a subprotocol from the current state and a label (can be done
+    /// multiple times). The current transcript is not modified.
+    pub fn fork(&self, label: &str) -> Self {
+        let mut transcript = self.clone();
+        transcript.absorb(FORK, label.as_bytes(), &[]);
+        transcript
+    }
+
+    fn absorb(&mut self, tag: u8, label: &[u8], data: &[u8]) {
+        self.hasher.update([tag]);
+        self.hasher.update(to_u32_be(label.len()));
+        self.hasher.update(label);
+        self.hasher.update(to_u32_be(data.len()));
+        self.hasher.update(data);
+    }
+}
+
+fn to_u32_be(len: usize) -> [u8; 4] {
+    u32::try_from(len)
+        .expect("length should be less than u32::MAX")
+        .to_be_bytes()
+}
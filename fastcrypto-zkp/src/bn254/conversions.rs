@@ -0,0 +1,77 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Conversions from the arkworks based types in this module to the BN254 groups in
+//! [`fastcrypto::groups::bn254`], which allows using the generic Groth16 verifier in
+//! [`crate::groth16`] over BN254.
+
+use ark_ec::AffineRepr;
+use fastcrypto::error::{FastCryptoError, FastCryptoResult};
+use fastcrypto::groups::bn254::{
+    G1Element, G2Element, GTElement, Scalar, FP_BYTE_LENGTH, GT_ELEMENT_BYTE_LENGTH, SCALAR_LENGTH,
+};
+use fastcrypto::serde_helpers::ToFromByteArray;
+
+use crate::bn254::{FieldElement, Proof, VerifyingKey};
+use crate::groth16;
+use crate::groth16::api::{FromLittleEndianByteArray, GTSerialize};
+
+impl From<&Proof> for groth16::Proof<G1Element> {
+    fn from(proof: &Proof) -> Self {
+        groth16::Proof::new(
+            G1Element::from(proof.0.a.into_group()),
+            G2Element::from(proof.0.b.into_group()),
+            G1Element::from(proof.0.c.into_group()),
+        )
+    }
+}
+
+impl TryFrom<&VerifyingKey> for groth16::VerifyingKey<G1Element> {
+    type Error = FastCryptoError;
+
+    fn try_from(vk: &VerifyingKey) -> FastCryptoResult<Self> {
+        groth16::VerifyingKey::new(
+            G1Element::from(vk.0.alpha_g1.into_group()),
+            G2Element::from(vk.0.beta_g2.into_group()),
+            G2Element::from(vk.0.gamma_g2.into_group()),
+            G2Element::from(vk.0.delta_g2.into_group()),
+            vk.0.gamma_abc_g1
+                .iter()
+                .map(|p| G1Element::from(p.into_group()))
+                .collect(),
+        )
+    }
+}
+
+impl From<&FieldElement> for Scalar {
+    fn from(element: &FieldElement) -> Self {
+        Scalar::from(element.0)
+    }
+}
+
+impl FromLittleEndianByteArray<SCALAR_LENGTH> for Scalar {
+    fn from_little_endian_byte_array(bytes: &[u8; SCALAR_LENGTH]) -> FastCryptoResult<Self> {
+        let mut reversed = *bytes;
+        reversed.reverse();
+        Scalar::from_byte_array(&reversed)
+    }
+}
+
+/// Arkworks serializes the coefficients of GT elements in the same order as [`GTElement`], but
+/// as little-endian integers.
+impl GTSerialize<GT_ELEMENT_BYTE_LENGTH> for GTElement {
+    fn to_arkworks_bytes(&self) -> [u8; GT_ELEMENT_BYTE_LENGTH] {
+        reverse_endianness(self.to_byte_array())
+    }
+
+    fn from_arkworks_bytes(bytes: &[u8; GT_ELEMENT_BYTE_LENGTH]) -> FastCryptoResult<Self> {
+        GTElement::from_byte_array(&reverse_endianness(*bytes))
+    }
+}
+
+fn reverse_endianness(mut bytes: [u8; GT_ELEMENT_BYTE_LENGTH]) -> [u8; GT_ELEMENT_BYTE_LENGTH] {
+    bytes
+        .chunks_exact_mut(FP_BYTE_LENGTH)
+        .for_each(|chunk| chunk.reverse());
+    bytes
+}
@@ -28,6 +28,9 @@ pub mod zk_login_api;
 /// Zk login utils
 pub mod utils;
 
+/// Conversions to the generic Groth16 types over BN254
+pub mod conversions;
+
 /// A field element in the BN254 construction. Thin wrapper around `ark_bn254::fields::fr::Fr`.
 #[derive(Clone, Debug, From, Into, PartialEq, Eq, Display, FromStr)]
 pub struct FieldElement(pub(crate) Fr);
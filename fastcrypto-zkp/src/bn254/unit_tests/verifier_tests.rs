@@ -1,13 +1,18 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 use ark_bn254::{Bn254, Fr};
+use ark_ec::pairing::Pairing;
 use ark_ff::UniformRand;
 use ark_groth16::Groth16;
+use ark_serialize::CanonicalSerialize;
 use ark_snark::SNARK;
 use ark_std::rand::thread_rng;
 use std::ops::Mul;
 
+use crate::bn254::{FieldElement, Proof, VerifyingKey};
 use crate::dummy_circuits::DummyCircuit;
+use crate::groth16;
+use fastcrypto::groups::bn254::{G1Element, Scalar};
 
 #[test]
 fn test_verify() {
@@ -26,3 +31,42 @@ fn test_verify() {
 
     assert!(Groth16::<Bn254>::verify(&vk, &[v], &proof).unwrap());
 }
+
+#[test]
+fn test_verify_with_generic_groth16() {
+    const PUBLIC_SIZE: usize = 128;
+    let rng = &mut thread_rng();
+    let c = DummyCircuit::<Fr> {
+        a: Some(<Fr>::rand(rng)),
+        b: Some(<Fr>::rand(rng)),
+        num_variables: PUBLIC_SIZE,
+        num_constraints: 256,
+    };
+
+    let (pk, vk) = Groth16::<Bn254>::circuit_specific_setup(c, rng).unwrap();
+    let proof = Groth16::<Bn254>::prove(&pk, c, rng).unwrap();
+    let v = c.a.unwrap().mul(c.b.unwrap());
+
+    let mut alpha_beta = Vec::new();
+    Bn254::pairing(vk.alpha_g1, vk.beta_g2)
+        .serialize_compressed(&mut alpha_beta)
+        .unwrap();
+
+    let vk = groth16::VerifyingKey::<G1Element>::try_from(&VerifyingKey::from(vk)).unwrap();
+    let pvk = groth16::PreparedVerifyingKey::from(&vk);
+    let proof = groth16::Proof::from(&Proof::from(proof));
+    let public_inputs = [Scalar::from(&FieldElement::from(v))];
+    assert!(pvk.verify(&public_inputs, &proof).is_ok());
+
+    let wrong_inputs = [Scalar::from(&FieldElement::from(v + Fr::from(1u64)))];
+    assert!(pvk.verify(&wrong_inputs, &proof).is_err());
+
+    // Serialize the prepared verifying key as done by the bytes API.
+    let parts = pvk.serialize_into_parts();
+    assert_eq!(parts[1], alpha_beta);
+    let pvk = groth16::PreparedVerifyingKey::<G1Element>::deserialize_from_parts(
+        &parts[0], &parts[1], &parts[2], &parts[3],
+    )
+    .unwrap();
+    assert!(pvk.verify(&public_inputs, &proof).is_ok());
+}
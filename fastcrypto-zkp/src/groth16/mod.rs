@@ -20,6 +20,16 @@ where
     c: G1,
 }
 
+impl<G1: Pairing> Proof<G1>
+where
+    G1::Other: Debug,
+{
+    /// Create a proof from its components.
+    pub fn new(a: G1, b: G1::Other, c: G1) -> Self {
+        Proof { a, b, c }
+    }
+}
+
 #[derive(Debug)]
 pub struct VerifyingKey<G1: Pairing>
 where
@@ -32,6 +42,32 @@ where
     gamma_abc: Vec<G1>,
 }
 
+impl<G1: Pairing> VerifyingKey<G1>
+where
+    G1::Other: Debug,
+{
+    /// Create a verifying key from its components. There must be at least one element in
+    /// `gamma_abc`, since its length should be equal to the number of public inputs + 1.
+    pub fn new(
+        alpha: G1,
+        beta: G1::Other,
+        gamma: G1::Other,
+        delta: G1::Other,
+        gamma_abc: Vec<G1>,
+    ) -> FastCryptoResult<Self> {
+        if gamma_abc.is_empty() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(VerifyingKey {
+            alpha,
+            beta,
+            gamma,
+            delta,
+            gamma_abc,
+        })
+    }
+}
+
 /// This is a helper function to store a pre-processed version of the verifying key.
 /// This is roughly homologous to [`ark_groth16::data_structures::PreparedVerifyingKey`].
 /// Note that contrary to Arkworks, we don't store a "prepared" version of the `gamma_neg` and
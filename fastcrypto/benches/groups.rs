@@ -10,6 +10,7 @@ mod group_benches {
         G1Element, G1ElementUncompressed, G2Element, GTElement, Scalar as BlsScalar,
         G1_ELEMENT_BYTE_LENGTH, G2_ELEMENT_BYTE_LENGTH, GT_ELEMENT_BYTE_LENGTH, SCALAR_LENGTH,
     };
+    use fastcrypto::groups::multiplier::constant_time::ConstantTimeMultiplier;
     use fastcrypto::groups::multiplier::windowed::WindowedScalarMultiplier;
     use fastcrypto::groups::multiplier::ScalarMultiplier;
     use fastcrypto::groups::ristretto255::RistrettoPoint;
@@ -95,6 +96,14 @@ mod group_benches {
             WindowedScalarMultiplier<ProjectivePoint, secp256r1::Scalar, 256, 5>,
             _,
         >("Secp256r1 Fixed window (256)", &mut group);
+        scale_single_precomputed::<ProjectivePoint, ConstantTimeMultiplier<ProjectivePoint, 4>, _>(
+            "Secp256r1 Constant time comb (4)",
+            &mut group,
+        );
+        scale_single_precomputed::<G1Element, ConstantTimeMultiplier<G1Element, 4>, _>(
+            "BLS12381-G1 Constant time comb (4)",
+            &mut group,
+        );
     }
 
     fn blst_msm_single<G: GroupElement + MultiScalarMul, M: Measurement>(
@@ -211,6 +220,37 @@ mod group_benches {
         pairing_single::<G1Element, _>("BLS12381-G1", &mut group);
     }
 
+    fn multi_pairing(c: &mut Criterion) {
+        static NUMBER_OF_PAIRS: [usize; 3] = [2, 4, 16];
+
+        let mut group: BenchmarkGroup<_> = c.benchmark_group("Multi-pairing");
+        for n in NUMBER_OF_PAIRS {
+            let points_g1: Vec<G1Element> = (0..n)
+                .map(|_| G1Element::generator() * BlsScalar::rand(&mut thread_rng()))
+                .collect();
+            let points_g2: Vec<G2Element> = (0..n)
+                .map(|_| G2Element::generator() * BlsScalar::rand(&mut thread_rng()))
+                .collect();
+            group.bench_with_input(
+                BenchmarkId::new("BLS12381 multi_pairing", n),
+                &(&points_g1, &points_g2),
+                |b, (g1, g2)| b.iter(|| G1Element::multi_pairing(g1, g2).unwrap()),
+            );
+            group.bench_with_input(
+                BenchmarkId::new("BLS12381 sum of pairings", n),
+                &(&points_g1, &points_g2),
+                |b, (g1, g2)| {
+                    b.iter(|| {
+                        g1.iter()
+                            .zip(g2.iter())
+                            .map(|(x, y)| x.pairing(y))
+                            .fold(GTElement::zero(), |acc, e| acc + e)
+                    })
+                },
+            );
+        }
+    }
+
     fn sum(c: &mut Criterion) {
         static NUMBER_OF_TERMS: [usize; 4] = [10, 100, 500, 1000];
 
@@ -340,6 +380,7 @@ mod group_benches {
             scale,
             hash_to_group,
             pairing,
+            multi_pairing,
             double_scale,
             blst_msm,
             sum,
@@ -39,15 +39,60 @@ mod hash_benches {
             hash_single::<Sha3_256, 32, _>("Sha3_256", &input, &mut group);
             hash_single::<Blake2b256, 32, _>("Blake2b256", &input, &mut group);
             hash_single::<Keccak256, 32, _>("Keccak256", &input, &mut group);
+            hash_single::<Blake3, 32, _>("Blake3", &input, &mut group);
+            hash_single::<Sha384, 48, _>("Sha384", &input, &mut group);
+            hash_single::<Sha3_384, 48, _>("Sha3_384", &input, &mut group);
             hash_single::<Sha512, 64, _>("Sha512", &input, &mut group);
             hash_single::<Sha3_512, 64, _>("Sha3_512", &input, &mut group);
         }
     }
 
+    fn multiset_hash_insert<H: MultisetHash<32> + Default, M: measurement::Measurement>(
+        name: &str,
+        input: &[u8],
+        c: &mut BenchmarkGroup<M>,
+    ) {
+        c.bench_with_input(
+            BenchmarkId::new(name.to_string(), input.len()),
+            &input,
+            |b, input| {
+                let mut hash = H::default();
+                b.iter(|| hash.insert(input));
+            },
+        );
+    }
+
+    fn multiset_hash(c: &mut Criterion) {
+        static INPUT_SIZES: [usize; 3] = [32, 128, 1024];
+
+        let mut group: BenchmarkGroup<_> = c.benchmark_group("MultisetHash");
+
+        for size in INPUT_SIZES.iter() {
+            let input: Vec<u8> = (0..*size).map(|_| rand::random::<u8>()).collect();
+            multiset_hash_insert::<EllipticCurveMultisetHash, _>("ECMH", &input, &mut group);
+            multiset_hash_insert::<LtHash<Blake3Xof>, _>("LtHash (Blake3)", &input, &mut group);
+            multiset_hash_insert::<LtHash<Shake128>, _>("LtHash (Shake128)", &input, &mut group);
+        }
+
+        let mut a = LtHash::<Blake3Xof>::default();
+        a.insert(b"a");
+        let mut b = LtHash::<Blake3Xof>::default();
+        b.insert(b"b");
+        group.bench_function("LtHash union", |bencher| bencher.iter(|| a.union(&b)));
+        group.bench_function("LtHash digest", |bencher| bencher.iter(|| a.digest()));
+
+        let mut a = EllipticCurveMultisetHash::default();
+        a.insert(b"a");
+        let mut b = EllipticCurveMultisetHash::default();
+        b.insert(b"b");
+        group.bench_function("ECMH union", |bencher| bencher.iter(|| a.union(&b)));
+        group.bench_function("ECMH digest", |bencher| bencher.iter(|| a.digest()));
+    }
+
     criterion_group! {
         name = hash_benches;
         config = Criterion::default();
-        targets = hash,
+        targets = hash, multiset_hash,
     }
 }
 
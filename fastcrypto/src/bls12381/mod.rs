@@ -14,10 +14,7 @@
 //! ```
 
 #[cfg(any(test, feature = "experimental"))]
-use crate::error::{
-    FastCryptoError::{InvalidInput, InvalidSignature},
-    FastCryptoResult,
-};
+use crate::error::{FastCryptoError::InvalidInput, FastCryptoResult};
 use crate::serde_helpers::BytesRepresentation;
 use crate::traits::{
     AggregateAuthenticator, AllowedRng, Authenticator, EncodeDecodeBase64, InsecureDefault,
@@ -188,28 +185,40 @@ macro_rules! define_bls12381 {
                     .validate()
                     .map_err(|_e| FastCryptoError::InvalidInput)
             }
-        }
 
-        impl VerifyingKey for BLS12381PublicKey {
-            type PrivKey = BLS12381PrivateKey;
-            type Sig = BLS12381Signature;
-            const LENGTH: usize = $pk_length;
-
-            fn verify(
+            /// Verify a signature on a message that was hashed to the curve using the given domain
+            /// separation tag. Used by schemes built on top of BLS signatures (e.g., the BLS VRF)
+            /// that must not share the signature scheme's tag.
+            pub(crate) fn verify_with_dst(
                 &self,
                 msg: &[u8],
                 signature: &BLS12381Signature,
+                dst: &[u8],
             ) -> Result<(), FastCryptoError> {
                 // verify() only validates the signature. Please use pk that was validated.
                 let err = signature
                     .sig
-                    .verify(true, msg, $dst_string, &[], &self.pubkey, false);
+                    .verify(true, msg, dst, &[], &self.pubkey, false);
                 if err == BLST_ERROR::BLST_SUCCESS {
                     Ok(())
                 } else {
                     Err(FastCryptoError::InvalidSignature)
                 }
             }
+        }
+
+        impl VerifyingKey for BLS12381PublicKey {
+            type PrivKey = BLS12381PrivateKey;
+            type Sig = BLS12381Signature;
+            const LENGTH: usize = $pk_length;
+
+            fn verify(
+                &self,
+                msg: &[u8],
+                signature: &BLS12381Signature,
+            ) -> Result<(), FastCryptoError> {
+                self.verify_with_dst(msg, signature, $dst_string)
+            }
 
             #[cfg(any(test, feature = "experimental"))]
             fn verify_batch_empty_fail(
@@ -236,24 +245,40 @@ macro_rules! define_bls12381 {
                 if sigs.is_empty() || sigs.len() != pks.len() {
                     return Err(InvalidInput);
                 }
-
-                let rands = get_random_scalars(sigs.len());
-
-                let result = blst::Signature::verify_multiple_aggregate_signatures(
-                    &msgs.iter().map(|m| m.borrow()).collect::<Vec<_>>(),
+                verify_batch_different_msg_with_dst(
+                    msgs,
+                    &pks.iter().collect::<Vec<_>>(),
+                    &sigs.iter().collect::<Vec<_>>(),
                     $dst_string,
-                    &pks.iter().map(|pk| &pk.pubkey).collect::<Vec<_>>(),
-                    false,
-                    &sigs.iter().map(|sig| &sig.sig).collect::<Vec<_>>(),
-                    true,
-                    &rands,
-                    BLS_BATCH_RANDOM_SCALAR_LENGTH,
-                );
-                if result == BLST_ERROR::BLST_SUCCESS {
-                    Ok(())
-                } else {
-                    Err(InvalidSignature)
-                }
+                )
+            }
+        }
+
+        /// Verify a batch of signatures on different messages, each hashed to the curve using the
+        /// given domain separation tag. The caller must check that the inputs are non-empty and of
+        /// equal length.
+        pub(crate) fn verify_batch_different_msg_with_dst<M: Borrow<[u8]>>(
+            msgs: &[M],
+            pks: &[&BLS12381PublicKey],
+            sigs: &[&BLS12381Signature],
+            dst: &[u8],
+        ) -> Result<(), FastCryptoError> {
+            let rands = get_random_scalars(sigs.len());
+
+            let result = blst::Signature::verify_multiple_aggregate_signatures(
+                &msgs.iter().map(|m| m.borrow()).collect::<Vec<_>>(),
+                dst,
+                &pks.iter().map(|pk| &pk.pubkey).collect::<Vec<_>>(),
+                false,
+                &sigs.iter().map(|sig| &sig.sig).collect::<Vec<_>>(),
+                true,
+                &rands,
+                BLS_BATCH_RANDOM_SCALAR_LENGTH,
+            );
+            if result == BLST_ERROR::BLST_SUCCESS {
+                Ok(())
+            } else {
+                Err(FastCryptoError::InvalidSignature)
             }
         }
 
@@ -356,8 +381,16 @@ macro_rules! define_bls12381 {
 
         impl Signer<BLS12381Signature> for BLS12381PrivateKey {
             fn sign(&self, msg: &[u8]) -> BLS12381Signature {
+                self.sign_with_dst(msg, $dst_string)
+            }
+        }
+
+        impl BLS12381PrivateKey {
+            /// Sign a message after hashing it to the curve using the given domain separation tag.
+            /// See [BLS12381PublicKey::verify_with_dst].
+            pub(crate) fn sign_with_dst(&self, msg: &[u8], dst: &[u8]) -> BLS12381Signature {
                 BLS12381Signature {
-                    sig: self.privkey.sign(msg, $dst_string, &[]),
+                    sig: self.privkey.sign(msg, dst, &[]),
                     bytes: OnceCell::new(),
                 }
             }
@@ -17,6 +17,7 @@
 //! ```
 
 use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use curve25519_dalek_ng::traits::{Identity, IsIdentity, VartimeMultiscalarMul};
 use curve25519_dalek_ng::{
     ristretto::{CompressedRistretto, RistrettoPoint},
     scalar::Scalar,
@@ -26,7 +27,12 @@ use merlin::Transcript;
 use once_cell::sync::OnceCell;
 
 use crate::error::FastCryptoError::{GeneralOpaqueError, InvalidInput};
+use crate::error::FastCryptoResult;
+use crate::groups::ristretto255;
+use crate::groups::ristretto255::RistrettoScalar;
+use crate::hash::{ExtendableOutputFunction, Shake256};
 use crate::serde_helpers::ToFromByteArray;
+use crate::traits::AllowedRng;
 use crate::{
     error::FastCryptoError, serialize_deserialize_with_to_from_byte_array, traits::ToFromBytes,
 };
@@ -60,6 +66,33 @@ impl PedersenCommitment {
         let blinding = Scalar::from_bits(blinding_factor);
         generators.commit(value, blinding).into()
     }
+
+    ///
+    /// Creates a new Pedersen commitment from a value and a blinding factor using the given
+    /// generators.
+    ///
+    pub fn new_with_generators(
+        value: &RistrettoScalar,
+        blinding_factor: &RistrettoScalar,
+        generators: &PedersenGenerators,
+    ) -> Self {
+        generators
+            .to_pedersen_gens()
+            .commit(value.0, blinding_factor.0)
+            .into()
+    }
+}
+
+impl From<ristretto255::RistrettoPoint> for PedersenCommitment {
+    fn from(point: ristretto255::RistrettoPoint) -> Self {
+        Self { point: point.0 }
+    }
+}
+
+impl From<&PedersenCommitment> for ristretto255::RistrettoPoint {
+    fn from(commitment: &PedersenCommitment) -> Self {
+        Self::from(commitment.point)
+    }
 }
 
 impl ToFromByteArray<PEDERSEN_COMMITMENT_LENGTH> for PedersenCommitment {
@@ -75,6 +108,57 @@ impl ToFromByteArray<PEDERSEN_COMMITMENT_LENGTH> for PedersenCommitment {
 
 serialize_deserialize_with_to_from_byte_array!(PedersenCommitment);
 
+/// The generators used for Pedersen commitments. A commitment to a value v with blinding factor r
+/// is v * G + r * H where G is the value generator and H is the blinding generator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PedersenGenerators {
+    value_generator: RistrettoPoint,
+    blinding_generator: RistrettoPoint,
+}
+
+impl PedersenGenerators {
+    /// Create a new set of generators. The generators must be chosen such that no one knows the
+    /// discrete logarithm of one with respect to the other, e.g. by hashing to the group.
+    pub fn new(
+        value_generator: ristretto255::RistrettoPoint,
+        blinding_generator: ristretto255::RistrettoPoint,
+    ) -> Self {
+        Self {
+            value_generator: value_generator.0,
+            blinding_generator: blinding_generator.0,
+        }
+    }
+
+    /// The generator G used for the value.
+    pub fn value_generator(&self) -> ristretto255::RistrettoPoint {
+        self.value_generator.into()
+    }
+
+    /// The generator H used for the blinding factor.
+    pub fn blinding_generator(&self) -> ristretto255::RistrettoPoint {
+        self.blinding_generator.into()
+    }
+
+    fn to_pedersen_gens(self) -> PedersenGens {
+        PedersenGens {
+            B: self.value_generator,
+            B_blinding: self.blinding_generator,
+        }
+    }
+}
+
+impl Default for PedersenGenerators {
+    /// The generators used by [PedersenCommitment::new] and
+    /// [BulletproofsRangeProof::prove_bit_length].
+    fn default() -> Self {
+        let generators = PedersenGens::default();
+        Self {
+            value_generator: generators.B,
+            blinding_generator: generators.B_blinding,
+        }
+    }
+}
+
 ///
 /// Bulletproof Range Proofs
 ///
@@ -161,6 +245,411 @@ impl BulletproofsRangeProof {
             )
             .map_err(|_| FastCryptoError::GeneralError("Failed to verify proof".to_string()))
     }
+
+    /// Prove that all the values are unsigned integers with bit length bits using a single
+    /// aggregated proof. Returns commitments to the values with the given blinding factors and
+    /// generators along with the proof. The size of the proof grows logarithmically with the
+    /// number of values.
+    /// Function only works for bits = 8, 16, 32, 64.
+    pub fn prove_bit_length_multiple(
+        values: &[u64],
+        blindings: &[RistrettoScalar],
+        bits: usize,
+        generators: &PedersenGenerators,
+        domain: &'static [u8],
+    ) -> FastCryptoResult<(Vec<PedersenCommitment>, Self)> {
+        if !is_valid_bit_length(bits)
+            || values.is_empty()
+            || values.len() != blindings.len()
+            || (bits < 64 && values.iter().any(|v| v >> bits != 0))
+        {
+            return Err(InvalidInput);
+        }
+
+        // Aggregation requires the number of values to be a power of two, so we pad with
+        // commitments to zero with zero blinding, which are the identity.
+        let m = values.len().next_power_of_two();
+        let mut padded_values = values.to_vec();
+        padded_values.resize(m, 0);
+        let mut padded_blindings = blindings.iter().map(|b| b.0).collect::<Vec<_>>();
+        padded_blindings.resize(m, Scalar::zero());
+
+        let bp_gens = BulletproofGens::new(bits, m);
+        let mut prover_transcript = Transcript::new(domain);
+        let (proof, commitments) = RangeProof::prove_multiple(
+            &bp_gens,
+            &generators.to_pedersen_gens(),
+            &mut prover_transcript,
+            &padded_values,
+            &padded_blindings,
+            bits,
+        )
+        .map_err(|_| GeneralOpaqueError)?;
+
+        let commitments = commitments
+            .iter()
+            .take(values.len())
+            .map(|c| c.decompress().map(PedersenCommitment::from))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(GeneralOpaqueError)?;
+        Ok((commitments, proof.into()))
+    }
+
+    /// Verifies that the commitments are Pedersen commitments with the given generators of values
+    /// with an unsigned bit length `bits`, using a proof created by
+    /// [BulletproofsRangeProof::prove_bit_length_multiple].
+    /// Function only works for bits = 8, 16, 32, 64.
+    pub fn verify_bit_length_multiple(
+        &self,
+        commitments: &[PedersenCommitment],
+        bits: usize,
+        generators: &PedersenGenerators,
+        domain: &'static [u8],
+    ) -> FastCryptoResult<()> {
+        if !is_valid_bit_length(bits) || commitments.is_empty() {
+            return Err(InvalidInput);
+        }
+
+        let commitments = pad_commitments(commitments);
+        let bp_gens = BulletproofGens::new(bits, commitments.len());
+        let mut verifier_transcript = Transcript::new(domain);
+
+        self.proof
+            .verify_multiple(
+                &bp_gens,
+                &generators.to_pedersen_gens(),
+                &mut verifier_transcript,
+                &commitments,
+                bits,
+            )
+            .map_err(|_| FastCryptoError::GeneralError("Failed to verify proof".to_string()))
+    }
+
+    /// Verify a batch of proofs created by [BulletproofsRangeProof::prove_bit_length_multiple]
+    /// along with the commitments they are for. All proofs must be for the same bit length,
+    /// generators and domain. This is faster than verifying the proofs one by one since all the
+    /// verification equations are combined into a single multi-scalar multiplication using random
+    /// weights.
+    /// Function only works for bits = 8, 16, 32, 64.
+    pub fn batch_verify_bit_length<R: AllowedRng>(
+        proofs: &[(&Self, &[PedersenCommitment])],
+        bits: usize,
+        generators: &PedersenGenerators,
+        domain: &'static [u8],
+        rng: &mut R,
+    ) -> FastCryptoResult<()> {
+        if !is_valid_bit_length(bits) || proofs.iter().any(|(_, c)| c.is_empty()) {
+            return Err(InvalidInput);
+        }
+        let max_m = match proofs
+            .iter()
+            .map(|(_, c)| c.len().next_power_of_two())
+            .max()
+        {
+            Some(m) => m,
+            None => return Ok(()),
+        };
+
+        let mut terms = VerificationTerms {
+            g_scalars: vec![Scalar::zero(); bits * max_m],
+            h_scalars: vec![Scalar::zero(); bits * max_m],
+            value_scalar: Scalar::zero(),
+            blinding_scalar: Scalar::zero(),
+            scalars: Vec::new(),
+            points: Vec::new(),
+        };
+        for (proof, commitments) in proofs {
+            proof.add_verification_terms(commitments, bits, domain, &mut terms, rng)?;
+        }
+
+        // The generators used for the inner product argument, as in the bulletproofs crate.
+        let g_generators = (0..max_m)
+            .flat_map(|j| bulletproof_generators(b'G', j, bits))
+            .collect::<Vec<_>>();
+        let h_generators = (0..max_m)
+            .flat_map(|j| bulletproof_generators(b'H', j, bits))
+            .collect::<Vec<_>>();
+
+        let result = RistrettoPoint::optional_multiscalar_mul(
+            terms
+                .scalars
+                .iter()
+                .chain(terms.g_scalars.iter())
+                .chain(terms.h_scalars.iter())
+                .chain([terms.value_scalar, terms.blinding_scalar].iter()),
+            terms
+                .points
+                .iter()
+                .map(|p| p.decompress())
+                .chain(g_generators.into_iter().map(Some))
+                .chain(h_generators.into_iter().map(Some))
+                .chain([
+                    Some(generators.value_generator),
+                    Some(generators.blinding_generator),
+                ]),
+        )
+        .ok_or(InvalidInput)?;
+
+        match result.is_identity() {
+            true => Ok(()),
+            false => Err(FastCryptoError::GeneralError(
+                "Failed to verify proof".to_string(),
+            )),
+        }
+    }
+
+    /// Prove that the value is in the range [lower, upper] (both inclusive). Returns a commitment
+    /// to the value with the given blinding factor and generators along with the proof.
+    ///
+    /// This is done by proving that both value - lower and upper - value are in [0, 2^bits) where
+    /// bits is the smallest of 8, 16, 32, 64 such that upper - lower < 2^bits.
+    pub fn prove_range(
+        value: u64,
+        blinding: &RistrettoScalar,
+        lower: u64,
+        upper: u64,
+        generators: &PedersenGenerators,
+        domain: &'static [u8],
+    ) -> FastCryptoResult<(PedersenCommitment, Self)> {
+        let bits = range_bit_length(lower, upper)?;
+        if value < lower || value > upper {
+            return Err(InvalidInput);
+        }
+
+        // The commitments to value - lower and upper - value are C - lower * G and
+        // upper * G - C, so the blinding factor of the latter is the negation of the original.
+        let (_, proof) = Self::prove_bit_length_multiple(
+            &[value - lower, upper - value],
+            &[*blinding, -*blinding],
+            bits,
+            generators,
+            domain,
+        )?;
+        let commitment = PedersenCommitment::new_with_generators(
+            &RistrettoScalar::from(value as u128),
+            blinding,
+            generators,
+        );
+        Ok((commitment, proof))
+    }
+
+    /// Verifies that the commitment is a Pedersen commitment with the given generators of a value
+    /// in the range [lower, upper] (both inclusive), using a proof created by
+    /// [BulletproofsRangeProof::prove_range].
+    pub fn verify_range(
+        &self,
+        commitment: &PedersenCommitment,
+        lower: u64,
+        upper: u64,
+        generators: &PedersenGenerators,
+        domain: &'static [u8],
+    ) -> FastCryptoResult<()> {
+        let bits = range_bit_length(lower, upper)?;
+        let lower_commitment = PedersenCommitment {
+            point: commitment.point - generators.value_generator * Scalar::from(lower),
+        };
+        let upper_commitment = PedersenCommitment {
+            point: generators.value_generator * Scalar::from(upper) - commitment.point,
+        };
+        self.verify_bit_length_multiple(
+            &[lower_commitment, upper_commitment],
+            bits,
+            generators,
+            domain,
+        )
+    }
+
+    /// Replay the transcript of this proof and add the terms of the verification equation,
+    /// weighted by a random scalar, to the given terms. This follows the verification in
+    /// [RangeProof::verify_multiple].
+    fn add_verification_terms<R: AllowedRng>(
+        &self,
+        commitments: &[PedersenCommitment],
+        bits: usize,
+        domain: &'static [u8],
+        terms: &mut VerificationTerms,
+        rng: &mut R,
+    ) -> FastCryptoResult<()> {
+        let commitments = pad_commitments(commitments);
+        let m = commitments.len();
+        let nm = bits * m;
+
+        // The serialization of a range proof is A, S, T_1, T_2, t_x, t_x_blinding, e_blinding
+        // followed by L_0, R_0, ..., L_{k-1}, R_{k-1}, a, b for the inner product proof.
+        let bytes = self.as_ref();
+        let lg_nm = nm.trailing_zeros() as usize;
+        if bytes.len() != 32 * (9 + 2 * lg_nm) {
+            return Err(InvalidInput);
+        }
+        let element = |i: usize| -> &[u8] { &bytes[32 * i..32 * (i + 1)] };
+        let point = |i: usize| CompressedRistretto::from_slice(element(i));
+        let scalar = |i: usize| {
+            Scalar::from_canonical_bytes(element(i).try_into().expect("Length is 32"))
+                .ok_or(InvalidInput)
+        };
+        let (a_point, s_point, t_1, t_2) = (point(0), point(1), point(2), point(3));
+        let (t_x, t_x_blinding, e_blinding) = (scalar(4)?, scalar(5)?, scalar(6)?);
+        let l_vec = (0..lg_nm).map(|i| point(7 + 2 * i)).collect::<Vec<_>>();
+        let r_vec = (0..lg_nm).map(|i| point(8 + 2 * i)).collect::<Vec<_>>();
+        let (a, b) = (scalar(7 + 2 * lg_nm)?, scalar(8 + 2 * lg_nm)?);
+
+        let mut transcript = Transcript::new(domain);
+        transcript.append_message(b"dom-sep", b"rangeproof v1");
+        transcript.append_u64(b"n", bits as u64);
+        transcript.append_u64(b"m", m as u64);
+        for commitment in commitments.iter() {
+            transcript.append_message(b"V", commitment.as_bytes());
+        }
+        validate_and_append_point(&mut transcript, b"A", &a_point)?;
+        validate_and_append_point(&mut transcript, b"S", &s_point)?;
+        let y = challenge_scalar(&mut transcript, b"y");
+        let z = challenge_scalar(&mut transcript, b"z");
+        validate_and_append_point(&mut transcript, b"T_1", &t_1)?;
+        validate_and_append_point(&mut transcript, b"T_2", &t_2)?;
+        let x = challenge_scalar(&mut transcript, b"x");
+        transcript.append_message(b"t_x", t_x.as_bytes());
+        transcript.append_message(b"t_x_blinding", t_x_blinding.as_bytes());
+        transcript.append_message(b"e_blinding", e_blinding.as_bytes());
+        let w = challenge_scalar(&mut transcript, b"w");
+
+        // Compute the challenges of the inner product proof and the scalars s_i.
+        transcript.append_message(b"dom-sep", b"ipp v1");
+        transcript.append_u64(b"n", nm as u64);
+        let mut challenges = Vec::with_capacity(lg_nm);
+        for (l, r) in l_vec.iter().zip(r_vec.iter()) {
+            validate_and_append_point(&mut transcript, b"L", l)?;
+            validate_and_append_point(&mut transcript, b"R", r)?;
+            challenges.push(challenge_scalar(&mut transcript, b"u"));
+        }
+        let mut challenges_inv = challenges.clone();
+        let all_inv = Scalar::batch_invert(&mut challenges_inv);
+        let challenges_sq = challenges.iter().map(|u| u * u).collect::<Vec<_>>();
+        let challenges_inv_sq = challenges_inv.iter().map(|u| u * u).collect::<Vec<_>>();
+        let mut s = Vec::with_capacity(nm);
+        s.push(all_inv);
+        for i in 1..nm {
+            let lg_i = (usize::BITS - 1 - i.leading_zeros()) as usize;
+            let k = 1 << lg_i;
+            s.push(s[i - k] * challenges_sq[(lg_nm - 1) - lg_i]);
+        }
+
+        // Random weights for the batching of the statements within this proof and across proofs.
+        let c = Scalar::random(rng);
+        let weight = Scalar::random(rng);
+
+        let zz = z * z;
+        let y_inv = y.invert();
+        let powers_of_2 = powers(Scalar::from(2u64), bits);
+        let powers_of_z = powers(z, m);
+        let mut exp_y_inv = Scalar::one();
+        for i in 0..nm {
+            let g = -z - a * s[i];
+            let h = z + exp_y_inv
+                * (zz * powers_of_z[i / bits] * powers_of_2[i % bits] - b * s[nm - 1 - i]);
+            terms.g_scalars[i] += weight * g;
+            terms.h_scalars[i] += weight * h;
+            exp_y_inv *= y_inv;
+        }
+
+        let delta = (z - zz) * powers(y, nm).iter().sum::<Scalar>()
+            - zz * z * powers_of_2.iter().sum::<Scalar>() * powers_of_z.iter().sum::<Scalar>();
+        terms.value_scalar += weight * (w * (t_x - a * b) + c * (delta - t_x));
+        terms.blinding_scalar += weight * (-e_blinding - c * t_x_blinding);
+
+        terms
+            .scalars
+            .extend([weight, weight * x, weight * c * x, weight * c * x * x]);
+        terms.points.extend([a_point, s_point, t_1, t_2]);
+        terms
+            .scalars
+            .extend(challenges_sq.iter().map(|u| weight * u));
+        terms.points.extend(l_vec);
+        terms
+            .scalars
+            .extend(challenges_inv_sq.iter().map(|u| weight * u));
+        terms.points.extend(r_vec);
+        terms
+            .scalars
+            .extend(powers_of_z.iter().map(|z_j| weight * c * zz * z_j));
+        terms.points.extend(commitments);
+        Ok(())
+    }
+}
+
+/// The scalars and points of a combined verification equation for multiple range proofs. The
+/// scalars for the generators shared between proofs are accumulated separately.
+struct VerificationTerms {
+    g_scalars: Vec<Scalar>,
+    h_scalars: Vec<Scalar>,
+    value_scalar: Scalar,
+    blinding_scalar: Scalar,
+    scalars: Vec<Scalar>,
+    points: Vec<CompressedRistretto>,
+}
+
+fn is_valid_bit_length(bits: usize) -> bool {
+    bits == 8 || bits == 16 || bits == 32 || bits == 64
+}
+
+/// Return the smallest valid bit length such that upper - lower < 2^bits.
+fn range_bit_length(lower: u64, upper: u64) -> FastCryptoResult<usize> {
+    if lower > upper {
+        return Err(InvalidInput);
+    }
+    let difference = upper - lower;
+    Ok([8, 16, 32]
+        .into_iter()
+        .find(|bits| difference < 1 << bits)
+        .unwrap_or(64))
+}
+
+/// Compress the commitments and pad them with the identity to a power of two.
+fn pad_commitments(commitments: &[PedersenCommitment]) -> Vec<CompressedRistretto> {
+    let m = commitments.len().next_power_of_two();
+    commitments
+        .iter()
+        .map(|c| c.point.compress())
+        .chain(std::iter::repeat(CompressedRistretto::identity()))
+        .take(m)
+        .collect()
+}
+
+/// Compute the first n generators for the given party as done by [BulletproofGens].
+fn bulletproof_generators(label: u8, party: usize, n: usize) -> Vec<RistrettoPoint> {
+    let mut hash = Shake256::default();
+    hash.update(b"GeneratorsChain");
+    hash.update([label]);
+    hash.update((party as u32).to_le_bytes());
+    hash.finalize(64 * n)
+        .chunks_exact(64)
+        .map(|bytes| RistrettoPoint::from_uniform_bytes(bytes.try_into().expect("Length is 64")))
+        .collect()
+}
+
+fn powers(x: Scalar, n: usize) -> Vec<Scalar> {
+    std::iter::successors(Some(Scalar::one()), |p| Some(p * x))
+        .take(n)
+        .collect()
+}
+
+fn challenge_scalar(transcript: &mut Transcript, label: &'static [u8]) -> Scalar {
+    let mut bytes = [0u8; 64];
+    transcript.challenge_bytes(label, &mut bytes);
+    Scalar::from_bytes_mod_order_wide(&bytes)
+}
+
+fn validate_and_append_point(
+    transcript: &mut Transcript,
+    label: &'static [u8],
+    point: &CompressedRistretto,
+) -> FastCryptoResult<()> {
+    if point.is_identity() {
+        return Err(FastCryptoError::GeneralError(
+            "Failed to verify proof".to_string(),
+        ));
+    }
+    transcript.append_message(label, point.as_bytes());
+    Ok(())
 }
 
 impl AsRef<[u8]> for BulletproofsRangeProof {
@@ -5,9 +5,12 @@ use crate::bls12381::min_pk::DST_G2;
 use crate::bls12381::min_sig::DST_G1;
 use crate::encoding::{Encoding, Hex};
 use crate::error::{FastCryptoError, FastCryptoError::InvalidInput, FastCryptoResult};
+use crate::groups::hash_to_curve::{EncodeToCurve, HashToCurve, HashToField};
+use crate::groups::multiplier::constant_time::{private, select_limbs, ConstantTimeSelect};
+use crate::groups::multiplier::{pippenger, ToLittleEndianBytes};
 use crate::groups::{
-    FiatShamirChallenge, FromTrustedByteArray, GroupElement, HashToGroupElement, MultiScalarMul,
-    Pairing, Scalar as ScalarType,
+    BatchNormalize, FiatShamirChallenge, FromTrustedByteArray, GroupElement, HashToGroupElement,
+    MultiScalarMul, Pairing, Scalar as ScalarType,
 };
 use crate::serde_helpers::BytesRepresentation;
 use crate::serde_helpers::ToFromByteArray;
@@ -15,17 +18,19 @@ use crate::traits::AllowedRng;
 use crate::utils::log2_byte;
 use crate::{generate_bytes_representation, serialize_deserialize_with_to_from_byte_array};
 use blst::{
-    blst_bendian_from_scalar, blst_final_exp, blst_fp, blst_fp12, blst_fp12_inverse, blst_fp12_mul,
-    blst_fp12_one, blst_fp12_sqr, blst_fp_from_bendian, blst_fr, blst_fr_add, blst_fr_cneg,
-    blst_fr_from_scalar, blst_fr_from_uint64, blst_fr_inverse, blst_fr_mul, blst_fr_rshift,
-    blst_fr_sub, blst_hash_to_g1, blst_hash_to_g2, blst_lendian_from_scalar, blst_miller_loop,
-    blst_p1, blst_p1_add_or_double, blst_p1_affine, blst_p1_cneg, blst_p1_compress,
-    blst_p1_deserialize, blst_p1_from_affine, blst_p1_in_g1, blst_p1_mult, blst_p1_serialize,
-    blst_p1_to_affine, blst_p1_uncompress, blst_p1s_add, blst_p2, blst_p2_add_or_double,
-    blst_p2_affine, blst_p2_cneg, blst_p2_compress, blst_p2_from_affine, blst_p2_in_g2,
-    blst_p2_mult, blst_p2_to_affine, blst_p2_uncompress, blst_scalar, blst_scalar_fr_check,
-    blst_scalar_from_be_bytes, blst_scalar_from_bendian, blst_scalar_from_fr, p1_affines,
-    p2_affines, BLS12_381_G1, BLS12_381_G2, BLST_ERROR,
+    blst_bendian_from_fp, blst_bendian_from_scalar, blst_encode_to_g1, blst_encode_to_g2,
+    blst_final_exp, blst_fp, blst_fp12, blst_fp12_inverse, blst_fp12_mul, blst_fp12_one,
+    blst_fp12_sqr, blst_fp2, blst_fp2_add, blst_fp2_cneg, blst_fp2_inverse, blst_fp2_mul,
+    blst_fp2_mul_by_3, blst_fp2_sqr, blst_fp_add, blst_fp_from_bendian, blst_fp_sub, blst_fr,
+    blst_fr_add, blst_fr_cneg, blst_fr_from_scalar, blst_fr_from_uint64, blst_fr_inverse,
+    blst_fr_mul, blst_fr_rshift, blst_fr_sub, blst_hash_to_g1, blst_hash_to_g2,
+    blst_lendian_from_scalar, blst_miller_loop, blst_p1, blst_p1_add_or_double, blst_p1_affine,
+    blst_p1_cneg, blst_p1_compress, blst_p1_deserialize, blst_p1_from_affine, blst_p1_in_g1,
+    blst_p1_mult, blst_p1_serialize, blst_p1_to_affine, blst_p1_uncompress, blst_p1s_add, blst_p2,
+    blst_p2_add_or_double, blst_p2_affine, blst_p2_cneg, blst_p2_compress, blst_p2_from_affine,
+    blst_p2_in_g2, blst_p2_mult, blst_p2_to_affine, blst_p2_uncompress, blst_scalar,
+    blst_scalar_fr_check, blst_scalar_from_be_bytes, blst_scalar_from_bendian, blst_scalar_from_fr,
+    p1_affines, p2_affines, BLS12_381_G1, BLS12_381_G2, BLST_ERROR,
 };
 use fastcrypto_derive::GroupOpsExtend;
 use hex_literal::hex;
@@ -59,6 +64,7 @@ pub const SCALAR_LENGTH: usize = 32;
 pub const G1_ELEMENT_BYTE_LENGTH: usize = 48;
 pub const G2_ELEMENT_BYTE_LENGTH: usize = 96;
 pub const GT_ELEMENT_BYTE_LENGTH: usize = 576;
+pub const GT_ELEMENT_COMPRESSED_BYTE_LENGTH: usize = 192;
 pub const FP_BYTE_LENGTH: usize = 48;
 
 impl Add for G1Element {
@@ -201,6 +207,57 @@ fn to_blst_type_slice<From, To>(points: &[From]) -> &[To] {
     unsafe { std::slice::from_raw_parts(points.as_ptr() as *const To, points.len()) }
 }
 
+impl BatchNormalize for G1Element {
+    fn batch_normalize(elements: &mut [Self]) {
+        // blst does not handle the point at infinity in batch conversions, so we skip those.
+        let non_zero = elements
+            .iter()
+            .filter(|p| **p != Self::zero())
+            .copied()
+            .collect::<Vec<_>>();
+        if non_zero.is_empty() {
+            return;
+        }
+        let affine = p1_affines::from(to_blst_type_slice(&non_zero));
+        for (element, affine) in elements
+            .iter_mut()
+            .filter(|p| **p != Self::zero())
+            .zip(affine.as_slice())
+        {
+            unsafe {
+                blst_p1_from_affine(&mut element.0, affine);
+            }
+        }
+    }
+}
+
+fn select_fp(a: &blst_fp, b: &blst_fp, choice: u8) -> blst_fp {
+    blst_fp {
+        l: select_limbs(&a.l, &b.l, choice),
+    }
+}
+
+fn select_fp2(a: &blst_fp2, b: &blst_fp2, choice: u8) -> blst_fp2 {
+    blst_fp2 {
+        fp: [
+            select_fp(&a.fp[0], &b.fp[0], choice),
+            select_fp(&a.fp[1], &b.fp[1], choice),
+        ],
+    }
+}
+
+impl private::Sealed for G1Element {}
+
+impl ConstantTimeSelect for G1Element {
+    fn select(a: &Self, b: &Self, choice: u8) -> Self {
+        Self(blst_p1 {
+            x: select_fp(&a.0.x, &b.0.x, choice),
+            y: select_fp(&a.0.y, &b.0.y, choice),
+            z: select_fp(&a.0.z, &b.0.z, choice),
+        })
+    }
+}
+
 impl GroupElement for G1Element {
     type ScalarType = Scalar;
 
@@ -234,6 +291,8 @@ impl Pairing for G1Element {
         GTElement(res)
     }
 
+    /// Compute the sum of the pairings by accumulating the Miller loops of all pairs and doing a
+    /// single final exponentiation.
     fn multi_pairing(
         points_g1: &[Self],
         points_g2: &[Self::Other],
@@ -241,51 +300,108 @@ impl Pairing for G1Element {
     where
         <Self as Pairing>::Output: GroupElement,
     {
-        if points_g1.len() != points_g2.len() {
-            return Err(FastCryptoError::InvalidInput);
-        }
+        Ok(match accumulate_miller_loops(points_g1, points_g2)? {
+            Some(mut blst_pairing) => GTElement(blst_pairing.as_fp12().final_exp()),
+            None => <Self as Pairing>::Output::zero(),
+        })
+    }
 
-        let (points_g1, points_g2): (Vec<_>, Vec<_>) = points_g1
-            .iter()
-            .zip(points_g2.iter())
-            .filter(|(&g1, &g2)| g1 != G1Element::zero() && g2 != G2Element::zero())
-            .map(|(&g1, &g2)| (g1, g2))
-            .unzip();
+    /// Check whether the sum of the pairings is the identity without computing it explicitly.
+    fn multi_pairing_is_one(points_g1: &[Self], points_g2: &[Self::Other]) -> FastCryptoResult<bool>
+    where
+        <Self as Pairing>::Output: GroupElement,
+    {
+        Ok(match accumulate_miller_loops(points_g1, points_g2)? {
+            Some(mut blst_pairing) => {
+                blst_pairing.commit();
+                blst_pairing.finalverify(None)
+            }
+            None => true,
+        })
+    }
+}
 
-        if points_g1.is_empty() {
-            return Ok(<Self as Pairing>::Output::zero());
-        }
+/// Accumulate the Miller loops of all pairs where both elements are non-zero. Returns None if
+/// there are no such pairs, in which case the sum of the pairings is the identity.
+fn accumulate_miller_loops(
+    points_g1: &[G1Element],
+    points_g2: &[G2Element],
+) -> FastCryptoResult<Option<blst::Pairing>> {
+    if points_g1.len() != points_g2.len() {
+        return Err(FastCryptoError::InvalidInput);
+    }
 
-        let mut blst_pairing = blst::Pairing::new(false, &[]);
-        for (g1, g2) in points_g1.iter().zip(points_g2.iter()) {
-            let mut g1_affine = blst_p1_affine::default();
-            let mut g2_affine = blst_p2_affine::default();
-            unsafe {
-                blst_p1_to_affine(&mut g1_affine, &g1.0);
-                blst_p2_to_affine(&mut g2_affine, &g2.0);
-            }
-            blst_pairing.raw_aggregate(&g2_affine, &g1_affine);
-        }
-        let result = blst_pairing.as_fp12().final_exp();
-        Ok(GTElement(result))
+    let (points_g1, points_g2): (Vec<_>, Vec<_>) = points_g1
+        .iter()
+        .zip(points_g2.iter())
+        .filter(|(&g1, &g2)| g1 != G1Element::zero() && g2 != G2Element::zero())
+        .map(|(&g1, &g2)| (g1, g2))
+        .unzip();
+
+    if points_g1.is_empty() {
+        return Ok(None);
     }
+
+    // Convert all points to affine form using a single inversion per group.
+    let affines_g1 = p1_affines::from(to_blst_type_slice(&points_g1));
+    let affines_g2 = p2_affines::from(to_blst_type_slice(&points_g2));
+
+    let mut blst_pairing = blst::Pairing::new(false, &[]);
+    for (g1, g2) in affines_g1.as_slice().iter().zip(affines_g2.as_slice()) {
+        blst_pairing.raw_aggregate(g2, g1);
+    }
+    Ok(Some(blst_pairing))
 }
 
 impl HashToGroupElement for G1Element {
     fn hash_to_group_element(msg: &[u8]) -> Self {
+        Self::hash_to_curve(msg, DST_G1).expect("DST_G1 is not empty")
+    }
+}
+
+impl HashToCurve for G1Element {
+    const SUITE_ID: &'static str = "BLS12381G1_XMD:SHA-256_SSWU_RO_";
+
+    fn hash_to_curve(msg: &[u8], dst: &[u8]) -> FastCryptoResult<Self> {
+        if dst.is_empty() {
+            return Err(InvalidInput);
+        }
         let mut res = blst_p1::default();
         unsafe {
             blst_hash_to_g1(
                 &mut res,
                 msg.as_ptr(),
                 msg.len(),
-                DST_G1.as_ptr(),
-                DST_G1.len(),
+                dst.as_ptr(),
+                dst.len(),
+                ptr::null(),
+                0,
+            );
+        }
+        Ok(Self(res))
+    }
+}
+
+impl EncodeToCurve for G1Element {
+    const SUITE_ID: &'static str = "BLS12381G1_XMD:SHA-256_SSWU_NU_";
+
+    fn encode_to_curve(msg: &[u8], dst: &[u8]) -> FastCryptoResult<Self> {
+        if dst.is_empty() {
+            return Err(InvalidInput);
+        }
+        let mut res = blst_p1::default();
+        unsafe {
+            blst_encode_to_g1(
+                &mut res,
+                msg.as_ptr(),
+                msg.len(),
+                dst.as_ptr(),
+                dst.len(),
                 ptr::null(),
                 0,
             );
         }
-        Self(res)
+        Ok(Self(res))
     }
 }
 
@@ -546,6 +662,42 @@ impl MultiScalarMul for G2Element {
     }
 }
 
+impl BatchNormalize for G2Element {
+    fn batch_normalize(elements: &mut [Self]) {
+        // blst does not handle the point at infinity in batch conversions, so we skip those.
+        let non_zero = elements
+            .iter()
+            .filter(|p| **p != Self::zero())
+            .copied()
+            .collect::<Vec<_>>();
+        if non_zero.is_empty() {
+            return;
+        }
+        let affine = p2_affines::from(to_blst_type_slice(&non_zero));
+        for (element, affine) in elements
+            .iter_mut()
+            .filter(|p| **p != Self::zero())
+            .zip(affine.as_slice())
+        {
+            unsafe {
+                blst_p2_from_affine(&mut element.0, affine);
+            }
+        }
+    }
+}
+
+impl private::Sealed for G2Element {}
+
+impl ConstantTimeSelect for G2Element {
+    fn select(a: &Self, b: &Self, choice: u8) -> Self {
+        Self(blst_p2 {
+            x: select_fp2(&a.0.x, &b.0.x, choice),
+            y: select_fp2(&a.0.y, &b.0.y, choice),
+            z: select_fp2(&a.0.z, &b.0.z, choice),
+        })
+    }
+}
+
 impl GroupElement for G2Element {
     type ScalarType = Scalar;
 
@@ -564,19 +716,53 @@ impl GroupElement for G2Element {
 
 impl HashToGroupElement for G2Element {
     fn hash_to_group_element(msg: &[u8]) -> Self {
+        Self::hash_to_curve(msg, DST_G2).expect("DST_G2 is not empty")
+    }
+}
+
+impl HashToCurve for G2Element {
+    const SUITE_ID: &'static str = "BLS12381G2_XMD:SHA-256_SSWU_RO_";
+
+    fn hash_to_curve(msg: &[u8], dst: &[u8]) -> FastCryptoResult<Self> {
+        if dst.is_empty() {
+            return Err(InvalidInput);
+        }
         let mut res = blst_p2::default();
         unsafe {
             blst_hash_to_g2(
                 &mut res,
                 msg.as_ptr(),
                 msg.len(),
-                DST_G2.as_ptr(),
-                DST_G2.len(),
+                dst.as_ptr(),
+                dst.len(),
+                ptr::null(),
+                0,
+            );
+        }
+        Ok(Self(res))
+    }
+}
+
+impl EncodeToCurve for G2Element {
+    const SUITE_ID: &'static str = "BLS12381G2_XMD:SHA-256_SSWU_NU_";
+
+    fn encode_to_curve(msg: &[u8], dst: &[u8]) -> FastCryptoResult<Self> {
+        if dst.is_empty() {
+            return Err(InvalidInput);
+        }
+        let mut res = blst_p2::default();
+        unsafe {
+            blst_encode_to_g2(
+                &mut res,
+                msg.as_ptr(),
+                msg.len(),
+                dst.as_ptr(),
+                dst.len(),
                 ptr::null(),
                 0,
             );
         }
-        Self(res)
+        Ok(Self(res))
     }
 }
 
@@ -725,8 +911,30 @@ impl GTElement {
     }
 }
 
+impl MultiScalarMul for GTElement {
+    /// blst does not have a multi-scalar multiplication for GT, so this uses the generic
+    /// implementation of Pippenger's algorithm.
+    fn multi_scalar_mul(scalars: &[Self::ScalarType], points: &[Self]) -> FastCryptoResult<Self> {
+        pippenger::multi_scalar_mul(scalars, points)
+    }
+}
+
 const P_AS_BYTES: [u8; FP_BYTE_LENGTH] = hex!("1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab");
 
+/// Parse a big-endian encoded field element, rejecting non-canonical encodings.
+fn fp_from_canonical_bendian(bytes: &[u8]) -> FastCryptoResult<blst_fp> {
+    // We compare with P_AS_BYTES to ensure that we process a canonical representation which uses
+    // mod p elements.
+    if bytes.len() != FP_BYTE_LENGTH || *bytes >= P_AS_BYTES[..] {
+        return Err(FastCryptoError::InvalidInput);
+    }
+    let mut fp = blst_fp::default();
+    unsafe {
+        blst_fp_from_bendian(&mut fp, bytes.as_ptr());
+    }
+    Ok(fp)
+}
+
 // Note that the serialization below is uncompressed, i.e. it uses 576 bytes.
 impl FromTrustedByteArray<GT_ELEMENT_BYTE_LENGTH> for GTElement {
     fn from_trusted_byte_array(bytes: &[u8; GT_ELEMENT_BYTE_LENGTH]) -> FastCryptoResult<Self> {
@@ -737,17 +945,8 @@ impl FromTrustedByteArray<GT_ELEMENT_BYTE_LENGTH> for GTElement {
         for i in 0..3 {
             for j in 0..2 {
                 for k in 0..2 {
-                    let mut fp = blst_fp::default();
-                    let slice = &bytes[current..current + FP_BYTE_LENGTH];
-                    // We compare with P_AS_BYTES to ensure that we process a canonical representation
-                    // which uses mod p elements.
-                    if *slice >= P_AS_BYTES[..] {
-                        return Err(FastCryptoError::InvalidInput);
-                    }
-                    unsafe {
-                        blst_fp_from_bendian(&mut fp, slice.as_ptr());
-                    }
-                    gt.fp6[j].fp2[i].fp[k] = fp;
+                    gt.fp6[j].fp2[i].fp[k] =
+                        fp_from_canonical_bendian(&bytes[current..current + FP_BYTE_LENGTH])?;
                     current += FP_BYTE_LENGTH;
                 }
             }
@@ -780,6 +979,138 @@ impl Debug for GTElement {
 
 serialize_deserialize_with_to_from_byte_array!(GTElement);
 
+/// A compressed serialization of a GT element which is a third of the size of the serialization
+/// used by `GTElement::serialize`, but which is slower to compute and to deserialize.
+///
+/// GT is a subgroup of the algebraic torus T6(Fp2) whose elements can be represented by two Fp2
+/// elements. Writing Fp12 = Fp6\[w\] with w^2 = v and Fp6 = Fp2\[v\] with v^3 = ξ = u + 1, any
+/// element other than the identity can be written as g = (c + w) / (c - w) for a unique
+/// c = c0 + c1 v + c2 v^2 in Fp6, and since g has norm one over Fp4 we have 3 * c0 * c1 = 3 * ξ *
+/// c2^2 + 1, so c0 can be recovered from c1 and c2. Since c1 = 0 implies that -1/(3ξ) is a square
+/// in Fp2, which it is not, c1 is never zero and the identity is encoded as c1 = c2 = 0.
+///
+/// The serialization is c1 || c2, where an Fp2 element a + bu is serialized as a || b and each
+/// field element is big-endian.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct GTElementCompressed(pub(crate) [u8; GT_ELEMENT_COMPRESSED_BYTE_LENGTH]);
+
+impl From<&GTElement> for GTElementCompressed {
+    fn from(element: &GTElement) -> Self {
+        let mut bytes = [0u8; GT_ELEMENT_COMPRESSED_BYTE_LENGTH];
+        if *element == GTElement::zero() {
+            return GTElementCompressed(bytes);
+        }
+
+        // Write g = g0 + g1 w and compute c = (1 + g0) / g1. Here, g1 is non-zero because the only
+        // element of GT in Fp6 is the identity.
+        let mut numerator = blst_fp12::default();
+        numerator.fp6[0] = element.0.fp6[0];
+        let mut denominator = blst_fp12::default();
+        denominator.fp6[0] = element.0.fp6[1];
+        let mut c = blst_fp12::default();
+        unsafe {
+            let one = *blst_fp12_one();
+            blst_fp2_add(
+                &mut numerator.fp6[0].fp2[0],
+                &element.0.fp6[0].fp2[0],
+                &one.fp6[0].fp2[0],
+            );
+            blst_fp12_inverse(&mut denominator, &denominator);
+            blst_fp12_mul(&mut c, &numerator, &denominator);
+        }
+
+        let fps = c.fp6[0].fp2[1].fp.iter().chain(c.fp6[0].fp2[2].fp.iter());
+        for (fp, chunk) in fps.zip(bytes.chunks_exact_mut(FP_BYTE_LENGTH)) {
+            unsafe {
+                blst_bendian_from_fp(chunk.as_mut_ptr(), fp);
+            }
+        }
+        GTElementCompressed(bytes)
+    }
+}
+
+impl TryFrom<&GTElementCompressed> for GTElement {
+    type Error = FastCryptoError;
+
+    fn try_from(value: &GTElementCompressed) -> Result<Self, Self::Error> {
+        let mut c1 = blst_fp2::default();
+        let mut c2 = blst_fp2::default();
+        for (fp, chunk) in c1
+            .fp
+            .iter_mut()
+            .chain(c2.fp.iter_mut())
+            .zip(value.0.chunks_exact(FP_BYTE_LENGTH))
+        {
+            *fp = fp_from_canonical_bendian(chunk)?;
+        }
+
+        if c1 == blst_fp2::default() {
+            return match c2 == blst_fp2::default() {
+                true => Ok(GTElement::zero()),
+                false => Err(InvalidInput),
+            };
+        }
+
+        let mut c = blst_fp12::default();
+        let mut numerator = blst_fp12::default();
+        let mut denominator = blst_fp12::default();
+        unsafe {
+            let one = (*blst_fp12_one()).fp6[0].fp2[0];
+
+            // c0 = (3 * ξ * c2^2 + 1) / (3 * c1)
+            let mut t = blst_fp2::default();
+            blst_fp2_sqr(&mut t, &c2);
+            t = fp2_mul_by_nonresidue(&t);
+            blst_fp2_mul_by_3(&mut t, &t);
+            blst_fp2_add(&mut t, &t, &one);
+            let mut c0 = blst_fp2::default();
+            blst_fp2_mul_by_3(&mut c0, &c1);
+            blst_fp2_inverse(&mut c0, &c0);
+            blst_fp2_mul(&mut c0, &c0, &t);
+            c.fp6[0].fp2 = [c0, c1, c2];
+
+            // g = (c + w) / (c - w)
+            numerator.fp6[0] = c.fp6[0];
+            numerator.fp6[1].fp2[0] = one;
+            denominator.fp6[0] = c.fp6[0];
+            blst_fp2_cneg(&mut denominator.fp6[1].fp2[0], &one, true);
+            blst_fp12_inverse(&mut denominator, &denominator);
+            blst_fp12_mul(&mut c, &numerator, &denominator);
+        }
+
+        // The element is in the torus by construction, but it may not be in the prime order
+        // subgroup.
+        match c.in_group() {
+            true => Ok(GTElement(c)),
+            false => Err(InvalidInput),
+        }
+    }
+}
+
+impl GTElementCompressed {
+    /// Create a new `GTElementCompressed` from a byte array. The input is validated when it is
+    /// converted to a [GTElement].
+    pub fn from_byte_array(bytes: [u8; GT_ELEMENT_COMPRESSED_BYTE_LENGTH]) -> Self {
+        Self(bytes)
+    }
+
+    /// Get the byte array representation of this element.
+    pub fn into_byte_array(self) -> [u8; GT_ELEMENT_COMPRESSED_BYTE_LENGTH] {
+        self.0
+    }
+}
+
+/// Multiply an Fp2 element by the non-residue ξ = u + 1 used to define Fp6.
+fn fp2_mul_by_nonresidue(a: &blst_fp2) -> blst_fp2 {
+    let mut ret = blst_fp2::default();
+    unsafe {
+        blst_fp_sub(&mut ret.fp[0], &a.fp[0], &a.fp[1]);
+        blst_fp_add(&mut ret.fp[1], &a.fp[0], &a.fp[1]);
+    }
+    ret
+}
+
 impl GroupElement for Scalar {
     type ScalarType = Self;
 
@@ -916,6 +1247,14 @@ pub fn buffer_to_scalar_mod_r(buffer: &[u8]) -> FastCryptoResult<Scalar> {
     Ok(Scalar(ret))
 }
 
+impl HashToField for Scalar {
+    const L: usize = 48;
+
+    fn from_uniform_bytes(bytes: &[u8]) -> Self {
+        buffer_to_scalar_mod_r(bytes).expect("Length is L")
+    }
+}
+
 impl FiatShamirChallenge for Scalar {
     fn fiat_shamir_reduction_to_group_element(uniform_buffer: &[u8]) -> Self {
         reduce_mod_uniform_buffer(uniform_buffer)
@@ -959,6 +1298,18 @@ impl ToFromByteArray<SCALAR_LENGTH> for Scalar {
     }
 }
 
+impl ToLittleEndianBytes for Scalar {
+    fn to_le_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![0u8; SCALAR_LENGTH];
+        unsafe {
+            let mut scalar = blst_scalar::default();
+            blst_scalar_from_fr(&mut scalar, &self.0);
+            blst_lendian_from_scalar(bytes.as_mut_ptr(), &scalar);
+        }
+        bytes
+    }
+}
+
 impl Debug for Scalar {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let bytes = Hex::encode(self.to_byte_array());
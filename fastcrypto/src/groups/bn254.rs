@@ -0,0 +1,485 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Implementation of the BN254 (aka alt_bn128) pairing-friendly curve used by Ethereum and Circom.
+//! The groups G1, G2 and GT all have prime order
+//! r = 21888242871839275222246405745257275088548364400416034343698204186575808495617.
+//!
+//! Points and scalars are serialized as in the Ethereum precompiles defined in EIP-196 and EIP-197:
+//! Field elements are encoded as 32-byte big-endian integers, a G1 point as x || y, and a G2 point
+//! as x_im || x_re || y_im || y_re. The identity is encoded as all zeros.
+
+use crate::error::{FastCryptoError, FastCryptoResult};
+use crate::groups::hash_to_curve::{expand_message_xmd, HashToField};
+use crate::groups::multiplier::ToLittleEndianBytes;
+use crate::groups::{
+    BatchNormalize, Doubling, FiatShamirChallenge, GroupElement, HashToGroupElement,
+    MultiScalarMul, Pairing, Scalar as ScalarTrait,
+};
+use crate::hash::Sha256;
+use crate::serde_helpers::ToFromByteArray;
+use crate::serialize_deserialize_with_to_from_byte_array;
+use crate::traits::AllowedRng;
+use ark_bn254::{Bn254, Fq, Fq12, Fq2, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::pairing::{Pairing as ArkPairing, PairingOutput};
+use ark_ec::{AffineRepr, CurveGroup, Group, VariableBaseMSM};
+use ark_ff::{BigInteger, Field, One, PrimeField, UniformRand, Zero};
+use ark_serialize::CanonicalDeserialize;
+use derive_more::{Add, From, Neg, Sub};
+use fastcrypto_derive::GroupOpsExtend;
+use once_cell::sync::OnceCell;
+use serde::{de, Deserialize};
+use std::ops::{Div, Mul};
+use zeroize::Zeroize;
+
+pub const SCALAR_LENGTH: usize = 32;
+pub const FP_BYTE_LENGTH: usize = 32;
+pub const G1_ELEMENT_BYTE_LENGTH: usize = 2 * FP_BYTE_LENGTH;
+pub const G2_ELEMENT_BYTE_LENGTH: usize = 4 * FP_BYTE_LENGTH;
+pub const GT_ELEMENT_BYTE_LENGTH: usize = 12 * FP_BYTE_LENGTH;
+
+/// The domain separation tag used by [HashToGroupElement] for [G1Element].
+pub const HASH_TO_G1_DST: &[u8] = b"FASTCRYPTO-V01-CS01-with-BN254G1_XMD:SHA-256_TAI_RO_";
+
+/// The domain separation tag used by [HashToGroupElement] for [G2Element].
+pub const HASH_TO_G2_DST: &[u8] = b"FASTCRYPTO-V01-CS01-with-BN254G2_XMD:SHA-256_TAI_RO_";
+
+/// Elements of the group G1 in BN254.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, From, Add, Sub, Neg, GroupOpsExtend)]
+pub struct G1Element(pub(crate) G1Projective);
+
+/// Elements of the group G2 in BN254.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, From, Add, Sub, Neg, GroupOpsExtend)]
+pub struct G2Element(pub(crate) G2Projective);
+
+/// Elements of the subgroup GT of F_q^{12} in BN254. Note that it is written in additive notation
+/// here.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, From, Add, Sub, Neg, GroupOpsExtend)]
+pub struct GTElement(pub(crate) PairingOutput<Bn254>);
+
+/// A scalar modulo the order r of the groups G1, G2 and GT.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, From, Add, Sub, Neg, GroupOpsExtend)]
+pub struct Scalar(pub(crate) Fr);
+
+impl GroupElement for G1Element {
+    type ScalarType = Scalar;
+
+    fn zero() -> Self {
+        Self(G1Projective::zero())
+    }
+
+    fn generator() -> Self {
+        Self(G1Projective::generator())
+    }
+}
+
+impl BatchNormalize for G1Element {
+    fn batch_normalize(elements: &mut [Self]) {
+        let affine =
+            G1Projective::normalize_batch(&elements.iter().map(|p| p.0).collect::<Vec<_>>());
+        elements
+            .iter_mut()
+            .zip(affine)
+            .for_each(|(p, a)| *p = Self(a.into()));
+    }
+}
+
+impl Doubling for G1Element {
+    fn double(self) -> Self {
+        Self(self.0.double())
+    }
+}
+
+impl Mul<Scalar> for G1Element {
+    type Output = Self;
+
+    fn mul(self, rhs: Scalar) -> Self {
+        Self(self.0 * rhs.0)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Div<Scalar> for G1Element {
+    type Output = FastCryptoResult<Self>;
+
+    fn div(self, rhs: Scalar) -> Self::Output {
+        Ok(self * rhs.inverse()?)
+    }
+}
+
+impl MultiScalarMul for G1Element {
+    fn multi_scalar_mul(scalars: &[Self::ScalarType], points: &[Self]) -> FastCryptoResult<Self> {
+        if scalars.len() != points.len() || scalars.is_empty() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let points = G1Projective::normalize_batch(&points.iter().map(|p| p.0).collect::<Vec<_>>());
+        let scalars = scalars.iter().map(|s| s.0).collect::<Vec<_>>();
+        Ok(Self(
+            G1Projective::msm(&points, &scalars).expect("Lengths are checked above"),
+        ))
+    }
+}
+
+impl Pairing for G1Element {
+    type Other = G2Element;
+    type Output = GTElement;
+
+    fn pairing(&self, other: &Self::Other) -> <Self as Pairing>::Output {
+        GTElement(Bn254::pairing(self.0, other.0))
+    }
+
+    /// Compute the sum of the pairings using a single final exponentiation.
+    fn multi_pairing(
+        points_g1: &[Self],
+        points_g2: &[Self::Other],
+    ) -> FastCryptoResult<<Self as Pairing>::Output> {
+        if points_g1.len() != points_g2.len() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(GTElement(Bn254::multi_pairing(
+            points_g1.iter().map(|p| p.0),
+            points_g2.iter().map(|p| p.0),
+        )))
+    }
+}
+
+impl HashToGroupElement for G1Element {
+    /// Hash to G1 using try-and-increment. This is not constant time, so it should only be used
+    /// with public inputs.
+    fn hash_to_group_element(msg: &[u8]) -> Self {
+        try_and_increment(msg, HASH_TO_G1_DST, 2 * FP_BYTE_LENGTH + 1, |bytes| {
+            G1Affine::get_point_from_x_unchecked(
+                Fq::from_be_bytes_mod_order(&bytes[..2 * FP_BYTE_LENGTH]),
+                bytes[2 * FP_BYTE_LENGTH] & 1 == 1,
+            )
+            .map(|p| Self(p.into_group()))
+        })
+    }
+}
+
+impl ToFromByteArray<G1_ELEMENT_BYTE_LENGTH> for G1Element {
+    fn from_byte_array(bytes: &[u8; G1_ELEMENT_BYTE_LENGTH]) -> FastCryptoResult<Self> {
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(Self::zero());
+        }
+        let x = fq_from_be_bytes(&bytes[..FP_BYTE_LENGTH])?;
+        let y = fq_from_be_bytes(&bytes[FP_BYTE_LENGTH..])?;
+        // G1 has cofactor 1, so all points on the curve are in the group.
+        let point = G1Affine::new_unchecked(x, y);
+        if !point.is_on_curve() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(Self(point.into_group()))
+    }
+
+    fn to_byte_array(&self) -> [u8; G1_ELEMENT_BYTE_LENGTH] {
+        let mut bytes = [0u8; G1_ELEMENT_BYTE_LENGTH];
+        if let Some((x, y)) = self.0.into_affine().xy() {
+            bytes[..FP_BYTE_LENGTH].copy_from_slice(&fq_to_be_bytes(x));
+            bytes[FP_BYTE_LENGTH..].copy_from_slice(&fq_to_be_bytes(y));
+        }
+        bytes
+    }
+}
+
+serialize_deserialize_with_to_from_byte_array!(G1Element);
+
+impl GroupElement for G2Element {
+    type ScalarType = Scalar;
+
+    fn zero() -> Self {
+        Self(G2Projective::zero())
+    }
+
+    fn generator() -> Self {
+        Self(G2Projective::generator())
+    }
+}
+
+impl BatchNormalize for G2Element {
+    fn batch_normalize(elements: &mut [Self]) {
+        let affine =
+            G2Projective::normalize_batch(&elements.iter().map(|p| p.0).collect::<Vec<_>>());
+        elements
+            .iter_mut()
+            .zip(affine)
+            .for_each(|(p, a)| *p = Self(a.into()));
+    }
+}
+
+impl Doubling for G2Element {
+    fn double(self) -> Self {
+        Self(self.0.double())
+    }
+}
+
+impl Mul<Scalar> for G2Element {
+    type Output = Self;
+
+    fn mul(self, rhs: Scalar) -> Self {
+        Self(self.0 * rhs.0)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Div<Scalar> for G2Element {
+    type Output = FastCryptoResult<Self>;
+
+    fn div(self, rhs: Scalar) -> Self::Output {
+        Ok(self * rhs.inverse()?)
+    }
+}
+
+impl MultiScalarMul for G2Element {
+    fn multi_scalar_mul(scalars: &[Self::ScalarType], points: &[Self]) -> FastCryptoResult<Self> {
+        if scalars.len() != points.len() || scalars.is_empty() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let points = G2Projective::normalize_batch(&points.iter().map(|p| p.0).collect::<Vec<_>>());
+        let scalars = scalars.iter().map(|s| s.0).collect::<Vec<_>>();
+        Ok(Self(
+            G2Projective::msm(&points, &scalars).expect("Lengths are checked above"),
+        ))
+    }
+}
+
+impl HashToGroupElement for G2Element {
+    /// Hash to G2 using try-and-increment followed by cofactor clearing. This is not constant time,
+    /// so it should only be used with public inputs.
+    fn hash_to_group_element(msg: &[u8]) -> Self {
+        try_and_increment(msg, HASH_TO_G2_DST, 4 * FP_BYTE_LENGTH + 1, |bytes| {
+            let x = Fq2::new(
+                Fq::from_be_bytes_mod_order(&bytes[..2 * FP_BYTE_LENGTH]),
+                Fq::from_be_bytes_mod_order(&bytes[2 * FP_BYTE_LENGTH..4 * FP_BYTE_LENGTH]),
+            );
+            G2Affine::get_point_from_x_unchecked(x, bytes[4 * FP_BYTE_LENGTH] & 1 == 1)
+                .map(|p| Self(p.clear_cofactor().into_group()))
+                .filter(|p| *p != Self::zero())
+        })
+    }
+}
+
+impl ToFromByteArray<G2_ELEMENT_BYTE_LENGTH> for G2Element {
+    fn from_byte_array(bytes: &[u8; G2_ELEMENT_BYTE_LENGTH]) -> FastCryptoResult<Self> {
+        if bytes.iter().all(|b| *b == 0) {
+            return Ok(Self::zero());
+        }
+        let x = fq2_from_be_bytes(&bytes[..2 * FP_BYTE_LENGTH])?;
+        let y = fq2_from_be_bytes(&bytes[2 * FP_BYTE_LENGTH..])?;
+        let point = G2Affine::new_unchecked(x, y);
+        if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(Self(point.into_group()))
+    }
+
+    fn to_byte_array(&self) -> [u8; G2_ELEMENT_BYTE_LENGTH] {
+        let mut bytes = [0u8; G2_ELEMENT_BYTE_LENGTH];
+        if let Some((x, y)) = self.0.into_affine().xy() {
+            bytes[..2 * FP_BYTE_LENGTH].copy_from_slice(&fq2_to_be_bytes(x));
+            bytes[2 * FP_BYTE_LENGTH..].copy_from_slice(&fq2_to_be_bytes(y));
+        }
+        bytes
+    }
+}
+
+serialize_deserialize_with_to_from_byte_array!(G2Element);
+
+impl GroupElement for GTElement {
+    type ScalarType = Scalar;
+
+    fn zero() -> Self {
+        Self(PairingOutput::zero())
+    }
+
+    fn generator() -> Self {
+        static G: OnceCell<PairingOutput<Bn254>> = OnceCell::new();
+        Self(*G.get_or_init(PairingOutput::generator))
+    }
+}
+
+impl Mul<Scalar> for GTElement {
+    type Output = Self;
+
+    fn mul(self, rhs: Scalar) -> Self {
+        Self(self.0 * rhs.0)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Div<Scalar> for GTElement {
+    type Output = FastCryptoResult<Self>;
+
+    fn div(self, rhs: Scalar) -> Self::Output {
+        Ok(self * rhs.inverse()?)
+    }
+}
+
+// Note that the serialization below is uncompressed, i.e. it uses 384 bytes. The coefficients are
+// serialized as big-endian integers in the order c0.c0.c0, c0.c0.c1, c0.c1.c0, ..., c1.c2.c1.
+impl ToFromByteArray<GT_ELEMENT_BYTE_LENGTH> for GTElement {
+    fn from_byte_array(bytes: &[u8; GT_ELEMENT_BYTE_LENGTH]) -> FastCryptoResult<Self> {
+        let coefficients = bytes
+            .chunks_exact(FP_BYTE_LENGTH)
+            .map(fq_from_be_bytes)
+            .collect::<FastCryptoResult<Vec<_>>>()?;
+        let fq2 = |i: usize| Fq2::new(coefficients[2 * i], coefficients[2 * i + 1]);
+        let f = Fq12::new(
+            ark_bn254::Fq6::new(fq2(0), fq2(1), fq2(2)),
+            ark_bn254::Fq6::new(fq2(3), fq2(4), fq2(5)),
+        );
+        // Check that the element is in the subgroup of order r.
+        if f.is_zero() || !f.pow(Fr::MODULUS).is_one() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(Self(PairingOutput(f)))
+    }
+
+    fn to_byte_array(&self) -> [u8; GT_ELEMENT_BYTE_LENGTH] {
+        let f = self.0 .0;
+        let mut bytes = [0u8; GT_ELEMENT_BYTE_LENGTH];
+        [f.c0.c0, f.c0.c1, f.c0.c2, f.c1.c0, f.c1.c1, f.c1.c2]
+            .iter()
+            .flat_map(|c| [c.c0, c.c1])
+            .zip(bytes.chunks_exact_mut(FP_BYTE_LENGTH))
+            .for_each(|(c, chunk)| chunk.copy_from_slice(&fq_to_be_bytes(&c)));
+        bytes
+    }
+}
+
+serialize_deserialize_with_to_from_byte_array!(GTElement);
+
+impl GroupElement for Scalar {
+    type ScalarType = Self;
+
+    fn zero() -> Self {
+        Self(Fr::zero())
+    }
+
+    fn generator() -> Self {
+        Self(Fr::one())
+    }
+}
+
+impl Mul<Scalar> for Scalar {
+    type Output = Self;
+
+    fn mul(self, rhs: Scalar) -> Self {
+        Self(self.0 * rhs.0)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Div<Scalar> for Scalar {
+    type Output = FastCryptoResult<Self>;
+
+    fn div(self, rhs: Scalar) -> Self::Output {
+        Ok(self * rhs.inverse()?)
+    }
+}
+
+impl From<u128> for Scalar {
+    fn from(value: u128) -> Self {
+        Self(Fr::from(value))
+    }
+}
+
+impl ScalarTrait for Scalar {
+    fn rand<R: AllowedRng>(rng: &mut R) -> Self {
+        Self(Fr::rand(rng))
+    }
+
+    fn inverse(&self) -> FastCryptoResult<Self> {
+        Ok(Self(self.0.inverse().ok_or(FastCryptoError::InvalidInput)?))
+    }
+}
+
+impl ToFromByteArray<SCALAR_LENGTH> for Scalar {
+    /// Deserialize a scalar from its canonical big-endian representation.
+    fn from_byte_array(bytes: &[u8; SCALAR_LENGTH]) -> FastCryptoResult<Self> {
+        let mut le_bytes = *bytes;
+        le_bytes.reverse();
+        Ok(Self(
+            Fr::deserialize_uncompressed(le_bytes.as_slice())
+                .map_err(|_| FastCryptoError::InvalidInput)?,
+        ))
+    }
+
+    /// Serialize a scalar as a big-endian integer.
+    fn to_byte_array(&self) -> [u8; SCALAR_LENGTH] {
+        self.0
+            .into_bigint()
+            .to_bytes_be()
+            .try_into()
+            .expect("Scalars are 32 bytes")
+    }
+}
+
+serialize_deserialize_with_to_from_byte_array!(Scalar);
+
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl FiatShamirChallenge for Scalar {
+    /// Reduce the given buffer, interpreted as a big-endian integer, modulo the group order. The
+    /// buffer should be at least 48 bytes long for the result to be close to uniform.
+    fn fiat_shamir_reduction_to_group_element(uniform_buffer: &[u8]) -> Self {
+        Self(Fr::from_be_bytes_mod_order(uniform_buffer))
+    }
+}
+
+impl HashToField for Scalar {
+    const L: usize = 48;
+
+    fn from_uniform_bytes(bytes: &[u8]) -> Self {
+        Self(Fr::from_be_bytes_mod_order(bytes))
+    }
+}
+
+impl ToLittleEndianBytes for Scalar {
+    fn to_le_bytes(&self) -> Vec<u8> {
+        self.0.into_bigint().to_bytes_le()
+    }
+}
+
+/// Hash the message and a counter to `length` bytes until `map` returns a point. Each attempt
+/// succeeds with probability about 1/2, so it is safe to assume that one of the 256 attempts does.
+fn try_and_increment<G>(
+    msg: &[u8],
+    dst: &[u8],
+    length: usize,
+    map: impl Fn(&[u8]) -> Option<G>,
+) -> G {
+    (0..=u8::MAX)
+        .find_map(|counter| {
+            let bytes = expand_message_xmd::<Sha256, 32>(&[msg, &[counter]].concat(), dst, length)
+                .expect("DST is not empty and length is small");
+            map(&bytes)
+        })
+        .expect("The probability that all attempts fail is negligible")
+}
+
+/// Deserialize a canonical big-endian encoding of a base field element.
+fn fq_from_be_bytes(bytes: &[u8]) -> FastCryptoResult<Fq> {
+    let mut le_bytes = bytes.to_vec();
+    le_bytes.reverse();
+    Fq::deserialize_uncompressed(le_bytes.as_slice()).map_err(|_| FastCryptoError::InvalidInput)
+}
+
+fn fq_to_be_bytes(x: &Fq) -> Vec<u8> {
+    x.into_bigint().to_bytes_be()
+}
+
+/// Deserialize an element of Fq2 encoded as im || re as in EIP-197.
+fn fq2_from_be_bytes(bytes: &[u8]) -> FastCryptoResult<Fq2> {
+    let im = fq_from_be_bytes(&bytes[..FP_BYTE_LENGTH])?;
+    let re = fq_from_be_bytes(&bytes[FP_BYTE_LENGTH..])?;
+    Ok(Fq2::new(re, im))
+}
+
+fn fq2_to_be_bytes(x: &Fq2) -> Vec<u8> {
+    [fq_to_be_bytes(&x.c1), fq_to_be_bytes(&x.c0)].concat()
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Implementation of the Edwards form of Curve25519 as used by Ed25519. The curve has order 8 * l,
+//! where l = 2^{252} + 27742317777372353535851937790883648493 is the order of the prime order
+//! subgroup generated by the base point, so unlike [crate::groups::ristretto255], points may have
+//! a small order component. Protocols which need a prime order group must check points using
+//! [EdwardsPoint::is_torsion_free] or clear the cofactor using [EdwardsPoint::mul_by_cofactor].
+//!
+//! Points can be converted to and from [Ed25519PublicKey]'s and scalars can be derived from
+//! [Ed25519PrivateKey]'s, so protocols over this group can produce standard Ed25519 signatures.
+
+use crate::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
+use crate::error::{FastCryptoError, FastCryptoResult};
+use crate::groups::ristretto255::RistrettoScalar;
+use crate::groups::{Doubling, GroupElement, MultiScalarMul, Scalar as ScalarTrait};
+use crate::hash::{HashFunction, Sha512};
+use crate::serde_helpers::ToFromByteArray;
+use crate::serialize_deserialize_with_to_from_byte_array;
+use crate::traits::ToFromBytes;
+use curve25519_dalek_ng::constants::ED25519_BASEPOINT_POINT;
+use curve25519_dalek_ng::edwards::{CompressedEdwardsY, EdwardsPoint as ExternalEdwardsPoint};
+use curve25519_dalek_ng::traits::{Identity, VartimeMultiscalarMul};
+use derive_more::{Add, From, Neg, Sub};
+use fastcrypto_derive::GroupOpsExtend;
+use serde::{de, Deserialize};
+use std::ops::{Div, Mul};
+
+/// The length of a compressed point in bytes.
+pub const POINT_SIZE_IN_BYTES: usize = 32;
+
+/// Scalars modulo the order of the base point. These are the same as the scalars of the
+/// ristretto255 group, and they use the same little-endian encoding as Ed25519 signatures.
+pub type Scalar = RistrettoScalar;
+
+/// A point on the Edwards form of Curve25519.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, From, Add, Sub, Neg, GroupOpsExtend)]
+pub struct EdwardsPoint(ExternalEdwardsPoint);
+
+impl EdwardsPoint {
+    /// Returns true if this point has small order, i.e., if it is in the torsion subgroup of order
+    /// 8. Ed25519 public keys and nonces of small order should be rejected by most protocols.
+    pub fn is_small_order(&self) -> bool {
+        self.0.is_small_order()
+    }
+
+    /// Returns true if this point is in the prime order subgroup generated by the base point.
+    pub fn is_torsion_free(&self) -> bool {
+        self.0.is_torsion_free()
+    }
+
+    /// Multiply this point by the cofactor 8. The result is always in the prime order subgroup.
+    pub fn mul_by_cofactor(&self) -> Self {
+        Self(self.0.mul_by_cofactor())
+    }
+
+    /// Return this point in compressed form.
+    pub fn compress(&self) -> [u8; POINT_SIZE_IN_BYTES] {
+        self.0.compress().to_bytes()
+    }
+
+    /// Decompress a point. Non-canonical encodings of the y-coordinate are rejected, but points of
+    /// small order are accepted.
+    pub fn decompress(bytes: &[u8; POINT_SIZE_IN_BYTES]) -> FastCryptoResult<Self> {
+        let point = CompressedEdwardsY(*bytes)
+            .decompress()
+            .ok_or(FastCryptoError::InvalidInput)?;
+        if point.compress().as_bytes() != bytes {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(Self(point))
+    }
+}
+
+impl Doubling for EdwardsPoint {
+    fn double(self) -> Self {
+        Self(self.0 + self.0)
+    }
+}
+
+impl GroupElement for EdwardsPoint {
+    type ScalarType = Scalar;
+
+    fn zero() -> Self {
+        Self(ExternalEdwardsPoint::identity())
+    }
+
+    fn generator() -> Self {
+        Self(ED25519_BASEPOINT_POINT)
+    }
+}
+
+impl Mul<Scalar> for EdwardsPoint {
+    type Output = EdwardsPoint;
+
+    fn mul(self, rhs: Scalar) -> EdwardsPoint {
+        Self(self.0 * rhs.0)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Div<Scalar> for EdwardsPoint {
+    type Output = Result<EdwardsPoint, FastCryptoError>;
+
+    /// Note that division is only well-defined for points in the prime order subgroup.
+    fn div(self, rhs: Scalar) -> Self::Output {
+        Ok(self * rhs.inverse()?)
+    }
+}
+
+impl MultiScalarMul for EdwardsPoint {
+    fn multi_scalar_mul(scalars: &[Self::ScalarType], points: &[Self]) -> FastCryptoResult<Self> {
+        if scalars.len() != points.len() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Ok(Self(ExternalEdwardsPoint::vartime_multiscalar_mul(
+            scalars.iter().map(|s| s.0),
+            points.iter().map(|p| p.0),
+        )))
+    }
+}
+
+impl ToFromByteArray<POINT_SIZE_IN_BYTES> for EdwardsPoint {
+    fn from_byte_array(bytes: &[u8; POINT_SIZE_IN_BYTES]) -> FastCryptoResult<Self> {
+        Self::decompress(bytes)
+    }
+
+    fn to_byte_array(&self) -> [u8; POINT_SIZE_IN_BYTES] {
+        self.compress()
+    }
+}
+
+serialize_deserialize_with_to_from_byte_array!(EdwardsPoint);
+
+impl From<&Ed25519PublicKey> for EdwardsPoint {
+    /// Public keys are not checked for small order or torsion, so callers should use
+    /// [EdwardsPoint::is_small_order] or [EdwardsPoint::is_torsion_free] if needed.
+    fn from(public_key: &Ed25519PublicKey) -> Self {
+        Self(
+            CompressedEdwardsY::from_slice(public_key.as_ref())
+                .decompress()
+                .expect("Public keys are valid points"),
+        )
+    }
+}
+
+impl From<&EdwardsPoint> for Ed25519PublicKey {
+    fn from(point: &EdwardsPoint) -> Self {
+        Ed25519PublicKey::from_bytes(&point.compress()).expect("Compressed points are valid")
+    }
+}
+
+impl From<&Ed25519PrivateKey> for Scalar {
+    /// Derive the secret scalar of an Ed25519 private key as defined in section 5.1.5 of RFC 8032,
+    /// such that the public key is this scalar times the base point.
+    fn from(private_key: &Ed25519PrivateKey) -> Self {
+        let h = Sha512::digest(private_key.as_ref()).digest;
+        let mut bytes: [u8; 32] = h[..32].try_into().expect("Digest has length 64");
+        bytes[0] &= 248;
+        bytes[31] &= 127;
+        bytes[31] |= 64;
+        Scalar::from_bytes_mod_order(&bytes)
+    }
+}
@@ -0,0 +1,208 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Implementation of the generic parts of [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380) for
+//! hashing to elliptic curves and finite fields with a caller-chosen domain separation tag (DST).
+//!
+//! Each protocol should use its own DST, as described in section 3.1 of the RFC, so hashes computed
+//! in one protocol cannot be reused in another one. The curve specific suites are implemented by the
+//! groups through the [HashToCurve] and [EncodeToCurve] traits, and scalars can be derived using
+//! [hash_to_field].
+//!
+//! # Example
+//! ```rust
+//! # use fastcrypto::groups::bls12381::{G1Element, Scalar};
+//! # use fastcrypto::groups::hash_to_curve::{hash_to_field, ExpandMsgXmd, HashToCurve};
+//! # use fastcrypto::hash::Sha256;
+//! let point = G1Element::hash_to_curve(b"Hello, world!", b"MY-PROTOCOL-V01-CS01").unwrap();
+//! let scalars =
+//!     hash_to_field::<Scalar, ExpandMsgXmd<Sha256, 32>>(b"Hello, world!", b"MY-PROTOCOL-V01-CS02", 2)
+//!         .unwrap();
+//! ```
+
+use crate::error::{FastCryptoError, FastCryptoResult};
+use crate::hash::{ExtendableOutputFunction, HashFunction, ReverseWrapper};
+use digest::core_api::{BlockSizeUser, CoreProxy};
+use std::marker::PhantomData;
+use typenum::Unsigned;
+
+/// Prefix used to hash DSTs which are longer than 255 bytes (see section 5.3.3 of RFC 9380).
+const OVERSIZE_DST_PREFIX: &[u8] = b"H2C-OVERSIZE-DST-";
+
+/// The maximal length of a DST before it is hashed.
+const MAX_DST_LENGTH: usize = 255;
+
+/// The maximal number of bytes that can be requested from an expander.
+const MAX_OUTPUT_LENGTH: usize = 65535;
+
+/// Trait for groups with a hash-to-curve suite from RFC 9380 giving a random oracle.
+pub trait HashToCurve: Sized {
+    /// The identifier of the suite, e.g., "BLS12381G1_XMD:SHA-256_SSWU_RO_".
+    const SUITE_ID: &'static str;
+
+    /// Hash the given message to a group element using the given domain separation tag, which must
+    /// be non-empty.
+    fn hash_to_curve(msg: &[u8], dst: &[u8]) -> FastCryptoResult<Self>;
+}
+
+/// Trait for groups with a nonuniform encoding from RFC 9380. This is faster than [HashToCurve], but
+/// the output is not uniformly distributed, so it should only be used if the protocol allows it.
+pub trait EncodeToCurve: Sized {
+    /// The identifier of the suite, e.g., "BLS12381G1_XMD:SHA-256_SSWU_NU_".
+    const SUITE_ID: &'static str;
+
+    /// Encode the given message as a group element using the given domain separation tag, which
+    /// must be non-empty.
+    fn encode_to_curve(msg: &[u8], dst: &[u8]) -> FastCryptoResult<Self>;
+}
+
+/// Trait for fields which elements can be derived from uniformly random bytes as in the
+/// hash_to_field function from section 5 of RFC 9380.
+pub trait HashToField: Sized {
+    /// The number of bytes used for each field element, called L in RFC 9380. This is
+    /// ceil((ceil(log2(p)) + k) / 8) where p is the order of the field and k is the security level.
+    const L: usize;
+
+    /// Interpret the given [HashToField::L] bytes as a big-endian integer and reduce it modulo the
+    /// order of the field.
+    fn from_uniform_bytes(bytes: &[u8]) -> Self;
+}
+
+/// A function to expand a message into a uniformly random byte string as defined in section 5.3 of
+/// RFC 9380.
+pub trait ExpandMessage {
+    /// Expand the given message into `len_in_bytes` bytes using the given domain separation tag.
+    fn expand_message(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> FastCryptoResult<Vec<u8>>;
+}
+
+/// The expand_message_xmd function using the given hash function. See [expand_message_xmd].
+pub struct ExpandMsgXmd<H, const DIGEST_LEN: usize>(PhantomData<H>);
+
+impl<H, const DIGEST_LEN: usize> ExpandMessage for ExpandMsgXmd<H, DIGEST_LEN>
+where
+    H: HashFunction<DIGEST_LEN> + ReverseWrapper,
+    <<H as ReverseWrapper>::Variant as CoreProxy>::Core: BlockSizeUser,
+{
+    fn expand_message(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> FastCryptoResult<Vec<u8>> {
+        expand_message_xmd::<H, DIGEST_LEN>(msg, dst, len_in_bytes)
+    }
+}
+
+/// The expand_message_xof function using the given extendable output function with security level
+/// `K` bits. See [expand_message_xof].
+pub struct ExpandMsgXof<X, const K: usize = 128>(PhantomData<X>);
+
+impl<X: ExtendableOutputFunction + Default, const K: usize> ExpandMessage for ExpandMsgXof<X, K> {
+    fn expand_message(msg: &[u8], dst: &[u8], len_in_bytes: usize) -> FastCryptoResult<Vec<u8>> {
+        expand_message_xof::<X>(msg, dst, len_in_bytes, K)
+    }
+}
+
+/// The expand_message_xmd function from section 5.3.1 of RFC 9380 using a Merkle-Damgård hash
+/// function like [crate::hash::Sha256]. The DST must be non-empty, and at most `255 * DIGEST_LEN`
+/// bytes can be requested.
+pub fn expand_message_xmd<H, const DIGEST_LEN: usize>(
+    msg: &[u8],
+    dst: &[u8],
+    len_in_bytes: usize,
+) -> FastCryptoResult<Vec<u8>>
+where
+    H: HashFunction<DIGEST_LEN> + ReverseWrapper,
+    <<H as ReverseWrapper>::Variant as CoreProxy>::Core: BlockSizeUser,
+{
+    let ell = len_in_bytes.div_ceil(DIGEST_LEN);
+    if dst.is_empty() || ell > 255 || len_in_bytes > MAX_OUTPUT_LENGTH {
+        return Err(FastCryptoError::InvalidInput);
+    }
+    let block_size =
+        <<<H as ReverseWrapper>::Variant as CoreProxy>::Core as BlockSizeUser>::BlockSize::USIZE;
+
+    let dst_prime = match dst.len() > MAX_DST_LENGTH {
+        true => {
+            let mut h = H::default();
+            h.update(OVERSIZE_DST_PREFIX);
+            h.update(dst);
+            dst_prime(h.finalize().as_ref())
+        }
+        false => dst_prime(dst),
+    };
+
+    let mut h = H::default();
+    h.update(vec![0u8; block_size]);
+    h.update(msg);
+    h.update((len_in_bytes as u16).to_be_bytes());
+    h.update([0u8]);
+    h.update(&dst_prime);
+    let b_0 = h.finalize();
+
+    let mut output = Vec::with_capacity(ell * DIGEST_LEN);
+    let mut b_i = [0u8; DIGEST_LEN];
+    for i in 1..=ell {
+        // b_1 = H(b_0 || 1 || DST_prime) and b_i = H((b_0 xor b_(i-1)) || i || DST_prime).
+        let mut h = H::default();
+        h.update(
+            b_0.digest
+                .iter()
+                .zip(b_i.iter())
+                .map(|(a, b)| a ^ b)
+                .collect::<Vec<_>>(),
+        );
+        h.update([i as u8]);
+        h.update(&dst_prime);
+        b_i = h.finalize().digest;
+        output.extend_from_slice(&b_i);
+    }
+    output.truncate(len_in_bytes);
+    Ok(output)
+}
+
+/// The expand_message_xof function from section 5.3.2 of RFC 9380 using an extendable output
+/// function like [crate::hash::Shake128] with a target security level of `k` bits, which is only
+/// used to hash DSTs longer than 255 bytes. The DST must be non-empty.
+pub fn expand_message_xof<X: ExtendableOutputFunction + Default>(
+    msg: &[u8],
+    dst: &[u8],
+    len_in_bytes: usize,
+    k: usize,
+) -> FastCryptoResult<Vec<u8>> {
+    if dst.is_empty() || len_in_bytes > MAX_OUTPUT_LENGTH {
+        return Err(FastCryptoError::InvalidInput);
+    }
+
+    let dst_prime = match dst.len() > MAX_DST_LENGTH {
+        true => {
+            let mut x = X::default();
+            x.update(OVERSIZE_DST_PREFIX);
+            x.update(dst);
+            dst_prime(&x.finalize((2 * k).div_ceil(8)))
+        }
+        false => dst_prime(dst),
+    };
+
+    let mut x = X::default();
+    x.update(msg);
+    x.update((len_in_bytes as u16).to_be_bytes());
+    x.update(&dst_prime);
+    Ok(x.finalize(len_in_bytes))
+}
+
+/// Returns DST || I2OSP(len(DST), 1). Assumes that the DST is at most 255 bytes.
+fn dst_prime(dst: &[u8]) -> Vec<u8> {
+    let mut dst_prime = dst.to_vec();
+    dst_prime.push(dst.len() as u8);
+    dst_prime
+}
+
+/// The hash_to_field function from section 5.2 of RFC 9380, which hashes the given message to
+/// `count` field elements using the given expander and domain separation tag.
+pub fn hash_to_field<F: HashToField, E: ExpandMessage>(
+    msg: &[u8],
+    dst: &[u8],
+    count: usize,
+) -> FastCryptoResult<Vec<F>> {
+    let uniform_bytes = E::expand_message(msg, dst, count * F::L)?;
+    Ok(uniform_bytes
+        .chunks_exact(F::L)
+        .map(F::from_uniform_bytes)
+        .collect())
+}
@@ -10,8 +10,13 @@ use std::fmt::Debug;
 use std::ops::{AddAssign, SubAssign};
 
 pub mod bls12381;
+pub mod bn254;
+pub mod ed25519;
+pub mod hash_to_curve;
 pub mod ristretto255;
+pub mod secp256k1;
 pub mod secp256r1;
+mod short_weierstrass;
 
 pub mod multiplier;
 
@@ -51,6 +56,27 @@ pub trait Scalar:
 {
     fn rand<R: AllowedRng>(rng: &mut R) -> Self;
     fn inverse(&self) -> FastCryptoResult<Self>;
+
+    /// Invert all the given scalars using Montgomery's trick which requires a single inversion and
+    /// 3(n-1) multiplications. Returns an error if any of the scalars is zero.
+    fn batch_inverse(scalars: &[Self]) -> FastCryptoResult<Vec<Self>> {
+        // Compute the prefix products, a_0 * ... * a_{i-1}, and the product of all scalars.
+        let mut prefix_products = Vec::with_capacity(scalars.len());
+        let product = scalars.iter().fold(Self::generator(), |acc, s| {
+            prefix_products.push(acc);
+            acc * s
+        });
+
+        // The product is zero if and only if one of the scalars is zero.
+        let mut inverse = product.inverse()?;
+        let mut result = vec![Self::zero(); scalars.len()];
+        for (i, s) in scalars.iter().enumerate().rev() {
+            // Here, inverse = (a_0 * ... * a_i)^{-1}.
+            result[i] = inverse * prefix_products[i];
+            inverse = inverse * s;
+        }
+        Ok(result)
+    }
 }
 
 /// Trait for group elements that has a fast doubling operation.
@@ -94,6 +120,15 @@ pub trait Pairing: GroupElement {
                 <Self as Pairing>::Output::add,
             ))
     }
+
+    /// Returns true if the sum of the pairings of the two slices of elements is the identity in
+    /// the target group. Returns an error if the slices have different lengths.
+    fn multi_pairing_is_one(points_g1: &[Self], points_g2: &[Self::Other]) -> FastCryptoResult<bool>
+    where
+        <Self as Pairing>::Output: GroupElement,
+    {
+        Ok(Self::multi_pairing(points_g1, points_g2)? == <Self as Pairing>::Output::zero())
+    }
 }
 
 /// Trait for groups that have a reduction from a random buffer to a group element that is secure
@@ -110,6 +145,16 @@ pub trait HashToGroupElement {
     fn hash_to_group_element(msg: &[u8]) -> Self;
 }
 
+/// Trait for group elements in projective coordinates which can be normalized, i.e., have their
+/// projective coordinates scaled such that Z = 1. This makes later conversions to affine
+/// coordinates, e.g. during serialization or pairings, cheaper. Implementations of
+/// [MultiScalarMul] already convert their inputs using a single inversion, so there is no need to
+/// normalize points before calling them.
+pub trait BatchNormalize: GroupElement {
+    /// Normalize all the given elements using a single field inversion.
+    fn batch_normalize(elements: &mut [Self]);
+}
+
 /// Trait for groups that support multi-scalar multiplication.
 pub trait MultiScalarMul: GroupElement {
     fn multi_scalar_mul(scalars: &[Self::ScalarType], points: &[Self]) -> FastCryptoResult<Self>;
@@ -0,0 +1,344 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::hint::black_box;
+
+use crate::groups::multiplier::integer_utils::{compute_base_2w_expansion, div_ceil};
+use crate::groups::multiplier::{ScalarMultiplier, ToLittleEndianBytes};
+use crate::groups::GroupElement;
+
+/// This scalar multiplier is intended for secret scalars, e.g. during key generation and signing.
+/// It uses a fixed-base comb method where a table of multiples of <i>2<sup>WINDOW_WIDTH * i</sup>
+/// base_element</i> is precomputed for every window <i>i</i> of the scalar, so a multiplication
+/// consists of one table lookup and one addition per window and no doublings.
+///
+/// Table lookups scan the entire table and select the right entry using [ConstantTimeSelect], so
+/// neither the control flow nor the memory access pattern of the lookups depend on the scalar. To
+/// avoid adding the identity element, which often has special-cased code paths, the table for
+/// window <i>i</i> contains the multiples <i>(d + 1) 2<sup>WINDOW_WIDTH * i</sup></i> of the base
+/// element for all digits <i>d</i>, and the sum of the offsets is subtracted at the end. The
+/// multiplication is constant time if the group operations for `G` are constant time. Note that
+/// this is not the case for the arkworks and blst backends used for [ProjectivePoint] and
+/// [G1Element]/[G2Element], whose additions are not constant time.
+///
+/// [ProjectivePoint]: crate::groups::secp256r1::ProjectivePoint
+/// [G1Element]: crate::groups::bls12381::G1Element
+/// [G2Element]: crate::groups::bls12381::G2Element
+///
+/// The table has <i>2<sup>WINDOW_WIDTH</sup></i> entries for each window, so there is a trade-off
+/// between the size of the table and the number of additions. A `WINDOW_WIDTH` of 4 is a good
+/// default for 256 bit scalars.
+pub struct ConstantTimeMultiplier<G, const WINDOW_WIDTH: usize> {
+    /// The i'th table contains the multiples (d + 1) * 2^{WINDOW_WIDTH * i} * base_element for
+    /// d = 0, ..., 2^WINDOW_WIDTH - 1.
+    tables: Vec<Vec<G>>,
+    /// The sum of 2^{WINDOW_WIDTH * i} * base_element over all windows.
+    offset: G,
+    /// The number of bytes in the little-endian representation of the scalars.
+    scalar_size: usize,
+}
+
+impl<G: GroupElement + ConstantTimeSelect, const WINDOW_WIDTH: usize>
+    ScalarMultiplier<G, G::ScalarType> for ConstantTimeMultiplier<G, WINDOW_WIDTH>
+where
+    G::ScalarType: ToLittleEndianBytes,
+{
+    fn new(base_element: G, zero: G) -> Self {
+        if WINDOW_WIDTH == 0 || WINDOW_WIDTH >= usize::BITS as usize {
+            panic!("WINDOW_WIDTH must be positive and smaller than the bit size of usize");
+        }
+        let scalar_size = G::ScalarType::zero().to_le_bytes().len();
+        let number_of_windows = div_ceil(8 * scalar_size, WINDOW_WIDTH);
+
+        let mut tables = Vec::with_capacity(number_of_windows);
+        let mut offset = zero;
+        let mut window_base = base_element;
+        for _ in 0..number_of_windows {
+            offset += window_base;
+            let mut table = Vec::with_capacity(1 << WINDOW_WIDTH);
+            table.push(window_base);
+            for d in 1..(1 << WINDOW_WIDTH) {
+                table.push(table[d - 1] + window_base);
+            }
+            // The last entry is 2^WINDOW_WIDTH * window_base which is the base for the next window.
+            window_base = table[table.len() - 1];
+            tables.push(table);
+        }
+
+        Self {
+            tables,
+            offset,
+            scalar_size,
+        }
+    }
+
+    fn mul(&self, scalar: &G::ScalarType) -> G {
+        let mut scalar_bytes = scalar.to_le_bytes();
+        scalar_bytes.resize(self.scalar_size, 0);
+        let digits = compute_base_2w_expansion(&scalar_bytes, WINDOW_WIDTH);
+
+        let mut result = constant_time_lookup(&self.tables[0], digits[0]);
+        for (table, digit) in self.tables.iter().zip(digits.iter()).skip(1) {
+            result += constant_time_lookup(table, *digit);
+        }
+        result - self.offset
+    }
+
+    /// Compute `self.base_element * base_scalar + other_element * other_scalar`. Note that the
+    /// second multiplication uses the multiplication of `G`, so this is only constant time if that
+    /// is.
+    fn two_scalar_mul(
+        &self,
+        base_scalar: &G::ScalarType,
+        other_element: &G,
+        other_scalar: &G::ScalarType,
+    ) -> G {
+        self.mul(base_scalar) + *other_element * other_scalar
+    }
+}
+
+/// Group elements where one of two elements can be selected without branching on the choice. This
+/// trait is sealed and only implemented for the group elements supported by
+/// [ConstantTimeMultiplier].
+pub trait ConstantTimeSelect: Copy + private::Sealed {
+    /// Return `a` if `choice` is 0 and `b` if `choice` is 1 without branching.
+    fn select(a: &Self, b: &Self, choice: u8) -> Self;
+}
+
+pub(crate) mod private {
+    pub trait Sealed {}
+}
+
+/// Select between two arrays of limbs without branching. Returns `a` if `choice` is 0 and `b` if
+/// `choice` is 1.
+pub(crate) fn select_limbs<const N: usize>(a: &[u64; N], b: &[u64; N], choice: u8) -> [u64; N] {
+    // Prevent the compiler from optimizing the masking into a branch.
+    let mask = black_box(0u64.wrapping_sub(choice as u64));
+    std::array::from_fn(|i| a[i] ^ (mask & (a[i] ^ b[i])))
+}
+
+/// Return `table[index]` by scanning the entire table.
+fn constant_time_lookup<G: ConstantTimeSelect>(table: &[G], index: usize) -> G {
+    let mut result = table[0];
+    for (i, element) in table.iter().enumerate().skip(1) {
+        result = G::select(&result, element, constant_time_eq(i, index));
+    }
+    result
+}
+
+/// Returns 1 if `a == b` and 0 otherwise without branching.
+fn constant_time_eq(a: usize, b: usize) -> u8 {
+    let x = a ^ b;
+    // The top bit of x | -x is set if and only if x is non-zero.
+    (1 ^ ((x | x.wrapping_neg()) >> (usize::BITS - 1))) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::ops::{Add, Div, Mul, Neg, Sub};
+
+    use fastcrypto_derive::GroupOpsExtend;
+    use rand::thread_rng;
+
+    use crate::error::FastCryptoError;
+    use crate::groups::bls12381::{G1Element, G2Element, Scalar as BlsScalar};
+    use crate::groups::ristretto255::{RistrettoPoint, RistrettoScalar};
+    use crate::groups::secp256r1::{ProjectivePoint, Scalar};
+    use crate::groups::Scalar as ScalarTrait;
+
+    use super::*;
+
+    fn test_scalar_multiplication<G: GroupElement + ConstantTimeSelect>()
+    where
+        G::ScalarType: ToLittleEndianBytes,
+    {
+        let scalars = [
+            G::ScalarType::zero(),
+            G::ScalarType::generator(),
+            G::ScalarType::from(2),
+            G::ScalarType::from(1234),
+            G::ScalarType::from(0xffffffffffffffff),
+            -G::ScalarType::generator(),
+            G::ScalarType::rand(&mut thread_rng()),
+        ];
+
+        let base_element = G::generator() * G::ScalarType::from(7);
+        let multiplier_1 = ConstantTimeMultiplier::<G, 1>::new(base_element, G::zero());
+        let multiplier_4 = ConstantTimeMultiplier::<G, 4>::new(base_element, G::zero());
+        let multiplier_7 = ConstantTimeMultiplier::<G, 7>::new(base_element, G::zero());
+        for scalar in scalars {
+            let expected = base_element * scalar;
+            assert_eq!(expected, multiplier_1.mul(&scalar));
+            assert_eq!(expected, multiplier_4.mul(&scalar));
+            assert_eq!(expected, multiplier_7.mul(&scalar));
+        }
+
+        let other_element = G::generator() * G::ScalarType::rand(&mut thread_rng());
+        let a = G::ScalarType::rand(&mut thread_rng());
+        let b = G::ScalarType::rand(&mut thread_rng());
+        assert_eq!(
+            base_element * a + other_element * b,
+            multiplier_4.two_scalar_mul(&a, &other_element, &b)
+        );
+    }
+
+    #[test]
+    fn test_scalar_multiplication_all_groups() {
+        test_scalar_multiplication::<RistrettoPoint>();
+        test_scalar_multiplication::<ProjectivePoint>();
+        test_scalar_multiplication::<G1Element>();
+        test_scalar_multiplication::<G2Element>();
+    }
+
+    #[test]
+    fn test_constant_time_lookup() {
+        let table: Vec<G1Element> = (1..=16)
+            .map(|i| G1Element::generator() * BlsScalar::from(i))
+            .collect();
+        for (i, element) in table.iter().enumerate() {
+            assert_eq!(constant_time_lookup(&table, i), *element);
+        }
+
+        assert_eq!(constant_time_eq(0, 0), 1);
+        assert_eq!(constant_time_eq(usize::MAX, usize::MAX), 1);
+        assert_eq!(constant_time_eq(0, 1), 0);
+        assert_eq!(constant_time_eq(0, usize::MAX), 0);
+        assert_eq!(constant_time_eq(1 << 63, 0), 0);
+
+        assert_eq!(select_limbs(&[1, 2], &[3, 4], 0), [1, 2]);
+        assert_eq!(select_limbs(&[1, 2], &[3, 4], 1), [3, 4]);
+    }
+
+    thread_local! {
+        /// The sequence of group operations performed by [CountingPoint]'s.
+        static OPERATIONS: RefCell<Vec<&'static str>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn record(operation: &'static str) {
+        OPERATIONS.with(|operations| operations.borrow_mut().push(operation));
+    }
+
+    fn take_operations() -> Vec<&'static str> {
+        OPERATIONS.with(|operations| operations.take())
+    }
+
+    /// A group element which records the group operations performed on it.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, GroupOpsExtend)]
+    struct CountingPoint(RistrettoPoint);
+
+    impl Add for CountingPoint {
+        type Output = Self;
+
+        fn add(self, rhs: Self) -> Self::Output {
+            record("add");
+            Self(self.0 + rhs.0)
+        }
+    }
+
+    impl Sub for CountingPoint {
+        type Output = Self;
+
+        fn sub(self, rhs: Self) -> Self::Output {
+            record("sub");
+            Self(self.0 - rhs.0)
+        }
+    }
+
+    impl Neg for CountingPoint {
+        type Output = Self;
+
+        fn neg(self) -> Self::Output {
+            record("neg");
+            Self(-self.0)
+        }
+    }
+
+    impl Mul<RistrettoScalar> for CountingPoint {
+        type Output = Self;
+
+        fn mul(self, rhs: RistrettoScalar) -> Self::Output {
+            record("mul");
+            Self(self.0 * rhs)
+        }
+    }
+
+    impl Div<RistrettoScalar> for CountingPoint {
+        type Output = Result<Self, FastCryptoError>;
+
+        fn div(self, rhs: RistrettoScalar) -> Self::Output {
+            record("div");
+            Ok(Self((self.0 / rhs)?))
+        }
+    }
+
+    impl private::Sealed for CountingPoint {}
+
+    impl ConstantTimeSelect for CountingPoint {
+        fn select(a: &Self, b: &Self, choice: u8) -> Self {
+            Self(RistrettoPoint::select(&a.0, &b.0, choice))
+        }
+    }
+
+    impl GroupElement for CountingPoint {
+        type ScalarType = RistrettoScalar;
+
+        fn zero() -> Self {
+            Self(RistrettoPoint::zero())
+        }
+
+        fn generator() -> Self {
+            Self(RistrettoPoint::generator())
+        }
+    }
+
+    #[test]
+    fn test_control_flow_is_independent_of_scalar() {
+        let multiplier = ConstantTimeMultiplier::<CountingPoint, 4>::new(
+            CountingPoint::generator(),
+            CountingPoint::zero(),
+        );
+        take_operations();
+
+        let scalars = [
+            RistrettoScalar::zero(),
+            RistrettoScalar::generator(),
+            RistrettoScalar::from(0xffffffffffffffff),
+            -RistrettoScalar::generator(),
+            RistrettoScalar::rand(&mut thread_rng()),
+            RistrettoScalar::rand(&mut thread_rng()),
+        ];
+
+        let mut traces = Vec::new();
+        for scalar in scalars {
+            assert_eq!(multiplier.mul(&scalar), CountingPoint::generator() * scalar);
+            // Remove the reference multiplication from the trace.
+            let mut trace = take_operations();
+            assert_eq!(trace.pop(), Some("mul"));
+            traces.push(trace);
+        }
+
+        // One addition per window after the first and a final subtraction of the offset.
+        assert_eq!(traces[0].len(), 64);
+        assert!(traces.iter().all(|trace| *trace == traces[0]));
+    }
+
+    #[test]
+    fn test_scalar_multiplication_secp256r1_edge_cases() {
+        // Scalars with many zero or one digits in a row.
+        let multiplier = ConstantTimeMultiplier::<ProjectivePoint, 5>::new(
+            ProjectivePoint::generator(),
+            ProjectivePoint::zero(),
+        );
+        for scalar in [
+            Scalar::from(1 << 120),
+            Scalar::from(u128::MAX),
+            Scalar::from(u128::MAX) * Scalar::from(u128::MAX),
+        ] {
+            assert_eq!(
+                multiplier.mul(&scalar),
+                ProjectivePoint::generator() * scalar
+            );
+        }
+    }
+}
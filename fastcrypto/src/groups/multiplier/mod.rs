@@ -6,7 +6,9 @@
 
 #[cfg(feature = "experimental")]
 pub mod bgmw;
+pub mod constant_time;
 mod integer_utils;
+pub mod pippenger;
 pub mod windowed;
 
 /// Trait for scalar multiplication for a fixed group element, e.g. by using precomputed values.
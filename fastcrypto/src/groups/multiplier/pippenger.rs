@@ -0,0 +1,230 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generic implementation of Pippenger's bucket method for multi-scalar multiplication. This may be
+//! used for groups which do not have a native multi-scalar multiplication.
+//!
+//! If the `rayon` feature is enabled, the windows are processed in parallel.
+
+use crate::error::{FastCryptoError, FastCryptoResult};
+use crate::groups::multiplier::integer_utils::{div_ceil, get_bits_from_bytes, log2};
+use crate::groups::multiplier::ToLittleEndianBytes;
+use crate::groups::GroupElement;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+/// Compute the linear combination of the given scalars and group elements using Pippenger's bucket
+/// method with signed digits. Returns an error if the inputs have different lengths or are empty.
+///
+/// This is not constant time, so it should only be used with public scalars.
+pub fn multi_scalar_mul<G: GroupElement + Send + Sync>(
+    scalars: &[G::ScalarType],
+    points: &[G],
+) -> FastCryptoResult<G>
+where
+    G::ScalarType: ToLittleEndianBytes,
+{
+    if scalars.len() != points.len() || scalars.is_empty() {
+        return Err(FastCryptoError::InvalidInput);
+    }
+
+    let window_width = optimal_window_width(scalars.len());
+    let scalar_bytes = scalars.iter().map(|s| s.to_le_bytes()).collect::<Vec<_>>();
+    let scalar_size = scalar_bytes
+        .iter()
+        .map(Vec::len)
+        .max()
+        .expect("Scalars are non-empty");
+
+    // Add a bit to the scalar size to make room for the carry from the top digit.
+    let number_of_windows = div_ceil(8 * scalar_size + 1, window_width);
+    let digits = scalar_bytes
+        .iter()
+        .map(|bytes| compute_signed_digits(bytes, window_width, number_of_windows))
+        .collect::<Vec<_>>();
+
+    let window_sum = |window: usize| {
+        sum_window(
+            points,
+            digits.iter().map(|d| d[window]),
+            1 << (window_width - 1),
+        )
+    };
+
+    #[cfg(feature = "rayon")]
+    let window_sums: Vec<G> = (0..number_of_windows)
+        .into_par_iter()
+        .map(window_sum)
+        .collect();
+    #[cfg(not(feature = "rayon"))]
+    let window_sums: Vec<G> = (0..number_of_windows).map(window_sum).collect();
+
+    // Combine the windows starting from the most significant one.
+    let mut result = window_sums[number_of_windows - 1];
+    for window_sum in window_sums.iter().rev().skip(1) {
+        for _ in 0..window_width {
+            result = result + result;
+        }
+        result += *window_sum;
+    }
+    Ok(result)
+}
+
+/// Compute the sum of digit * point over all pairs using buckets for each absolute value of the
+/// digits. The digits must be in the range [-number_of_buckets, number_of_buckets].
+fn sum_window<G: GroupElement>(
+    points: &[G],
+    digits: impl Iterator<Item = i64>,
+    number_of_buckets: usize,
+) -> G {
+    let mut buckets = vec![G::zero(); number_of_buckets];
+    for (point, digit) in points.iter().zip(digits) {
+        match digit {
+            0 => {}
+            d if d > 0 => buckets[d as usize - 1] += *point,
+            d => buckets[(-d) as usize - 1] -= *point,
+        }
+    }
+
+    // Compute sum_i (i + 1) * buckets[i] using a running sum.
+    let mut running_sum = G::zero();
+    let mut result = G::zero();
+    for bucket in buckets.iter().rev() {
+        running_sum += *bucket;
+        result += running_sum;
+    }
+    result
+}
+
+/// Compute the signed base 2^window_width digits of the integer represented by the given bytes in
+/// little-endian format. The digits are in the range [-2^{window_width - 1}, 2^{window_width - 1}],
+/// and the number of digits must be large enough to represent the input including a final carry.
+fn compute_signed_digits(bytes: &[u8], window_width: usize, number_of_digits: usize) -> Vec<i64> {
+    let radix = 1i64 << window_width;
+    let half_radix = radix >> 1;
+    let mut carry = 0;
+    let mut digits = Vec::with_capacity(number_of_digits);
+    for i in 0..number_of_digits {
+        let start = window_width * i;
+        let digit = if start < 8 * bytes.len() {
+            get_bits_from_bytes(bytes, start, start + window_width) as i64
+        } else {
+            0
+        } + carry;
+
+        // Shift digits in the upper half of the range down to a negative digit and carry one.
+        carry = (digit + half_radix - 1) / radix;
+        digits.push(digit - carry * radix);
+    }
+    debug_assert_eq!(carry, 0, "Too few digits");
+    digits
+}
+
+/// Return a window width which is approximately optimal for the given number of terms.
+fn optimal_window_width(number_of_terms: usize) -> usize {
+    if number_of_terms < 32 {
+        3
+    } else {
+        // This is approximately ln(n) + 2.
+        log2(number_of_terms) * 69 / 100 + 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::groups::bls12381::{G1Element, GTElement, Scalar as BlsScalar};
+    use crate::groups::ristretto255::{RistrettoPoint, RistrettoScalar};
+    use crate::groups::secp256r1::ProjectivePoint;
+    use crate::groups::{MultiScalarMul, Scalar};
+    use num_bigint::BigUint;
+    use rand::thread_rng;
+
+    fn test_against_native<G: GroupElement + MultiScalarMul + Send + Sync>()
+    where
+        G::ScalarType: ToLittleEndianBytes,
+    {
+        for n in [1, 2, 10, 33, 100, 300] {
+            let scalars = (0..n)
+                .map(|i| match i % 4 {
+                    0 => G::ScalarType::zero(),
+                    1 => -G::ScalarType::generator(),
+                    _ => G::ScalarType::rand(&mut thread_rng()),
+                })
+                .collect::<Vec<_>>();
+            let points = (0..n)
+                .map(|i| match i % 5 {
+                    0 => G::zero(),
+                    _ => G::generator() * G::ScalarType::rand(&mut thread_rng()),
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                multi_scalar_mul(&scalars, &points).unwrap(),
+                G::multi_scalar_mul(&scalars, &points).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn test_multi_scalar_mul() {
+        test_against_native::<RistrettoPoint>();
+        test_against_native::<ProjectivePoint>();
+        test_against_native::<G1Element>();
+    }
+
+    #[test]
+    fn test_multi_scalar_mul_gt() {
+        let scalars = (0..40)
+            .map(|_| BlsScalar::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let points = (0..40)
+            .map(|_| GTElement::generator() * BlsScalar::rand(&mut thread_rng()))
+            .collect::<Vec<_>>();
+        let expected = scalars
+            .iter()
+            .zip(points.iter())
+            .fold(GTElement::zero(), |acc, (s, p)| acc + *p * s);
+        assert_eq!(multi_scalar_mul(&scalars, &points).unwrap(), expected);
+        assert_eq!(
+            GTElement::multi_scalar_mul(&scalars, &points).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let scalars = [RistrettoScalar::generator(); 2];
+        let points = [RistrettoPoint::generator(); 3];
+        assert!(multi_scalar_mul(&scalars, &points).is_err());
+        assert!(multi_scalar_mul::<RistrettoPoint>(&[], &[]).is_err());
+    }
+
+    #[test]
+    fn test_signed_digits() {
+        for window_width in 1..10 {
+            for value in [0u64, 1, 2, 7, 8, 255, 256, 1234567, u64::MAX] {
+                let bytes = value.to_le_bytes();
+                let number_of_digits = div_ceil(8 * bytes.len() + 1, window_width);
+                let digits = compute_signed_digits(&bytes, window_width, number_of_digits);
+                assert_eq!(digits.len(), number_of_digits);
+
+                let half_radix = 1i64 << (window_width - 1);
+                assert!(digits.iter().all(|d| -half_radix <= *d && *d <= half_radix));
+
+                // Reconstruct the value from the digits.
+                let radix = BigUint::from(1u64 << window_width);
+                let (positive, negative) = digits.iter().rev().fold(
+                    (BigUint::from(0u64), BigUint::from(0u64)),
+                    |(positive, negative), d| {
+                        let (p, n) = match *d >= 0 {
+                            true => (*d as u64, 0),
+                            false => (0, d.unsigned_abs()),
+                        };
+                        (positive * &radix + p, negative * &radix + n)
+                    },
+                );
+                assert_eq!(positive - negative, BigUint::from(value));
+            }
+        }
+    }
+}
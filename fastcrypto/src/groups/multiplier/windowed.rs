@@ -239,16 +239,9 @@ mod tests {
     use crate::groups::secp256r1::{ProjectivePoint, Scalar};
     use crate::groups::GroupElement;
     use crate::groups::Scalar as ScalarTrait;
-    use crate::serde_helpers::ToFromByteArray;
 
     use super::*;
 
-    impl ToLittleEndianBytes for RistrettoScalar {
-        fn to_le_bytes(&self) -> Vec<u8> {
-            self.to_byte_array().to_vec()
-        }
-    }
-
     #[test]
     fn test_scalar_multiplication_ristretto() {
         let multiplier = WindowedScalarMultiplier::<RistrettoPoint, RistrettoScalar, 16, 4>::new(
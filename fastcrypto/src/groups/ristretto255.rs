@@ -5,6 +5,9 @@
 //! prime order 2^{252} + 27742317777372353535851937790883648493 built over Curve25519.
 
 use crate::error::FastCryptoResult;
+use crate::groups::hash_to_curve::{expand_message_xmd, HashToCurve, HashToField};
+use crate::groups::multiplier::constant_time::{private, ConstantTimeSelect};
+use crate::groups::multiplier::ToLittleEndianBytes;
 use crate::groups::{
     Doubling, FiatShamirChallenge, GroupElement, HashToGroupElement, MultiScalarMul, Scalar,
 };
@@ -24,6 +27,7 @@ use derive_more::{Add, Div, From, Neg, Sub};
 use fastcrypto_derive::GroupOpsExtend;
 use serde::{de, Deserialize};
 use std::ops::{Add, Div, Mul};
+use subtle_ng::{Choice, ConditionallySelectable};
 use zeroize::Zeroize;
 
 const RISTRETTO_POINT_BYTE_LENGTH: usize = 32;
@@ -31,7 +35,7 @@ const RISTRETTO_SCALAR_BYTE_LENGTH: usize = 32;
 
 /// Represents a point in the Ristretto group for Curve25519.
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq, From, Add, Sub, Neg, GroupOpsExtend)]
-pub struct RistrettoPoint(ExternalRistrettoPoint);
+pub struct RistrettoPoint(pub(crate) ExternalRistrettoPoint);
 
 impl RistrettoPoint {
     /// Construct a RistrettoPoint from the given data using an Ristretto-flavoured Elligator 2 map.
@@ -96,6 +100,18 @@ impl Mul<RistrettoScalar> for RistrettoPoint {
     }
 }
 
+impl private::Sealed for RistrettoPoint {}
+
+impl ConstantTimeSelect for RistrettoPoint {
+    fn select(a: &Self, b: &Self, choice: u8) -> Self {
+        Self(ExternalRistrettoPoint::conditional_select(
+            &a.0,
+            &b.0,
+            Choice::from(choice),
+        ))
+    }
+}
+
 impl GroupElement for RistrettoPoint {
     type ScalarType = RistrettoScalar;
 
@@ -125,6 +141,17 @@ impl HashToGroupElement for RistrettoPoint {
     }
 }
 
+impl HashToCurve for RistrettoPoint {
+    const SUITE_ID: &'static str = "ristretto255_XMD:SHA-512_R255MAP_RO_";
+
+    fn hash_to_curve(msg: &[u8], dst: &[u8]) -> FastCryptoResult<Self> {
+        let uniform_bytes = expand_message_xmd::<Sha512, 64>(msg, dst, 64)?;
+        Ok(Self::from_uniform_bytes(
+            &uniform_bytes.try_into().expect("The output has length 64"),
+        ))
+    }
+}
+
 impl ToFromByteArray<RISTRETTO_POINT_BYTE_LENGTH> for RistrettoPoint {
     fn from_byte_array(bytes: &[u8; RISTRETTO_POINT_BYTE_LENGTH]) -> Result<Self, FastCryptoError> {
         Self::try_from(bytes.as_slice())
@@ -139,7 +166,7 @@ serialize_deserialize_with_to_from_byte_array!(RistrettoPoint);
 
 /// Represents a scalar.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, From, Add, Sub, Neg, Div, GroupOpsExtend, Zeroize)]
-pub struct RistrettoScalar(ExternalRistrettoScalar);
+pub struct RistrettoScalar(pub(crate) ExternalRistrettoScalar);
 
 impl RistrettoScalar {
     /// The order of the base point.
@@ -212,6 +239,19 @@ impl HashToGroupElement for RistrettoScalar {
     }
 }
 
+impl HashToField for RistrettoScalar {
+    const L: usize = 48;
+
+    fn from_uniform_bytes(bytes: &[u8]) -> Self {
+        // Convert the big-endian input to a little-endian 64 byte integer.
+        let mut wide = [0u8; 64];
+        for (i, b) in bytes.iter().rev().enumerate() {
+            wide[i] = *b;
+        }
+        Self::from_bytes_mod_order_wide(&wide)
+    }
+}
+
 impl FiatShamirChallenge for RistrettoScalar {
     fn fiat_shamir_reduction_to_group_element(msg: &[u8]) -> Self {
         Self::hash_to_group_element(msg)
@@ -233,4 +273,10 @@ impl ToFromByteArray<RISTRETTO_SCALAR_BYTE_LENGTH> for RistrettoScalar {
     }
 }
 
+impl ToLittleEndianBytes for RistrettoScalar {
+    fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_byte_array().to_vec()
+    }
+}
+
 serialize_deserialize_with_to_from_byte_array!(RistrettoScalar);
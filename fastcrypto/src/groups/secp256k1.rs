@@ -0,0 +1,300 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Implementation of the Secp256k1 curve. This is a 256-bit Koblitz curve of prime order.
+//! See "SEC 2: Recommended Elliptic Curve Domain Parameters" for details.
+//!
+//! Points can be converted to and from [Secp256k1PublicKey]'s, and scalars can be created from
+//! [Secp256k1PrivateKey]'s, so keys from the signature scheme can be used in generic protocols.
+
+use crate::error::{FastCryptoError, FastCryptoResult};
+use crate::groups::hash_to_curve::{EncodeToCurve, HashToCurve, HashToField};
+use crate::groups::multiplier::ToLittleEndianBytes;
+use crate::groups::short_weierstrass;
+use crate::groups::{
+    Doubling, FiatShamirChallenge, GroupElement, HashToGroupElement, MultiScalarMul,
+    Scalar as ScalarTrait,
+};
+use crate::secp256k1::{Secp256k1PrivateKey, Secp256k1PublicKey};
+use crate::serde_helpers::ToFromByteArray;
+use crate::serialize_deserialize_with_to_from_byte_array;
+use crate::traits::{AllowedRng, ToFromBytes};
+use ark_ec::Group;
+use ark_ff::{Field, One, PrimeField, UniformRand, Zero};
+use ark_secp256k1::{Config, Fr, Projective};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use derive_more::{Add, From, Neg, Sub};
+use elliptic_curve::hash2curve::{ExpandMsgXmd, GroupDigest};
+use fastcrypto_derive::GroupOpsExtend;
+use serde::{de, Deserialize};
+use std::ops::{Div, Mul};
+use zeroize::Zeroize;
+
+pub const SCALAR_SIZE_IN_BYTES: usize = 32;
+
+/// The length of a point in SEC1 compressed form.
+pub const POINT_SIZE_IN_BYTES: usize = 33;
+
+/// The length of a point in SEC1 uncompressed form.
+pub const UNCOMPRESSED_POINT_SIZE_IN_BYTES: usize = 65;
+
+/// The domain separation tag used by [HashToGroupElement] for [ProjectivePoint].
+pub const HASH_TO_GROUP_DST: &[u8] = b"FASTCRYPTO-V01-CS01-with-secp256k1_XMD:SHA-256_SSWU_RO_";
+
+/// A point on the Secp256k1 curve in projective coordinates.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, From, Add, Sub, Neg, GroupOpsExtend)]
+pub struct ProjectivePoint(pub(crate) Projective);
+
+impl GroupElement for ProjectivePoint {
+    type ScalarType = Scalar;
+
+    fn zero() -> Self {
+        Self(Projective::zero())
+    }
+
+    fn generator() -> Self {
+        Self(Projective::generator())
+    }
+}
+
+impl Doubling for ProjectivePoint {
+    fn double(self) -> Self {
+        ProjectivePoint::from(self.0.double())
+    }
+}
+
+impl Mul<Scalar> for ProjectivePoint {
+    type Output = ProjectivePoint;
+
+    fn mul(self, rhs: Scalar) -> ProjectivePoint {
+        ProjectivePoint::from(self.0 * rhs.0)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Div<Scalar> for ProjectivePoint {
+    type Output = Result<ProjectivePoint, FastCryptoError>;
+
+    fn div(self, rhs: Scalar) -> Result<ProjectivePoint, FastCryptoError> {
+        Ok(self * rhs.inverse()?)
+    }
+}
+
+impl ProjectivePoint {
+    /// Serialize this point in SEC1 uncompressed form. The identity is encoded as all zeros.
+    pub fn to_uncompressed_bytes(&self) -> [u8; UNCOMPRESSED_POINT_SIZE_IN_BYTES] {
+        self.encode_sec1(false)
+    }
+
+    /// Deserialize a point in SEC1 uncompressed form. The identity is encoded as all zeros.
+    pub fn from_uncompressed_bytes(
+        bytes: &[u8; UNCOMPRESSED_POINT_SIZE_IN_BYTES],
+    ) -> FastCryptoResult<Self> {
+        Self::decode_sec1(bytes)
+    }
+
+    fn encode_sec1<const LENGTH: usize>(&self, compress: bool) -> [u8; LENGTH] {
+        short_weierstrass::encode_sec1::<k256_arithmetic::Secp256k1, Config, LENGTH>(
+            &self.0, compress,
+        )
+    }
+
+    fn decode_sec1(bytes: &[u8]) -> FastCryptoResult<Self> {
+        short_weierstrass::decode_sec1::<k256_arithmetic::Secp256k1, Config>(bytes)
+            .map(ProjectivePoint)
+    }
+}
+
+impl ToFromByteArray<POINT_SIZE_IN_BYTES> for ProjectivePoint {
+    /// Deserialize a point in SEC1 compressed form. The identity is encoded as all zeros.
+    fn from_byte_array(bytes: &[u8; POINT_SIZE_IN_BYTES]) -> FastCryptoResult<Self> {
+        Self::decode_sec1(bytes)
+    }
+
+    /// Serialize this point in SEC1 compressed form. The identity is encoded as all zeros.
+    fn to_byte_array(&self) -> [u8; POINT_SIZE_IN_BYTES] {
+        self.encode_sec1(true)
+    }
+}
+
+serialize_deserialize_with_to_from_byte_array!(ProjectivePoint);
+
+impl From<&Secp256k1PublicKey> for ProjectivePoint {
+    fn from(public_key: &Secp256k1PublicKey) -> Self {
+        ProjectivePoint::from_byte_array(&public_key.pubkey.serialize())
+            .expect("Public keys are valid points")
+    }
+}
+
+impl TryFrom<&ProjectivePoint> for Secp256k1PublicKey {
+    type Error = FastCryptoError;
+
+    /// Convert a point to a public key. Fails if the point is the identity.
+    fn try_from(point: &ProjectivePoint) -> FastCryptoResult<Self> {
+        if point.0.is_zero() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        Secp256k1PublicKey::from_bytes(&point.to_byte_array())
+    }
+}
+
+impl MultiScalarMul for ProjectivePoint {
+    fn multi_scalar_mul(scalars: &[Self::ScalarType], points: &[Self]) -> FastCryptoResult<Self> {
+        short_weierstrass::multi_scalar_mul(
+            &scalars.iter().map(|s| s.0).collect::<Vec<_>>(),
+            &points.iter().map(|p| p.0).collect::<Vec<_>>(),
+        )
+        .map(ProjectivePoint)
+    }
+}
+
+impl HashToGroupElement for ProjectivePoint {
+    fn hash_to_group_element(msg: &[u8]) -> Self {
+        Self::hash_to_curve(msg, HASH_TO_GROUP_DST).expect("HASH_TO_GROUP_DST is not empty")
+    }
+}
+
+impl HashToCurve for ProjectivePoint {
+    const SUITE_ID: &'static str = "secp256k1_XMD:SHA-256_SSWU_RO_";
+
+    fn hash_to_curve(msg: &[u8], dst: &[u8]) -> FastCryptoResult<Self> {
+        if dst.is_empty() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let point = k256_arithmetic::Secp256k1::hash_from_bytes::<ExpandMsgXmd<sha2::Sha256>>(
+            &[msg],
+            &[dst],
+        )
+        .map_err(|_| FastCryptoError::InvalidInput)?;
+        Ok(ProjectivePoint(short_weierstrass::to_arkworks::<
+            k256_arithmetic::Secp256k1,
+            Config,
+        >(&point.to_affine())))
+    }
+}
+
+impl EncodeToCurve for ProjectivePoint {
+    const SUITE_ID: &'static str = "secp256k1_XMD:SHA-256_SSWU_NU_";
+
+    fn encode_to_curve(msg: &[u8], dst: &[u8]) -> FastCryptoResult<Self> {
+        if dst.is_empty() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let point = k256_arithmetic::Secp256k1::encode_from_bytes::<ExpandMsgXmd<sha2::Sha256>>(
+            &[msg],
+            &[dst],
+        )
+        .map_err(|_| FastCryptoError::InvalidInput)?;
+        Ok(ProjectivePoint(short_weierstrass::to_arkworks::<
+            k256_arithmetic::Secp256k1,
+            Config,
+        >(&point.to_affine())))
+    }
+}
+
+/// A field element in the prime field of the same order as the curve.
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, From, Add, Sub, Neg, GroupOpsExtend)]
+pub struct Scalar(pub(crate) Fr);
+
+impl GroupElement for Scalar {
+    type ScalarType = Scalar;
+
+    fn zero() -> Self {
+        Scalar(Fr::zero())
+    }
+
+    fn generator() -> Self {
+        Scalar(Fr::one())
+    }
+}
+
+impl Mul<Scalar> for Scalar {
+    type Output = Scalar;
+
+    fn mul(self, rhs: Scalar) -> Self::Output {
+        Scalar(self.0 * rhs.0)
+    }
+}
+
+#[allow(clippy::suspicious_arithmetic_impl)]
+impl Div<Scalar> for Scalar {
+    type Output = Result<Scalar, FastCryptoError>;
+
+    fn div(self, rhs: Scalar) -> Result<Scalar, FastCryptoError> {
+        Ok(self * rhs.inverse()?)
+    }
+}
+
+impl From<u128> for Scalar {
+    fn from(value: u128) -> Self {
+        Scalar(Fr::from(value))
+    }
+}
+
+impl From<&Secp256k1PrivateKey> for Scalar {
+    fn from(private_key: &Secp256k1PrivateKey) -> Self {
+        // Private keys are always non-zero and smaller than the group order.
+        Scalar(Fr::from_be_bytes_mod_order(
+            &private_key.privkey.secret_bytes(),
+        ))
+    }
+}
+
+impl ScalarTrait for Scalar {
+    fn rand<R: AllowedRng>(rng: &mut R) -> Self {
+        Scalar(Fr::rand(rng))
+    }
+
+    fn inverse(&self) -> FastCryptoResult<Self> {
+        Ok(Scalar(
+            self.0.inverse().ok_or(FastCryptoError::InvalidInput)?,
+        ))
+    }
+}
+
+impl ToFromByteArray<SCALAR_SIZE_IN_BYTES> for Scalar {
+    fn from_byte_array(bytes: &[u8; SCALAR_SIZE_IN_BYTES]) -> Result<Self, FastCryptoError> {
+        Ok(Scalar(
+            Fr::deserialize_uncompressed(bytes.as_slice())
+                .map_err(|_| FastCryptoError::InvalidInput)?,
+        ))
+    }
+
+    fn to_byte_array(&self) -> [u8; SCALAR_SIZE_IN_BYTES] {
+        let mut bytes = [0u8; SCALAR_SIZE_IN_BYTES];
+        self.0
+            .serialize_uncompressed(&mut bytes[..])
+            .expect("Byte array not large enough");
+        bytes
+    }
+}
+
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl FiatShamirChallenge for Scalar {
+    /// Reduce the given buffer, interpreted as a big-endian integer, modulo the group order. The
+    /// buffer should be at least 48 bytes long for the result to be close to uniform.
+    fn fiat_shamir_reduction_to_group_element(uniform_buffer: &[u8]) -> Self {
+        Scalar(Fr::from_be_bytes_mod_order(uniform_buffer))
+    }
+}
+
+impl HashToField for Scalar {
+    const L: usize = 48;
+
+    fn from_uniform_bytes(bytes: &[u8]) -> Self {
+        Scalar(Fr::from_be_bytes_mod_order(bytes))
+    }
+}
+
+impl ToLittleEndianBytes for Scalar {
+    fn to_le_bytes(&self) -> Vec<u8> {
+        self.to_byte_array().to_vec()
+    }
+}
+
+serialize_deserialize_with_to_from_byte_array!(Scalar);
@@ -5,26 +5,59 @@
 //! See "SEC 2: Recommended Elliptic Curve Domain Parameters" for details."
 
 use crate::error::{FastCryptoError, FastCryptoResult};
+use crate::groups::hash_to_curve::{EncodeToCurve, HashToCurve, HashToField};
+use crate::groups::multiplier::constant_time::{private, select_limbs, ConstantTimeSelect};
 use crate::groups::multiplier::ToLittleEndianBytes;
-use crate::groups::{Doubling, GroupElement, Scalar as ScalarTrait};
+use crate::groups::short_weierstrass;
+use crate::groups::{
+    BatchNormalize, Doubling, FiatShamirChallenge, GroupElement, HashToGroupElement,
+    MultiScalarMul, Scalar as ScalarTrait,
+};
 use crate::serde_helpers::ToFromByteArray;
 use crate::serialize_deserialize_with_to_from_byte_array;
 use crate::traits::AllowedRng;
-use ark_ec::Group;
-use ark_ff::{Field, One, UniformRand, Zero};
-use ark_secp256r1::{Fr, Projective};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::{BigInt, Field, Fp, One, PrimeField, UniformRand, Zero};
+use ark_secp256r1::{Config, Fq, Fr, Projective};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use derive_more::{Add, From, Neg, Sub};
+use elliptic_curve::hash2curve::{ExpandMsgXmd, GroupDigest};
 use fastcrypto_derive::GroupOpsExtend;
 use serde::{de, Deserialize};
+use std::marker::PhantomData;
 use std::ops::{Div, Mul};
+use zeroize::Zeroize;
 
 pub const SCALAR_SIZE_IN_BYTES: usize = 32;
 
+/// The length of a point in SEC1 compressed form.
+pub const POINT_SIZE_IN_BYTES: usize = 33;
+
+/// The length of a point in SEC1 uncompressed form.
+pub const UNCOMPRESSED_POINT_SIZE_IN_BYTES: usize = 65;
+
+/// The domain separation tag used by [HashToGroupElement] for [ProjectivePoint].
+pub const HASH_TO_GROUP_DST: &[u8] = b"FASTCRYPTO-V01-CS01-with-P256_XMD:SHA-256_SSWU_RO_";
+
 /// A point on the Secp256r1 curve in projective coordinates.
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq, From, Add, Sub, Neg, GroupOpsExtend)]
 pub struct ProjectivePoint(pub(crate) Projective);
 
+impl private::Sealed for ProjectivePoint {}
+
+impl ConstantTimeSelect for ProjectivePoint {
+    fn select(a: &Self, b: &Self, choice: u8) -> Self {
+        // Select the limbs of the Montgomery representations of the coordinates.
+        let select =
+            |x: &Fq, y: &Fq| Fp(BigInt(select_limbs(&x.0 .0, &y.0 .0, choice)), PhantomData);
+        Self(Projective::new_unchecked(
+            select(&a.0.x, &b.0.x),
+            select(&a.0.y, &b.0.y),
+            select(&a.0.z, &b.0.z),
+        ))
+    }
+}
+
 impl GroupElement for ProjectivePoint {
     type ScalarType = Scalar;
 
@@ -37,6 +70,16 @@ impl GroupElement for ProjectivePoint {
     }
 }
 
+impl BatchNormalize for ProjectivePoint {
+    fn batch_normalize(elements: &mut [Self]) {
+        let affine = Projective::normalize_batch(&elements.iter().map(|p| p.0).collect::<Vec<_>>());
+        elements
+            .iter_mut()
+            .zip(affine)
+            .for_each(|(p, a)| *p = Self(a.into()));
+    }
+}
+
 impl Doubling for ProjectivePoint {
     fn double(self) -> Self {
         ProjectivePoint::from(self.0.double())
@@ -60,6 +103,90 @@ impl Div<Scalar> for ProjectivePoint {
     }
 }
 
+impl ProjectivePoint {
+    /// Serialize this point in SEC1 uncompressed form. The identity is encoded as all zeros.
+    pub fn to_uncompressed_bytes(&self) -> [u8; UNCOMPRESSED_POINT_SIZE_IN_BYTES] {
+        self.encode_sec1(false)
+    }
+
+    /// Deserialize a point in SEC1 uncompressed form. The identity is encoded as all zeros.
+    pub fn from_uncompressed_bytes(
+        bytes: &[u8; UNCOMPRESSED_POINT_SIZE_IN_BYTES],
+    ) -> FastCryptoResult<Self> {
+        Self::decode_sec1(bytes)
+    }
+
+    fn encode_sec1<const LENGTH: usize>(&self, compress: bool) -> [u8; LENGTH] {
+        short_weierstrass::encode_sec1::<p256::NistP256, Config, LENGTH>(&self.0, compress)
+    }
+
+    fn decode_sec1(bytes: &[u8]) -> FastCryptoResult<Self> {
+        short_weierstrass::decode_sec1::<p256::NistP256, Config>(bytes).map(ProjectivePoint)
+    }
+}
+
+impl ToFromByteArray<POINT_SIZE_IN_BYTES> for ProjectivePoint {
+    /// Deserialize a point in SEC1 compressed form. The identity is encoded as all zeros.
+    fn from_byte_array(bytes: &[u8; POINT_SIZE_IN_BYTES]) -> FastCryptoResult<Self> {
+        Self::decode_sec1(bytes)
+    }
+
+    /// Serialize this point in SEC1 compressed form. The identity is encoded as all zeros.
+    fn to_byte_array(&self) -> [u8; POINT_SIZE_IN_BYTES] {
+        self.encode_sec1(true)
+    }
+}
+
+serialize_deserialize_with_to_from_byte_array!(ProjectivePoint);
+
+impl MultiScalarMul for ProjectivePoint {
+    fn multi_scalar_mul(scalars: &[Self::ScalarType], points: &[Self]) -> FastCryptoResult<Self> {
+        short_weierstrass::multi_scalar_mul(
+            &scalars.iter().map(|s| s.0).collect::<Vec<_>>(),
+            &points.iter().map(|p| p.0).collect::<Vec<_>>(),
+        )
+        .map(ProjectivePoint)
+    }
+}
+
+impl HashToGroupElement for ProjectivePoint {
+    fn hash_to_group_element(msg: &[u8]) -> Self {
+        Self::hash_to_curve(msg, HASH_TO_GROUP_DST).expect("HASH_TO_GROUP_DST is not empty")
+    }
+}
+
+impl HashToCurve for ProjectivePoint {
+    const SUITE_ID: &'static str = "P256_XMD:SHA-256_SSWU_RO_";
+
+    fn hash_to_curve(msg: &[u8], dst: &[u8]) -> FastCryptoResult<Self> {
+        if dst.is_empty() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let point = p256::NistP256::hash_from_bytes::<ExpandMsgXmd<sha2::Sha256>>(&[msg], &[dst])
+            .map_err(|_| FastCryptoError::InvalidInput)?;
+        Ok(ProjectivePoint(short_weierstrass::to_arkworks::<
+            p256::NistP256,
+            Config,
+        >(&point.to_affine())))
+    }
+}
+
+impl EncodeToCurve for ProjectivePoint {
+    const SUITE_ID: &'static str = "P256_XMD:SHA-256_SSWU_NU_";
+
+    fn encode_to_curve(msg: &[u8], dst: &[u8]) -> FastCryptoResult<Self> {
+        if dst.is_empty() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let point = p256::NistP256::encode_from_bytes::<ExpandMsgXmd<sha2::Sha256>>(&[msg], &[dst])
+            .map_err(|_| FastCryptoError::InvalidInput)?;
+        Ok(ProjectivePoint(short_weierstrass::to_arkworks::<
+            p256::NistP256,
+            Config,
+        >(&point.to_affine())))
+    }
+}
+
 /// A field element in the prime field of the same order as the curve.
 #[derive(Default, Clone, Copy, Debug, PartialEq, Eq, From, Add, Sub, Neg, GroupOpsExtend)]
 pub struct Scalar(pub(crate) Fr);
@@ -128,6 +255,28 @@ impl ToFromByteArray<SCALAR_SIZE_IN_BYTES> for Scalar {
     }
 }
 
+impl Zeroize for Scalar {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl FiatShamirChallenge for Scalar {
+    /// Reduce the given buffer, interpreted as a big-endian integer, modulo the group order. The
+    /// buffer should be at least 48 bytes long for the result to be close to uniform.
+    fn fiat_shamir_reduction_to_group_element(uniform_buffer: &[u8]) -> Self {
+        Scalar(Fr::from_be_bytes_mod_order(uniform_buffer))
+    }
+}
+
+impl HashToField for Scalar {
+    const L: usize = 48;
+
+    fn from_uniform_bytes(bytes: &[u8]) -> Self {
+        Scalar(Fr::from_be_bytes_mod_order(bytes))
+    }
+}
+
 impl ToLittleEndianBytes for Scalar {
     fn to_le_bytes(&self) -> Vec<u8> {
         self.to_byte_array().to_vec()
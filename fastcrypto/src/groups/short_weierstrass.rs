@@ -0,0 +1,95 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers shared by the short Weierstrass curves [secp256k1](crate::groups::secp256k1) and
+//! [secp256r1](crate::groups::secp256r1), which use arkworks for the group operations and the
+//! RustCrypto crates for SEC1 encodings and hashing to the curve.
+
+use crate::error::{FastCryptoError, FastCryptoResult};
+use ark_ec::short_weierstrass::{Affine, Projective, SWCurveConfig};
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use elliptic_curve::sec1::{EncodedPoint, FromEncodedPoint, ModulusSize, ToEncodedPoint};
+use elliptic_curve::{AffinePoint, CurveArithmetic, FieldBytes, FieldBytesSize};
+
+/// Serialize a point in SEC1 form, compressed or uncompressed depending on `LENGTH`. The identity
+/// is encoded as all zeros.
+pub(crate) fn encode_sec1<C, P, const LENGTH: usize>(
+    point: &Projective<P>,
+    compress: bool,
+) -> [u8; LENGTH]
+where
+    C: CurveArithmetic,
+    FieldBytesSize<C>: ModulusSize,
+    P: SWCurveConfig,
+    P::BaseField: PrimeField,
+{
+    let mut bytes = [0u8; LENGTH];
+    if point.is_zero() {
+        return bytes;
+    }
+    let affine = point.into_affine();
+    let (x, y) = affine.xy().expect("The identity is handled above");
+    let encoded_point = EncodedPoint::<C>::from_affine_coordinates(
+        FieldBytes::<C>::from_slice(&x.into_bigint().to_bytes_be()),
+        FieldBytes::<C>::from_slice(&y.into_bigint().to_bytes_be()),
+        compress,
+    );
+    bytes.copy_from_slice(encoded_point.as_bytes());
+    bytes
+}
+
+/// Deserialize a point in SEC1 form. The identity is encoded as all zeros. Fails if the point is
+/// not on the curve.
+pub(crate) fn decode_sec1<C, P>(bytes: &[u8]) -> FastCryptoResult<Projective<P>>
+where
+    C: CurveArithmetic,
+    FieldBytesSize<C>: ModulusSize,
+    AffinePoint<C>: FromEncodedPoint<C> + ToEncodedPoint<C>,
+    P: SWCurveConfig,
+    P::BaseField: PrimeField,
+{
+    if bytes.iter().all(|b| *b == 0) {
+        return Ok(Projective::zero());
+    }
+    let encoded_point =
+        EncodedPoint::<C>::from_bytes(bytes).map_err(|_| FastCryptoError::InvalidInput)?;
+    let point =
+        Option::<AffinePoint<C>>::from(AffinePoint::<C>::from_encoded_point(&encoded_point))
+            .ok_or(FastCryptoError::InvalidInput)?;
+    Ok(to_arkworks::<C, P>(&point))
+}
+
+/// Convert an affine point from the RustCrypto crates to a projective point in arkworks.
+pub(crate) fn to_arkworks<C, P>(point: &AffinePoint<C>) -> Projective<P>
+where
+    C: CurveArithmetic,
+    FieldBytesSize<C>: ModulusSize,
+    AffinePoint<C>: ToEncodedPoint<C>,
+    P: SWCurveConfig,
+    P::BaseField: PrimeField,
+{
+    let encoded_point = point.to_encoded_point(false);
+    match (encoded_point.x(), encoded_point.y()) {
+        (Some(x), Some(y)) => Affine::<P>::new_unchecked(
+            P::BaseField::from_be_bytes_mod_order(x),
+            P::BaseField::from_be_bytes_mod_order(y),
+        )
+        .into_group(),
+        _ => Projective::zero(),
+    }
+}
+
+/// Compute sum s_i P_i. Fails if the lengths differ or the inputs are empty.
+pub(crate) fn multi_scalar_mul<P: SWCurveConfig>(
+    scalars: &[P::ScalarField],
+    points: &[Projective<P>],
+) -> FastCryptoResult<Projective<P>> {
+    if scalars.len() != points.len() || scalars.is_empty() {
+        return Err(FastCryptoError::InvalidInput);
+    }
+    Ok(
+        Projective::<P>::msm(&Projective::normalize_batch(points), scalars)
+            .expect("Lengths are checked above"),
+    )
+}
@@ -17,11 +17,12 @@
 //! ```
 
 use core::fmt::Debug;
-use digest::OutputSizeUser;
+use digest::{OutputSizeUser, XofReader};
 use generic_array::GenericArray;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::fmt;
+use std::io::{ErrorKind, Read};
 
 use crate::encoding::{Base64, Encoding};
 use crate::groups::ristretto255::RistrettoPoint;
@@ -105,6 +106,39 @@ pub trait HashFunction<const DIGEST_LENGTH: usize>: Default {
         iter.for_each(|item| h.update(item));
         h.finalize()
     }
+
+    /// Process all data from the given reader until it is exhausted. Returns the number of bytes read.
+    fn update_reader<R: Read>(&mut self, reader: R) -> std::io::Result<u64> {
+        read_to_end(reader, |chunk| self.update(chunk))
+    }
+
+    /// Compute the digest of all data from the given reader until it is exhausted.
+    fn digest_reader<R: Read>(reader: R) -> std::io::Result<Digest<DIGEST_LENGTH>> {
+        let mut h = Self::default();
+        h.update_reader(reader)?;
+        Ok(h.finalize())
+    }
+}
+
+/// Size of the buffer used when hashing data from a [Read].
+const READ_BUFFER_SIZE: usize = 8192;
+
+/// Read all data from the given reader and pass it to `consume` in chunks. Returns the total number of
+/// bytes read.
+fn read_to_end<R: Read>(mut reader: R, mut consume: impl FnMut(&[u8])) -> std::io::Result<u64> {
+    let mut buffer = [0u8; READ_BUFFER_SIZE];
+    let mut total = 0;
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => return Ok(total),
+            Ok(n) => {
+                consume(&buffer[..n]);
+                total += n as u64;
+            }
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
 }
 
 /// This trait is implemented by all messages that can be hashed.
@@ -177,6 +211,249 @@ pub type Keccak256 = HashFunctionWrapper<sha3::Keccak256, 32>;
 /// The [BLAKE2-256](https://en.wikipedia.org/wiki/BLAKE_(hash_function)#BLAKE2) hash function with 256 bit digests.
 pub type Blake2b256 = HashFunctionWrapper<blake2::Blake2b<typenum::U32>, 32>;
 
+/// The [SHA-384](https://en.wikipedia.org/wiki/SHA-2) hash function with 384 bit digests.
+pub type Sha384 = HashFunctionWrapper<sha2::Sha384, 48>;
+
+/// The [SHA-3](https://en.wikipedia.org/wiki/SHA-3) hash function with 384 bit digests.
+pub type Sha3_384 = HashFunctionWrapper<sha3::Sha3_384, 48>;
+
+/// The [BLAKE3](https://github.com/BLAKE3-team/BLAKE3-specs) hash function with 256 bit digests. Use
+/// [Blake3Xof] for outputs of other lengths.
+pub type Blake3 = HashFunctionWrapper<blake3::Hasher, 32>;
+
+impl Blake3 {
+    /// Create a new instance of BLAKE3 in keyed mode which may be used as a MAC or a PRF.
+    pub fn new_keyed(key: &[u8; 32]) -> Self {
+        Self(blake3::Hasher::new_keyed(key))
+    }
+
+    /// Create a new instance of BLAKE3 in key derivation mode. The context string should be
+    /// hardcoded, globally unique and application-specific.
+    pub fn new_derive_key(context: &str) -> Self {
+        Self(blake3::Hasher::new_derive_key(context))
+    }
+}
+
+/// Trait implemented by extendable-output functions (XOFs), that is, hash functions whose output can
+/// be of any length.
+///
+/// # Example
+/// ```
+/// # use fastcrypto::hash::*;
+/// let output1 = Shake256::digest(b"Hello, world!", 100);
+///
+/// let mut xof = Shake256::default();
+/// xof.update(b"Hello, ");
+/// xof.update(b"world!");
+/// let output2 = xof.finalize(100);
+///
+/// assert_eq!(output1, output2);
+/// ```
+pub trait ExtendableOutputFunction: Sized {
+    /// Reader used to squeeze output from the function after all data has been processed.
+    type Reader: XofReader;
+
+    /// Process the given data, and update the internal of the hash function.
+    fn update<Data: AsRef<[u8]>>(&mut self, data: Data);
+
+    /// Process all data from the given reader until it is exhausted. Returns the number of bytes read.
+    fn update_reader<R: Read>(&mut self, reader: R) -> std::io::Result<u64> {
+        read_to_end(reader, |chunk| self.update(chunk))
+    }
+
+    /// Consume the hash function and return a reader from which an arbitrary amount of output can
+    /// be read.
+    fn finalize_xof(self) -> Self::Reader;
+
+    /// Consume the hash function and fill the given buffer with output.
+    fn finalize_into(self, output: &mut [u8]) {
+        self.finalize_xof().read(output);
+    }
+
+    /// Consume the hash function and return `output_length` bytes of output.
+    fn finalize(self, output_length: usize) -> Vec<u8> {
+        let mut output = vec![0u8; output_length];
+        self.finalize_into(&mut output);
+        output
+    }
+
+    /// Compute `output_length` bytes of output from the given data.
+    fn digest<Data: AsRef<[u8]>>(data: Data, output_length: usize) -> Vec<u8>
+    where
+        Self: Default,
+    {
+        let mut h = Self::default();
+        h.update(data);
+        h.finalize(output_length)
+    }
+}
+
+/// This wraps a [digest::ExtendableOutput] as an [ExtendableOutputFunction].
+#[derive(Default, Clone)]
+pub struct XofWrapper<Variant>(Variant);
+
+impl<Variant: digest::Update + digest::ExtendableOutput> ExtendableOutputFunction
+    for XofWrapper<Variant>
+{
+    type Reader = Variant::Reader;
+
+    fn update<Data: AsRef<[u8]>>(&mut self, data: Data) {
+        self.0.update(data.as_ref());
+    }
+
+    fn finalize_xof(self) -> Self::Reader {
+        self.0.finalize_xof()
+    }
+}
+
+/// The [SHAKE128](https://en.wikipedia.org/wiki/SHA-3) extendable-output function.
+pub type Shake128 = XofWrapper<sha3::Shake128>;
+
+/// The [SHAKE256](https://en.wikipedia.org/wiki/SHA-3) extendable-output function.
+pub type Shake256 = XofWrapper<sha3::Shake256>;
+
+/// The [BLAKE3](https://github.com/BLAKE3-team/BLAKE3-specs) hash function used as an
+/// extendable-output function. The first 32 bytes of output are equal to the [Blake3] digest.
+pub type Blake3Xof = XofWrapper<blake3::Hasher>;
+
+impl Blake3Xof {
+    /// Create a new instance of BLAKE3 in keyed mode. See [Blake3::new_keyed].
+    pub fn new_keyed(key: &[u8; 32]) -> Self {
+        Self(blake3::Hasher::new_keyed(key))
+    }
+
+    /// Create a new instance of BLAKE3 in key derivation mode. See [Blake3::new_derive_key].
+    pub fn new_derive_key(context: &str) -> Self {
+        Self(blake3::Hasher::new_derive_key(context))
+    }
+}
+
+/// The customizable SHAKE (cSHAKE) functions defined in [NIST SP 800-185](https://doi.org/10.6028/NIST.SP.800-185).
+pub trait CShakeVariant: digest::Update + digest::ExtendableOutput {
+    /// The rate of the underlying Keccak sponge in bytes.
+    const RATE: usize;
+
+    /// Create a new instance with the given function name and customization string.
+    fn new_with_function_name(function_name: &[u8], customization: &[u8]) -> Self;
+}
+
+impl CShakeVariant for sha3::CShake128 {
+    const RATE: usize = 168;
+
+    fn new_with_function_name(function_name: &[u8], customization: &[u8]) -> Self {
+        Self::from_core(sha3::CShake128Core::new_with_function_name(
+            function_name,
+            customization,
+        ))
+    }
+}
+
+impl CShakeVariant for sha3::CShake256 {
+    const RATE: usize = 136;
+
+    fn new_with_function_name(function_name: &[u8], customization: &[u8]) -> Self {
+        Self::from_core(sha3::CShake256Core::new_with_function_name(
+            function_name,
+            customization,
+        ))
+    }
+}
+
+impl<Variant: CShakeVariant> XofWrapper<Variant> {
+    /// Create a new instance of cSHAKE with the given customization string. Two instances with
+    /// different customization strings give unrelated outputs.
+    pub fn new(customization: &[u8]) -> Self {
+        Self::new_with_function_name(&[], customization)
+    }
+
+    /// Create a new instance of cSHAKE with the given function name and customization string. The
+    /// function name is reserved for functions defined by NIST and should otherwise be empty.
+    pub fn new_with_function_name(function_name: &[u8], customization: &[u8]) -> Self {
+        Self(Variant::new_with_function_name(
+            function_name,
+            customization,
+        ))
+    }
+}
+
+/// The [cSHAKE128](https://doi.org/10.6028/NIST.SP.800-185) extendable-output function.
+pub type CShake128 = XofWrapper<sha3::CShake128>;
+
+/// The [cSHAKE256](https://doi.org/10.6028/NIST.SP.800-185) extendable-output function.
+pub type CShake256 = XofWrapper<sha3::CShake256>;
+
+/// The KECCAK Message Authentication Code (KMAC) defined in
+/// [NIST SP 800-185](https://doi.org/10.6028/NIST.SP.800-185).
+///
+/// Used as an [ExtendableOutputFunction] this is KMACXOF, where the output does not depend on the
+/// requested output length. Use [KmacWrapper::finalize_mac] for the fixed-length KMAC where it does.
+pub struct KmacWrapper<Variant>(Variant);
+
+/// Function name used by KMAC when instantiating cSHAKE.
+const KMAC_FUNCTION_NAME: &[u8] = b"KMAC";
+
+impl<Variant: CShakeVariant> KmacWrapper<Variant> {
+    /// Create a new instance of KMAC with the given key and customization string.
+    pub fn new(key: &[u8], customization: &[u8]) -> Self {
+        let mut cshake = Variant::new_with_function_name(KMAC_FUNCTION_NAME, customization);
+        // bytepad(encode_string(K), rate)
+        let mut padded_key = left_encode(Variant::RATE as u64);
+        padded_key.extend(left_encode(8 * key.len() as u64));
+        padded_key.extend_from_slice(key);
+        padded_key.resize(padded_key.len().next_multiple_of(Variant::RATE), 0);
+        cshake.update(&padded_key);
+        Self(cshake)
+    }
+
+    /// Consume the function and fill the given buffer with the fixed-length KMAC output. Here, the
+    /// output depends on its length, so the first bytes of a longer MAC do not give a shorter MAC.
+    pub fn finalize_mac(mut self, output: &mut [u8]) {
+        self.0.update(&right_encode(8 * output.len() as u64));
+        self.0.finalize_xof_into(output);
+    }
+}
+
+impl<Variant: CShakeVariant> ExtendableOutputFunction for KmacWrapper<Variant> {
+    type Reader = Variant::Reader;
+
+    fn update<Data: AsRef<[u8]>>(&mut self, data: Data) {
+        self.0.update(data.as_ref());
+    }
+
+    fn finalize_xof(mut self) -> Self::Reader {
+        self.0.update(&right_encode(0));
+        self.0.finalize_xof()
+    }
+}
+
+/// KMAC128 and KMACXOF128 (see [KmacWrapper]).
+pub type Kmac128 = KmacWrapper<sha3::CShake128>;
+
+/// KMAC256 and KMACXOF256 (see [KmacWrapper]).
+pub type Kmac256 = KmacWrapper<sha3::CShake256>;
+
+/// Big-endian encoding of `x` with as few bytes as possible but at least one.
+fn encode_integer(x: u64) -> Vec<u8> {
+    let bytes = x.to_be_bytes();
+    let leading_zeros = bytes.iter().take_while(|b| **b == 0).count().min(7);
+    bytes[leading_zeros..].to_vec()
+}
+
+/// The left_encode function from NIST SP 800-185.
+fn left_encode(x: u64) -> Vec<u8> {
+    let encoded = encode_integer(x);
+    let mut result = vec![encoded.len() as u8];
+    result.extend(encoded);
+    result
+}
+
+/// The right_encode function from NIST SP 800-185.
+fn right_encode(x: u64) -> Vec<u8> {
+    let mut result = encode_integer(x);
+    result.push(result.len() as u8);
+    result
+}
+
 /// A Multiset Hash is a homomorphic hash function, which hashes arbitrary multisets of objects such
 /// that the hash of the union of two multisets is easy to compute from the hashes of the two multisets.
 ///
@@ -316,3 +593,132 @@ impl Debug for EllipticCurveMultisetHash {
         f.debug_struct("Accumulator").finish()
     }
 }
+
+/// The number of 16-bit limbs in the state of an [LtHash].
+pub const LT_HASH_LIMBS: usize = 1024;
+
+/// `LtHash` is a homomorphic multiset hash function based on lattices. Each element is expanded to a
+/// vector of [LT_HASH_LIMBS] 16-bit limbs using an extendable output function, and the hash is the
+/// sum of all such vectors, where the limbs are added modulo 2^16.
+///
+/// Compared to [EllipticCurveMultisetHash], inserting and removing elements is much cheaper since it
+/// only requires an evaluation of the XOF and a vector addition, but the state is larger (2048 bytes).
+///
+/// For more information about the construction and its security, see ["Securing Update Propagation
+/// with Homomorphic Hashing" by K. Lewi et al.](https://eprint.iacr.org/2019/227), which calls this
+/// instantiation LtHash16. The XOF defaults to BLAKE3 but may be any [ExtendableOutputFunction], e.g.
+/// [Shake128]. The digest is computed by hashing the state with the same XOF, so digests are 32 bytes
+/// long.
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct LtHash<X: ExtendableOutputFunction + Default = Blake3Xof> {
+    #[serde_as(as = "[_; LT_HASH_LIMBS]")]
+    limbs: [u16; LT_HASH_LIMBS],
+    #[serde(skip)]
+    _xof: std::marker::PhantomData<X>,
+}
+
+impl<X: ExtendableOutputFunction + Default> Default for LtHash<X> {
+    fn default() -> Self {
+        Self {
+            limbs: [0u16; LT_HASH_LIMBS],
+            _xof: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<X: ExtendableOutputFunction + Default> PartialEq for LtHash<X> {
+    fn eq(&self, other: &Self) -> bool {
+        self.limbs == other.limbs
+    }
+}
+
+impl<X: ExtendableOutputFunction + Default> Eq for LtHash<X> {}
+
+impl<X: ExtendableOutputFunction + Default> MultisetHash<32> for LtHash<X> {
+    fn insert<Data: AsRef<[u8]>>(&mut self, item: Data) {
+        add_limbs(&mut self.limbs, &Self::hash_to_limbs(item));
+    }
+
+    fn insert_all<It, Data>(&mut self, items: It)
+    where
+        It: IntoIterator<Item = Data>,
+        Data: AsRef<[u8]>,
+    {
+        for i in items {
+            self.insert(i);
+        }
+    }
+
+    fn union(&mut self, other: &Self) {
+        add_limbs(&mut self.limbs, &other.limbs);
+    }
+
+    fn remove<Data: AsRef<[u8]>>(&mut self, item: Data) {
+        sub_limbs(&mut self.limbs, &Self::hash_to_limbs(item));
+    }
+
+    fn remove_all<It, Data>(&mut self, items: It)
+    where
+        It: IntoIterator<Item = Data>,
+        Data: AsRef<[u8]>,
+    {
+        for i in items {
+            self.remove(i);
+        }
+    }
+
+    fn digest(&self) -> Digest<32> {
+        let mut xof = X::default();
+        xof.update(self.to_bytes());
+        let mut digest = [0u8; 32];
+        xof.finalize_into(&mut digest);
+        Digest::new(digest)
+    }
+}
+
+impl<X: ExtendableOutputFunction + Default> LtHash<X> {
+    /// The state of this hash function as the little-endian encoding of the limbs.
+    pub fn to_bytes(&self) -> [u8; 2 * LT_HASH_LIMBS] {
+        let mut bytes = [0u8; 2 * LT_HASH_LIMBS];
+        for (chunk, limb) in bytes.chunks_exact_mut(2).zip(self.limbs.iter()) {
+            chunk.copy_from_slice(&limb.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Expand the given item into a vector of limbs to be used by the insert and remove methods.
+    fn hash_to_limbs<Data: AsRef<[u8]>>(item: Data) -> [u16; LT_HASH_LIMBS] {
+        let mut xof = X::default();
+        xof.update(item);
+        let mut bytes = [0u8; 2 * LT_HASH_LIMBS];
+        xof.finalize_into(&mut bytes);
+        let mut limbs = [0u16; LT_HASH_LIMBS];
+        for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(2)) {
+            *limb = u16::from_le_bytes([chunk[0], chunk[1]]);
+        }
+        limbs
+    }
+}
+
+// The limb-wise operations below are written as simple loops over fixed-size arrays without
+// branches, which the compiler turns into SIMD instructions on platforms supporting them.
+
+fn add_limbs(a: &mut [u16; LT_HASH_LIMBS], b: &[u16; LT_HASH_LIMBS]) {
+    for (x, y) in a.iter_mut().zip(b.iter()) {
+        *x = x.wrapping_add(*y);
+    }
+}
+
+fn sub_limbs(a: &mut [u16; LT_HASH_LIMBS], b: &[u16; LT_HASH_LIMBS]) {
+    for (x, y) in a.iter_mut().zip(b.iter()) {
+        *x = x.wrapping_sub(*y);
+    }
+}
+
+impl<X: ExtendableOutputFunction + Default> Debug for LtHash<X> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LtHash").finish()
+    }
+}
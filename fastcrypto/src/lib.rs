@@ -12,6 +12,14 @@
 #[path = "tests/ed25519_tests.rs"]
 pub mod ed25519_tests;
 
+#[cfg(test)]
+#[path = "tests/bn254_group_tests.rs"]
+pub mod bn254_group_tests;
+
+#[cfg(test)]
+#[path = "tests/ed25519_group_tests.rs"]
+pub mod ed25519_group_tests;
+
 #[cfg(test)]
 #[path = "tests/secp256k1_tests.rs"]
 pub mod secp256k1_tests;
@@ -36,6 +44,10 @@ pub mod bls12381_tests;
 #[path = "tests/bulletproofs_tests.rs"]
 pub mod bulletproofs_tests;
 
+#[cfg(all(test, feature = "experimental"))]
+#[path = "tests/twisted_elgamal_tests.rs"]
+pub mod twisted_elgamal_tests;
+
 #[cfg(all(test, feature = "aes"))]
 #[path = "tests/aes_tests.rs"]
 pub mod aes_tests;
@@ -64,6 +76,10 @@ pub mod ristretto255_tests;
 #[path = "tests/bls12381_group_tests.rs"]
 pub mod bls12381_group_tests;
 
+#[cfg(test)]
+#[path = "tests/hash_to_curve_tests.rs"]
+pub mod hash_to_curve_tests;
+
 #[cfg(test)]
 #[path = "tests/vrf_tests.rs"]
 pub mod vrf_tests;
@@ -80,10 +96,26 @@ pub mod test_helpers;
 #[path = "tests/utils_tests.rs"]
 pub mod utils_tests;
 
+#[cfg(test)]
+#[path = "tests/secp256k1_group_tests.rs"]
+pub mod secp256k1_group_tests;
+
 #[cfg(test)]
 #[path = "tests/secp256r1_group_tests.rs"]
 pub mod secp256r1_group_tests;
 
+#[cfg(test)]
+#[path = "tests/merkle_tests.rs"]
+pub mod merkle_tests;
+
+#[cfg(test)]
+#[path = "tests/mmr_tests.rs"]
+pub mod mmr_tests;
+
+#[cfg(test)]
+#[path = "tests/sparse_merkle_tests.rs"]
+pub mod sparse_merkle_tests;
+
 pub mod traits;
 
 #[cfg(feature = "aes")]
@@ -98,12 +130,15 @@ pub mod groups;
 pub mod hash;
 pub mod hmac;
 pub mod jwt_utils;
+pub mod merkle;
 pub mod private_seed;
 pub mod rsa;
 pub mod secp256k1;
 pub mod secp256r1;
 pub mod serde_helpers;
 pub mod signature_service;
+#[cfg(feature = "experimental")]
+pub mod twisted_elgamal;
 pub mod utils;
 pub mod vrf;
 
@@ -0,0 +1,201 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module contains an implementation of Merkle mountain ranges, which are append-only
+//! accumulators for ever-growing lists of leaves.
+//!
+//! A Merkle mountain range with n leaves consists of a list of complete binary trees, called peaks,
+//! with sizes given by the binary representation of n. Appending a leaf adds a new peak and merges
+//! peaks of equal size, so the amortized cost of an append is constant. The root is computed by
+//! bagging the peaks from right to left, which gives the same root as a [MerkleTree] over the
+//! same leaves. This means that inclusion proofs are [MerkleProof]s and that proofs that one root
+//! extends another, also called ancestry proofs, are [ConsistencyProof]s. Proofs may be computed
+//! against the root of any previous size of the range, and proofs for old leaves can be given
+//! against new roots.
+//!
+//! # Example
+//! ```rust
+//! # use fastcrypto::hash::Sha256;
+//! # use fastcrypto::merkle::mmr::MerkleMountainRange;
+//! let mut mmr = MerkleMountainRange::<Sha256, 32>::new();
+//! mmr.append(b"a");
+//! mmr.append(b"b");
+//! let old_root = mmr.root();
+//! mmr.append(b"c");
+//!
+//! let proof = mmr.get_proof(0).unwrap();
+//! assert!(proof.verify::<Sha256>(&mmr.root(), b"a", 0, 3).is_ok());
+//!
+//! let ancestry_proof = mmr.get_ancestry_proof(2, 3).unwrap();
+//! assert!(ancestry_proof
+//!     .verify::<Sha256>(2, &old_root, 3, &mmr.root())
+//!     .is_ok());
+//! ```
+
+use crate::error::{FastCryptoError, FastCryptoResult};
+use crate::hash::{Digest, HashFunction};
+#[cfg(doc)]
+use crate::merkle::MerkleTree;
+use crate::merkle::{
+    compute_root_from_nodes, consistency_subproof, leaf_hash, node_hash, subtree_root,
+    ConsistencyProof, MerkleProof,
+};
+use std::marker::PhantomData;
+
+/// A Merkle mountain range over a list of leaves.
+pub struct MerkleMountainRange<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize> {
+    /// The roots of all complete subtrees. The first level contains the leaf hashes, and the i'th
+    /// level contains the roots of the complete subtrees with 2^i leaves from left to right.
+    levels: Vec<Vec<Digest<DIGEST_LEN>>>,
+    _hash_function: PhantomData<H>,
+}
+
+impl<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize> Default
+    for MerkleMountainRange<H, DIGEST_LEN>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize> MerkleMountainRange<H, DIGEST_LEN> {
+    /// Create a new empty range.
+    pub fn new() -> Self {
+        Self {
+            levels: vec![vec![]],
+            _hash_function: PhantomData,
+        }
+    }
+
+    /// Append a leaf with the given data and return its index.
+    pub fn append(&mut self, data: &[u8]) -> usize {
+        self.append_leaf_hash(leaf_hash::<H, DIGEST_LEN>(data))
+    }
+
+    /// Append a leaf given by its hash, computed using [leaf_hash], and return its index.
+    pub fn append_leaf_hash(&mut self, leaf_hash: Digest<DIGEST_LEN>) -> usize {
+        self.levels[0].push(leaf_hash);
+        let mut level = 0;
+        while self.levels[level].len() & 1 == 0 {
+            let nodes = &self.levels[level];
+            let parent =
+                node_hash::<H, DIGEST_LEN>(&nodes[nodes.len() - 2], &nodes[nodes.len() - 1]);
+            if self.levels.len() == level + 1 {
+                self.levels.push(vec![]);
+            }
+            self.levels[level + 1].push(parent);
+            level += 1;
+        }
+        self.len() - 1
+    }
+
+    /// The number of leaves in this range.
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Returns true if this range has no leaves.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The peaks of this range from left to right, which are the roots of the largest complete
+    /// subtrees.
+    pub fn peaks(&self) -> Vec<Digest<DIGEST_LEN>> {
+        self.peaks_at(self.len())
+            .expect("The current size is always valid")
+    }
+
+    /// The peaks of this range when it had `size` leaves.
+    pub fn peaks_at(&self, size: usize) -> FastCryptoResult<Vec<Digest<DIGEST_LEN>>> {
+        if size > self.len() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let mut peaks = Vec::with_capacity(size.count_ones() as usize);
+        let mut offset = 0;
+        for level in (0..usize::BITS as usize).rev() {
+            if size & (1 << level) != 0 {
+                peaks.push(self.levels[level][offset >> level]);
+                offset += 1 << level;
+            }
+        }
+        Ok(peaks)
+    }
+
+    /// The root of this range. The root of an empty range is the hash of the empty string.
+    pub fn root(&self) -> Digest<DIGEST_LEN> {
+        bag_peaks::<H, DIGEST_LEN>(&self.peaks())
+    }
+
+    /// The root of this range when it had `size` leaves.
+    pub fn root_at(&self, size: usize) -> FastCryptoResult<Digest<DIGEST_LEN>> {
+        Ok(bag_peaks::<H, DIGEST_LEN>(&self.peaks_at(size)?))
+    }
+
+    /// Get a proof that the leaf with the given index is included in this range.
+    pub fn get_proof(&self, leaf_index: usize) -> FastCryptoResult<MerkleProof<DIGEST_LEN>> {
+        self.get_proof_at(leaf_index, self.len())
+    }
+
+    /// Get a proof that the leaf with the given index is included in this range when it had `size`
+    /// leaves. The proof can be verified against [MerkleMountainRange::root_at] for that size.
+    pub fn get_proof_at(
+        &self,
+        leaf_index: usize,
+        size: usize,
+    ) -> FastCryptoResult<MerkleProof<DIGEST_LEN>> {
+        if leaf_index >= size || size > self.len() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let mut path = Vec::new();
+        compute_root_from_nodes::<H, DIGEST_LEN>(
+            size,
+            vec![(leaf_index, self.levels[0][leaf_index])],
+            |level, index| {
+                let hash = subtree_root::<H, DIGEST_LEN>(
+                    index << level,
+                    usize::min((index + 1) << level, size),
+                    &|level, index| self.levels[level][index],
+                );
+                path.push(hash);
+                Ok(hash)
+            },
+        )?;
+        Ok(MerkleProof { path })
+    }
+
+    /// Get a proof that the range with `new_size` leaves extends the range with `old_size` leaves.
+    pub fn get_ancestry_proof(
+        &self,
+        old_size: usize,
+        new_size: usize,
+    ) -> FastCryptoResult<ConsistencyProof<DIGEST_LEN>> {
+        if old_size == 0 || old_size > new_size || new_size > self.len() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let mut path = Vec::new();
+        consistency_subproof::<H, DIGEST_LEN>(
+            old_size,
+            0,
+            new_size,
+            true,
+            &|level, index| self.levels[level][index],
+            &mut path,
+        );
+        Ok(ConsistencyProof { path })
+    }
+}
+
+/// Compute the root of a Merkle mountain range from its peaks, given from left to right, by hashing
+/// them together from right to left.
+pub fn bag_peaks<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize>(
+    peaks: &[Digest<DIGEST_LEN>],
+) -> Digest<DIGEST_LEN> {
+    match peaks.split_last() {
+        None => H::digest([]),
+        Some((last, rest)) => rest
+            .iter()
+            .rev()
+            .fold(*last, |acc, peak| node_hash::<H, DIGEST_LEN>(peak, &acc)),
+    }
+}
@@ -0,0 +1,398 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module contains an implementation of binary Merkle trees which is generic over the hash
+//! function. The trees follow [RFC 6962](https://www.rfc-editor.org/rfc/rfc6962) and
+//! [RFC 9162](https://www.rfc-editor.org/rfc/rfc9162), so leaves and inner nodes are hashed with
+//! different prefixes and trees of any size are supported. Besides inclusion proofs for single
+//! leaves, the module supports compact proofs for many leaves at once and consistency proofs which
+//! show that a tree is a prefix of a larger tree.
+//!
+//! # Example
+//! ```rust
+//! # use fastcrypto::hash::Sha256;
+//! # use fastcrypto::merkle::MerkleTree;
+//! let leaves: Vec<&[u8]> = vec![b"a", b"b", b"c"];
+//! let tree = MerkleTree::<Sha256, 32>::build(&leaves);
+//! let proof = tree.get_proof(1).unwrap();
+//! assert!(proof
+//!     .verify::<Sha256>(&tree.root(), b"b", 1, tree.len())
+//!     .is_ok());
+//! ```
+
+use crate::error::{FastCryptoError, FastCryptoResult};
+use crate::hash::{Digest, HashFunction};
+use serde::{Deserialize, Serialize};
+use std::marker::PhantomData;
+
+pub mod mmr;
+pub mod sparse;
+
+/// Prefix used when hashing leaves.
+pub const LEAF_PREFIX: u8 = 0x00;
+
+/// Prefix used when hashing inner nodes.
+pub const NODE_PREFIX: u8 = 0x01;
+
+/// Compute the hash of a leaf with the given data.
+pub fn leaf_hash<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize>(
+    data: &[u8],
+) -> Digest<DIGEST_LEN> {
+    let mut hash_function = H::default();
+    hash_function.update([LEAF_PREFIX]);
+    hash_function.update(data);
+    hash_function.finalize()
+}
+
+/// Compute the hash of an inner node with the given children.
+pub fn node_hash<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize>(
+    left: &Digest<DIGEST_LEN>,
+    right: &Digest<DIGEST_LEN>,
+) -> Digest<DIGEST_LEN> {
+    let mut hash_function = H::default();
+    hash_function.update([NODE_PREFIX]);
+    hash_function.update(left);
+    hash_function.update(right);
+    hash_function.finalize()
+}
+
+/// A binary Merkle tree over a list of leaves.
+///
+/// If a level has an odd number of nodes, the last node is moved up to the next level unchanged. This
+/// gives the same root as the recursive definition in RFC 6962.
+pub struct MerkleTree<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize> {
+    /// The hashes of all nodes. The first level contains the leaf hashes and the last level the root.
+    levels: Vec<Vec<Digest<DIGEST_LEN>>>,
+    _hash_function: PhantomData<H>,
+}
+
+/// A proof that a leaf is included in a tree.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleProof<const DIGEST_LEN: usize> {
+    /// The siblings of the nodes on the path from the leaf to the root, starting from the bottom.
+    pub path: Vec<Digest<DIGEST_LEN>>,
+}
+
+/// A proof that a set of leaves are included in a tree. This is more compact than individual proofs
+/// for each leaf because nodes that can be computed from the leaves themselves are omitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MerkleMultiProof<const DIGEST_LEN: usize> {
+    /// The nodes needed to compute the root, ordered by level from the bottom and left to right.
+    pub hashes: Vec<Digest<DIGEST_LEN>>,
+}
+
+/// A proof that a tree is a prefix of a larger tree, as defined in section 2.1.4 of RFC 9162.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConsistencyProof<const DIGEST_LEN: usize> {
+    pub path: Vec<Digest<DIGEST_LEN>>,
+}
+
+impl<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize> MerkleTree<H, DIGEST_LEN> {
+    /// Build a tree from the given leaves.
+    pub fn build<I: IntoIterator<Item = D>, D: AsRef<[u8]>>(leaves: I) -> Self {
+        Self::build_from_leaf_hashes(
+            leaves
+                .into_iter()
+                .map(|leaf| leaf_hash::<H, DIGEST_LEN>(leaf.as_ref()))
+                .collect(),
+        )
+    }
+
+    /// Build a tree from the hashes of the leaves, computed using [leaf_hash].
+    pub fn build_from_leaf_hashes(leaf_hashes: Vec<Digest<DIGEST_LEN>>) -> Self {
+        let mut levels = vec![leaf_hashes];
+        while levels.last().expect("Levels are never empty").len() > 1 {
+            let next = levels
+                .last()
+                .expect("Levels are never empty")
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash::<H, DIGEST_LEN>(left, right),
+                    [single] => *single,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self {
+            levels,
+            _hash_function: PhantomData,
+        }
+    }
+
+    /// The number of leaves in this tree.
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    /// Returns true if this tree has no leaves.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The root of this tree. The root of an empty tree is the hash of the empty string.
+    pub fn root(&self) -> Digest<DIGEST_LEN> {
+        match self.levels.last().expect("Levels are never empty").first() {
+            Some(root) => *root,
+            None => H::digest([]),
+        }
+    }
+
+    /// Get a proof that the leaf with the given index is included in this tree.
+    pub fn get_proof(&self, leaf_index: usize) -> FastCryptoResult<MerkleProof<DIGEST_LEN>> {
+        let proof = self.get_multiproof(&[leaf_index])?;
+        Ok(MerkleProof { path: proof.hashes })
+    }
+
+    /// Get a proof that the leaves with the given indices are included in this tree. The indices must
+    /// be strictly increasing.
+    pub fn get_multiproof(
+        &self,
+        leaf_indices: &[usize],
+    ) -> FastCryptoResult<MerkleMultiProof<DIGEST_LEN>> {
+        let leaves = leaf_indices
+            .iter()
+            .map(|&i| {
+                Ok((
+                    i,
+                    *self.levels[0].get(i).ok_or(FastCryptoError::InvalidInput)?,
+                ))
+            })
+            .collect::<FastCryptoResult<Vec<_>>>()?;
+        let mut hashes = Vec::new();
+        compute_root_from_nodes::<H, DIGEST_LEN>(self.len(), leaves, |level, index| {
+            let hash = self.levels[level][index];
+            hashes.push(hash);
+            Ok(hash)
+        })?;
+        Ok(MerkleMultiProof { hashes })
+    }
+
+    /// Get a proof that the tree with the first `old_size` leaves of this tree is a prefix of this
+    /// tree. Follows section 2.1.4.1 of RFC 9162.
+    pub fn get_consistency_proof(
+        &self,
+        old_size: usize,
+    ) -> FastCryptoResult<ConsistencyProof<DIGEST_LEN>> {
+        if old_size == 0 || old_size > self.len() {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let mut path = Vec::new();
+        consistency_subproof::<H, DIGEST_LEN>(
+            old_size,
+            0,
+            self.len(),
+            true,
+            &|level, index| self.levels[level][index],
+            &mut path,
+        );
+        Ok(ConsistencyProof { path })
+    }
+}
+
+impl<const DIGEST_LEN: usize> MerkleProof<DIGEST_LEN> {
+    /// Verify that the given leaf is at index `leaf_index` in a tree with `tree_size` leaves and the
+    /// given root.
+    pub fn verify<H: HashFunction<DIGEST_LEN>>(
+        &self,
+        root: &Digest<DIGEST_LEN>,
+        leaf: &[u8],
+        leaf_index: usize,
+        tree_size: usize,
+    ) -> FastCryptoResult<()> {
+        MerkleMultiProof {
+            hashes: self.path.clone(),
+        }
+        .verify::<H, &[u8]>(root, &[(leaf_index, leaf)], tree_size)
+    }
+}
+
+impl<const DIGEST_LEN: usize> MerkleMultiProof<DIGEST_LEN> {
+    /// Verify that the given leaves, given as pairs of indices and data, are in a tree with
+    /// `tree_size` leaves and the given root. The indices must be strictly increasing.
+    pub fn verify<H: HashFunction<DIGEST_LEN>, D: AsRef<[u8]>>(
+        &self,
+        root: &Digest<DIGEST_LEN>,
+        leaves: &[(usize, D)],
+        tree_size: usize,
+    ) -> FastCryptoResult<()> {
+        if leaves.iter().any(|(index, _)| *index >= tree_size) {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let leaves = leaves
+            .iter()
+            .map(|(index, data)| (*index, leaf_hash::<H, DIGEST_LEN>(data.as_ref())))
+            .collect();
+        let mut hashes = self.hashes.iter();
+        let computed_root = compute_root_from_nodes::<H, DIGEST_LEN>(tree_size, leaves, |_, _| {
+            hashes.next().copied().ok_or(FastCryptoError::InvalidProof)
+        })?;
+        if hashes.next().is_some() || computed_root != *root {
+            return Err(FastCryptoError::InvalidProof);
+        }
+        Ok(())
+    }
+}
+
+impl<const DIGEST_LEN: usize> ConsistencyProof<DIGEST_LEN> {
+    /// Verify that a tree with `old_size` leaves and root `old_root` is a prefix of a tree with
+    /// `new_size` leaves and root `new_root`. Follows section 2.1.4.2 of RFC 9162.
+    pub fn verify<H: HashFunction<DIGEST_LEN>>(
+        &self,
+        old_size: usize,
+        old_root: &Digest<DIGEST_LEN>,
+        new_size: usize,
+        new_root: &Digest<DIGEST_LEN>,
+    ) -> FastCryptoResult<()> {
+        if old_size == 0 || old_size > new_size {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        if old_size == new_size {
+            return match self.path.is_empty() && old_root == new_root {
+                true => Ok(()),
+                false => Err(FastCryptoError::InvalidProof),
+            };
+        }
+
+        // If the old tree is a complete subtree of the new tree, its root is not included in the proof.
+        let mut path = self.path.iter();
+        let first = match old_size.is_power_of_two() {
+            true => *old_root,
+            false => *path.next().ok_or(FastCryptoError::InvalidProof)?,
+        };
+
+        let mut fn_ = old_size - 1;
+        let mut sn = new_size - 1;
+        while fn_ & 1 == 1 {
+            fn_ >>= 1;
+            sn >>= 1;
+        }
+
+        let mut fr = first;
+        let mut sr = first;
+        for c in path {
+            if sn == 0 {
+                return Err(FastCryptoError::InvalidProof);
+            }
+            if fn_ & 1 == 1 || fn_ == sn {
+                fr = node_hash::<H, DIGEST_LEN>(c, &fr);
+                sr = node_hash::<H, DIGEST_LEN>(c, &sr);
+                while fn_ & 1 == 0 && fn_ != 0 {
+                    fn_ >>= 1;
+                    sn >>= 1;
+                }
+            } else {
+                sr = node_hash::<H, DIGEST_LEN>(&sr, c);
+            }
+            fn_ >>= 1;
+            sn >>= 1;
+        }
+
+        if sn != 0 || fr != *old_root || sr != *new_root {
+            return Err(FastCryptoError::InvalidProof);
+        }
+        Ok(())
+    }
+}
+
+/// Compute the root of a tree with `tree_size` leaves from the given nodes at the leaf level, given as
+/// pairs of indices and hashes which must be strictly increasing. Whenever a node is needed which
+/// cannot be computed from the given nodes, `sibling` is called with its level and index.
+fn compute_root_from_nodes<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize>(
+    tree_size: usize,
+    mut nodes: Vec<(usize, Digest<DIGEST_LEN>)>,
+    mut sibling: impl FnMut(usize, usize) -> FastCryptoResult<Digest<DIGEST_LEN>>,
+) -> FastCryptoResult<Digest<DIGEST_LEN>> {
+    if nodes.is_empty() || nodes.windows(2).any(|pair| pair[0].0 >= pair[1].0) {
+        return Err(FastCryptoError::InvalidInput);
+    }
+
+    let mut level = 0;
+    let mut level_size = tree_size;
+    while level_size > 1 {
+        let mut parents = Vec::with_capacity(nodes.len());
+        let mut i = 0;
+        while i < nodes.len() {
+            let (index, hash) = nodes[i];
+            let parent = if index % 2 == 1 {
+                node_hash::<H, DIGEST_LEN>(&sibling(level, index - 1)?, &hash)
+            } else if index + 1 == level_size {
+                // The last node of a level with an odd number of nodes is moved up unchanged.
+                hash
+            } else if i + 1 < nodes.len() && nodes[i + 1].0 == index + 1 {
+                i += 1;
+                node_hash::<H, DIGEST_LEN>(&hash, &nodes[i].1)
+            } else {
+                node_hash::<H, DIGEST_LEN>(&hash, &sibling(level, index + 1)?)
+            };
+            parents.push((index / 2, parent));
+            i += 1;
+        }
+        nodes = parents;
+        level += 1;
+        level_size = level_size.div_ceil(2);
+    }
+    Ok(nodes[0].1)
+}
+
+/// The SUBPROOF function from RFC 9162 for the leaves in the range `start..end`. The function
+/// `complete_node` returns the root of the complete subtree with the given level and index.
+fn consistency_subproof<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize>(
+    m: usize,
+    start: usize,
+    end: usize,
+    complete_subtree: bool,
+    complete_node: &impl Fn(usize, usize) -> Digest<DIGEST_LEN>,
+    path: &mut Vec<Digest<DIGEST_LEN>>,
+) {
+    let n = end - start;
+    if m == n {
+        if !complete_subtree {
+            path.push(subtree_root::<H, DIGEST_LEN>(start, end, complete_node));
+        }
+        return;
+    }
+    let k = largest_power_of_two_smaller_than(n);
+    if m <= k {
+        consistency_subproof::<H, DIGEST_LEN>(
+            m,
+            start,
+            start + k,
+            complete_subtree,
+            complete_node,
+            path,
+        );
+        path.push(subtree_root::<H, DIGEST_LEN>(start + k, end, complete_node));
+    } else {
+        consistency_subproof::<H, DIGEST_LEN>(m - k, start + k, end, false, complete_node, path);
+        path.push(subtree_root::<H, DIGEST_LEN>(
+            start,
+            start + k,
+            complete_node,
+        ));
+    }
+}
+
+/// Compute the root of the tree over the leaves in the range `start..end`, which must be non-empty.
+/// The function `complete_node` returns the root of the complete subtree with the given level and
+/// index.
+fn subtree_root<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize>(
+    start: usize,
+    end: usize,
+    complete_node: &impl Fn(usize, usize) -> Digest<DIGEST_LEN>,
+) -> Digest<DIGEST_LEN> {
+    let n = end - start;
+    if n.is_power_of_two() && start & (n - 1) == 0 {
+        return complete_node(n.trailing_zeros() as usize, start / n);
+    }
+    let k = largest_power_of_two_smaller_than(n);
+    node_hash::<H, DIGEST_LEN>(
+        &subtree_root::<H, DIGEST_LEN>(start, start + k, complete_node),
+        &subtree_root::<H, DIGEST_LEN>(start + k, end, complete_node),
+    )
+}
+
+/// Returns the largest power of two strictly smaller than `n`. Assumes that `n > 1`.
+fn largest_power_of_two_smaller_than(n: usize) -> usize {
+    1 << (usize::BITS - (n - 1).leading_zeros() - 1)
+}
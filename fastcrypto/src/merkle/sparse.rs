@@ -0,0 +1,537 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module contains an implementation of a sparse Merkle tree with 256-bit keys, which is an
+//! authenticated key-value map supporting both membership and non-membership proofs.
+//!
+//! Conceptually, the tree has a leaf for each of the 2^256 possible keys, but only non-empty subtrees
+//! are stored: The hash of an empty subtree is the all-zero digest, and a subtree containing a single
+//! leaf is represented by that leaf. As a result, the tree has depth O(log n) for n random keys, and
+//! the root only depends on the set of key-value pairs and not on the order in which they were
+//! inserted. Proofs omit the siblings which are empty subtrees.
+//!
+//! The nodes are content-addressed by their hashes and kept in a [NodeStore], so roots of previous
+//! versions of the tree remain valid as long as their nodes are kept in the store.
+//!
+//! # Example
+//! ```rust
+//! # use fastcrypto::hash::{HashFunction, Sha256};
+//! # use fastcrypto::merkle::sparse::SparseMerkleTree;
+//! let mut tree = SparseMerkleTree::<Sha256, 32>::new();
+//! let key = Sha256::digest(b"key");
+//! tree.insert(key, b"value").unwrap();
+//!
+//! let proof = tree.get_proof(&key).unwrap();
+//! assert!(proof
+//!     .verify_membership::<Sha256>(&tree.root(), &key, b"value")
+//!     .is_ok());
+//!
+//! let other_key = Sha256::digest(b"other key");
+//! let proof = tree.get_proof(&other_key).unwrap();
+//! assert!(proof
+//!     .verify_non_membership::<Sha256>(&tree.root(), &other_key)
+//!     .is_ok());
+//! ```
+
+use crate::error::{FastCryptoError, FastCryptoResult};
+use crate::hash::{Digest, HashFunction};
+use crate::merkle::{LEAF_PREFIX, NODE_PREFIX};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+/// Keys of a sparse Merkle tree are 256-bit digests, e.g., the hash of the application key.
+pub type Key = Digest<32>;
+
+/// The number of bits in a key, which is also the maximal depth of a tree.
+const KEY_BITS: usize = 256;
+
+/// A node of a sparse Merkle tree as kept in a [NodeStore]. Empty subtrees are not stored.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Node<const DIGEST_LEN: usize> {
+    /// An inner node with the hashes of its two children. At most one of them is empty.
+    Internal {
+        left: Digest<DIGEST_LEN>,
+        right: Digest<DIGEST_LEN>,
+    },
+    /// A subtree with a single leaf.
+    Leaf {
+        key: Key,
+        value_hash: Digest<DIGEST_LEN>,
+    },
+}
+
+/// Storage for the nodes of a sparse Merkle tree, indexed by their hashes.
+pub trait NodeStore<const DIGEST_LEN: usize> {
+    /// Get the node with the given hash if it is in the store.
+    fn get(&self, hash: &Digest<DIGEST_LEN>) -> Option<Node<DIGEST_LEN>>;
+
+    /// Add a node with the given hash to the store.
+    fn insert(&mut self, hash: Digest<DIGEST_LEN>, node: Node<DIGEST_LEN>);
+}
+
+/// A [NodeStore] keeping all nodes in memory.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryNodeStore<const DIGEST_LEN: usize> {
+    nodes: HashMap<Digest<DIGEST_LEN>, Node<DIGEST_LEN>>,
+}
+
+impl<const DIGEST_LEN: usize> NodeStore<DIGEST_LEN> for InMemoryNodeStore<DIGEST_LEN> {
+    fn get(&self, hash: &Digest<DIGEST_LEN>) -> Option<Node<DIGEST_LEN>> {
+        self.nodes.get(hash).cloned()
+    }
+
+    fn insert(&mut self, hash: Digest<DIGEST_LEN>, node: Node<DIGEST_LEN>) {
+        self.nodes.insert(hash, node);
+    }
+}
+
+/// The hash of an empty subtree.
+const fn empty_hash<const DIGEST_LEN: usize>() -> Digest<DIGEST_LEN> {
+    Digest {
+        digest: [0u8; DIGEST_LEN],
+    }
+}
+
+/// Compute the hash of a leaf with the given key and value hash.
+pub fn leaf_hash<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize>(
+    key: &Key,
+    value_hash: &Digest<DIGEST_LEN>,
+) -> Digest<DIGEST_LEN> {
+    let mut hash_function = H::default();
+    hash_function.update([LEAF_PREFIX]);
+    hash_function.update(key);
+    hash_function.update(value_hash);
+    hash_function.finalize()
+}
+
+/// Compute the hash of an inner node with the given children.
+fn node_hash<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize>(
+    left: &Digest<DIGEST_LEN>,
+    right: &Digest<DIGEST_LEN>,
+) -> Digest<DIGEST_LEN> {
+    let mut hash_function = H::default();
+    hash_function.update([NODE_PREFIX]);
+    hash_function.update(left);
+    hash_function.update(right);
+    hash_function.finalize()
+}
+
+/// Returns the bit of the key at the given depth, where depth 0 is the most significant bit.
+fn bit(key: &Key, depth: usize) -> bool {
+    key.digest[depth / 8] & (0x80 >> (depth % 8)) != 0
+}
+
+/// The root of a subtree after an update, together with its type which is needed to decide whether
+/// it should be collapsed into its parent.
+#[derive(Clone, Copy)]
+enum Subtree<const DIGEST_LEN: usize> {
+    Empty,
+    Leaf(Digest<DIGEST_LEN>),
+    Internal(Digest<DIGEST_LEN>),
+}
+
+impl<const DIGEST_LEN: usize> Subtree<DIGEST_LEN> {
+    fn hash(&self) -> Digest<DIGEST_LEN> {
+        match self {
+            Subtree::Empty => empty_hash(),
+            Subtree::Leaf(hash) | Subtree::Internal(hash) => *hash,
+        }
+    }
+}
+
+/// A sparse Merkle tree with 256-bit keys, where the values are represented by their hashes.
+pub struct SparseMerkleTree<
+    H: HashFunction<DIGEST_LEN>,
+    const DIGEST_LEN: usize,
+    S: NodeStore<DIGEST_LEN> = InMemoryNodeStore<DIGEST_LEN>,
+> {
+    root: Digest<DIGEST_LEN>,
+    store: S,
+    _hash_function: PhantomData<H>,
+}
+
+impl<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize>
+    SparseMerkleTree<H, DIGEST_LEN, InMemoryNodeStore<DIGEST_LEN>>
+{
+    /// Create a new empty tree which keeps its nodes in memory.
+    pub fn new() -> Self {
+        Self::new_with_store(InMemoryNodeStore::default())
+    }
+}
+
+impl<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize> Default
+    for SparseMerkleTree<H, DIGEST_LEN, InMemoryNodeStore<DIGEST_LEN>>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<H: HashFunction<DIGEST_LEN>, const DIGEST_LEN: usize, S: NodeStore<DIGEST_LEN>>
+    SparseMerkleTree<H, DIGEST_LEN, S>
+{
+    /// Create a new empty tree using the given store.
+    pub fn new_with_store(store: S) -> Self {
+        Self::from_root(empty_hash(), store)
+    }
+
+    /// Open the tree with the given root. The store must contain all the nodes of the tree.
+    pub fn from_root(root: Digest<DIGEST_LEN>, store: S) -> Self {
+        Self {
+            root,
+            store,
+            _hash_function: PhantomData,
+        }
+    }
+
+    /// The root of this tree. The root of the empty tree is the all-zero digest.
+    pub fn root(&self) -> Digest<DIGEST_LEN> {
+        self.root
+    }
+
+    /// The store holding the nodes of this tree.
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Insert the given value at the given key, replacing the existing value if there is one. Returns
+    /// the new root.
+    pub fn insert(&mut self, key: Key, value: &[u8]) -> FastCryptoResult<Digest<DIGEST_LEN>> {
+        self.batch_update([(key, Some(H::digest(value)))])
+    }
+
+    /// Remove the value at the given key if there is one. Returns the new root.
+    pub fn remove(&mut self, key: Key) -> FastCryptoResult<Digest<DIGEST_LEN>> {
+        self.batch_update([(key, None)])
+    }
+
+    /// Get the hash of the value at the given key, or `None` if there is no value.
+    pub fn get(&self, key: &Key) -> FastCryptoResult<Option<Digest<DIGEST_LEN>>> {
+        let proof = self.get_proof(key)?;
+        Ok(proof
+            .leaf
+            .filter(|(leaf_key, _)| leaf_key == key)
+            .map(|(_, value_hash)| value_hash))
+    }
+
+    /// Apply a batch of updates to the tree and return the new root. Each update is a key together
+    /// with the hash of the new value, or `None` if the value should be removed. If a key appears
+    /// more than once, the last update is used.
+    ///
+    /// Only the nodes on the paths to the updated keys are recomputed, and each of them only once.
+    pub fn batch_update<I: IntoIterator<Item = (Key, Option<Digest<DIGEST_LEN>>)>>(
+        &mut self,
+        updates: I,
+    ) -> FastCryptoResult<Digest<DIGEST_LEN>> {
+        let mut new_nodes = Vec::new();
+        let root = self.compute_root(updates, &mut new_nodes)?;
+        for (hash, node) in new_nodes {
+            self.store.insert(hash, node);
+        }
+        self.root = root;
+        Ok(root)
+    }
+
+    /// Compute the root the tree would have after applying the given updates (see
+    /// [SparseMerkleTree::batch_update]) without modifying the tree.
+    pub fn root_after_updates<I: IntoIterator<Item = (Key, Option<Digest<DIGEST_LEN>>)>>(
+        &self,
+        updates: I,
+    ) -> FastCryptoResult<Digest<DIGEST_LEN>> {
+        self.compute_root(updates, &mut Vec::new())
+    }
+
+    fn compute_root<I: IntoIterator<Item = (Key, Option<Digest<DIGEST_LEN>>)>>(
+        &self,
+        updates: I,
+        new_nodes: &mut Vec<(Digest<DIGEST_LEN>, Node<DIGEST_LEN>)>,
+    ) -> FastCryptoResult<Digest<DIGEST_LEN>> {
+        // Sort the updates by key, keeping only the last update for each key. The sort is stable, so
+        // reversing before and after deduplication keeps the last one.
+        let mut updates = updates.into_iter().collect::<Vec<_>>();
+        updates.reverse();
+        updates.sort_by_key(|(key, _)| *key);
+        updates.dedup_by_key(|(key, _)| *key);
+
+        let root = self.get_subtree(&self.root)?;
+        Ok(self.update_subtree(root, 0, &updates, new_nodes)?.hash())
+    }
+
+    /// Apply the updates, which must be sorted and have distinct keys with the same first `depth`
+    /// bits, to the given subtree at the given depth.
+    fn update_subtree(
+        &self,
+        subtree: Subtree<DIGEST_LEN>,
+        depth: usize,
+        updates: &[(Key, Option<Digest<DIGEST_LEN>>)],
+        new_nodes: &mut Vec<(Digest<DIGEST_LEN>, Node<DIGEST_LEN>)>,
+    ) -> FastCryptoResult<Subtree<DIGEST_LEN>> {
+        if updates.is_empty() {
+            return Ok(subtree);
+        }
+        match subtree {
+            Subtree::Empty => Ok(self.build_subtree(
+                depth,
+                &updates
+                    .iter()
+                    .filter_map(|(key, value)| value.map(|value| (*key, value)))
+                    .collect::<Vec<_>>(),
+                new_nodes,
+            )),
+            Subtree::Leaf(hash) => {
+                let (leaf_key, leaf_value) = match self.get_node(&hash)? {
+                    Node::Leaf { key, value_hash } => (key, value_hash),
+                    Node::Internal { .. } => return Err(missing_node()),
+                };
+                // The existing leaf is kept unless it is updated.
+                let mut leaves = updates
+                    .iter()
+                    .filter_map(|(key, value)| value.map(|value| (*key, value)))
+                    .collect::<Vec<_>>();
+                if updates
+                    .binary_search_by_key(&leaf_key, |(key, _)| *key)
+                    .is_err()
+                {
+                    let position = leaves.partition_point(|(key, _)| *key < leaf_key);
+                    leaves.insert(position, (leaf_key, leaf_value));
+                }
+                Ok(self.build_subtree(depth, &leaves, new_nodes))
+            }
+            Subtree::Internal(hash) => {
+                let (left, right) = match self.get_node(&hash)? {
+                    Node::Internal { left, right } => (left, right),
+                    Node::Leaf { .. } => return Err(missing_node()),
+                };
+                let split = updates.partition_point(|(key, _)| !bit(key, depth));
+                let left = self.update_subtree(
+                    self.get_subtree(&left)?,
+                    depth + 1,
+                    &updates[..split],
+                    new_nodes,
+                )?;
+                let right = self.update_subtree(
+                    self.get_subtree(&right)?,
+                    depth + 1,
+                    &updates[split..],
+                    new_nodes,
+                )?;
+                Ok(Self::combine(left, right, new_nodes))
+            }
+        }
+    }
+
+    /// Build a new subtree at the given depth from the given leaves, which must be sorted and have
+    /// distinct keys with the same first `depth` bits.
+    fn build_subtree(
+        &self,
+        depth: usize,
+        leaves: &[(Key, Digest<DIGEST_LEN>)],
+        new_nodes: &mut Vec<(Digest<DIGEST_LEN>, Node<DIGEST_LEN>)>,
+    ) -> Subtree<DIGEST_LEN> {
+        match leaves {
+            [] => Subtree::Empty,
+            [(key, value_hash)] => {
+                let hash = leaf_hash::<H, DIGEST_LEN>(key, value_hash);
+                new_nodes.push((
+                    hash,
+                    Node::Leaf {
+                        key: *key,
+                        value_hash: *value_hash,
+                    },
+                ));
+                Subtree::Leaf(hash)
+            }
+            _ => {
+                // Distinct keys differ before depth KEY_BITS, so this terminates.
+                debug_assert!(depth < KEY_BITS);
+                let split = leaves.partition_point(|(key, _)| !bit(key, depth));
+                let left = self.build_subtree(depth + 1, &leaves[..split], new_nodes);
+                let right = self.build_subtree(depth + 1, &leaves[split..], new_nodes);
+                Self::combine(left, right, new_nodes)
+            }
+        }
+    }
+
+    /// Combine two subtrees into their parent. A single leaf with an empty sibling is moved up.
+    fn combine(
+        left: Subtree<DIGEST_LEN>,
+        right: Subtree<DIGEST_LEN>,
+        new_nodes: &mut Vec<(Digest<DIGEST_LEN>, Node<DIGEST_LEN>)>,
+    ) -> Subtree<DIGEST_LEN> {
+        match (left, right) {
+            (Subtree::Empty, Subtree::Empty) => Subtree::Empty,
+            (Subtree::Leaf(hash), Subtree::Empty) | (Subtree::Empty, Subtree::Leaf(hash)) => {
+                Subtree::Leaf(hash)
+            }
+            _ => {
+                let (left, right) = (left.hash(), right.hash());
+                let hash = node_hash::<H, DIGEST_LEN>(&left, &right);
+                new_nodes.push((hash, Node::Internal { left, right }));
+                Subtree::Internal(hash)
+            }
+        }
+    }
+
+    /// Get a proof for the value at the given key if there is one, or a proof that there is no
+    /// value otherwise.
+    pub fn get_proof(&self, key: &Key) -> FastCryptoResult<SparseMerkleProof<DIGEST_LEN>> {
+        let mut siblings = Vec::new();
+        let mut current = self.root;
+        loop {
+            if current == empty_hash() {
+                return Ok(SparseMerkleProof::new(&siblings, None));
+            }
+            match self.get_node(&current)? {
+                Node::Leaf { key, value_hash } => {
+                    return Ok(SparseMerkleProof::new(&siblings, Some((key, value_hash))));
+                }
+                Node::Internal { left, right } => {
+                    let (next, sibling) = match bit(key, siblings.len()) {
+                        false => (left, right),
+                        true => (right, left),
+                    };
+                    siblings.push(sibling);
+                    current = next;
+                }
+            }
+        }
+    }
+
+    fn get_node(&self, hash: &Digest<DIGEST_LEN>) -> FastCryptoResult<Node<DIGEST_LEN>> {
+        self.store.get(hash).ok_or_else(missing_node)
+    }
+
+    fn get_subtree(&self, hash: &Digest<DIGEST_LEN>) -> FastCryptoResult<Subtree<DIGEST_LEN>> {
+        if *hash == empty_hash() {
+            return Ok(Subtree::Empty);
+        }
+        Ok(match self.get_node(hash)? {
+            Node::Leaf { .. } => Subtree::Leaf(*hash),
+            Node::Internal { .. } => Subtree::Internal(*hash),
+        })
+    }
+}
+
+fn missing_node() -> FastCryptoError {
+    FastCryptoError::GeneralError("Node is missing from the store".to_string())
+}
+
+/// A proof that a key has a given value in a sparse Merkle tree, or that it has no value.
+///
+/// The proof consists of the siblings of the nodes on the path from the root to the node where the
+/// search for the key ended, which is either an empty subtree or a leaf. The siblings which are
+/// empty subtrees are omitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SparseMerkleProof<const DIGEST_LEN: usize> {
+    /// The number of siblings including the empty ones.
+    depth: u16,
+    /// Bit i is set if the sibling at depth i is not empty. The remaining bits of the last byte
+    /// must be zero.
+    non_empty_siblings: Vec<u8>,
+    /// The siblings which are not empty, starting from the root.
+    siblings: Vec<Digest<DIGEST_LEN>>,
+    /// The leaf where the search ended, or `None` if it ended in an empty subtree.
+    leaf: Option<(Key, Digest<DIGEST_LEN>)>,
+}
+
+impl<const DIGEST_LEN: usize> SparseMerkleProof<DIGEST_LEN> {
+    fn new(siblings: &[Digest<DIGEST_LEN>], leaf: Option<(Key, Digest<DIGEST_LEN>)>) -> Self {
+        let mut non_empty_siblings = vec![0u8; siblings.len().div_ceil(8)];
+        for (i, sibling) in siblings.iter().enumerate() {
+            if *sibling != empty_hash() {
+                non_empty_siblings[i / 8] |= 0x80 >> (i % 8);
+            }
+        }
+        Self {
+            depth: siblings.len() as u16,
+            non_empty_siblings,
+            siblings: siblings
+                .iter()
+                .filter(|sibling| **sibling != empty_hash())
+                .copied()
+                .collect(),
+            leaf,
+        }
+    }
+
+    /// Verify that the tree with the given root has the given value at the given key.
+    pub fn verify_membership<H: HashFunction<DIGEST_LEN>>(
+        &self,
+        root: &Digest<DIGEST_LEN>,
+        key: &Key,
+        value: &[u8],
+    ) -> FastCryptoResult<()> {
+        self.verify_value_hash::<H>(root, key, Some(H::digest(value)))
+    }
+
+    /// Verify that the tree with the given root has no value at the given key.
+    pub fn verify_non_membership<H: HashFunction<DIGEST_LEN>>(
+        &self,
+        root: &Digest<DIGEST_LEN>,
+        key: &Key,
+    ) -> FastCryptoResult<()> {
+        self.verify_value_hash::<H>(root, key, None)
+    }
+
+    /// Verify that the tree with the given root has a value with the given hash at the given key, or
+    /// no value if `value_hash` is `None`.
+    pub fn verify_value_hash<H: HashFunction<DIGEST_LEN>>(
+        &self,
+        root: &Digest<DIGEST_LEN>,
+        key: &Key,
+        value_hash: Option<Digest<DIGEST_LEN>>,
+    ) -> FastCryptoResult<()> {
+        let depth = self.depth as usize;
+        if depth > KEY_BITS
+            || self.non_empty_siblings.len() != depth.div_ceil(8)
+            || self.siblings.len() != self.non_empty_sibling_count()
+            || (depth..8 * self.non_empty_siblings.len())
+                .any(|i| self.non_empty_siblings[i / 8] & (0x80 >> (i % 8)) != 0)
+        {
+            return Err(FastCryptoError::InvalidProof);
+        }
+
+        let mut current = match (&self.leaf, value_hash) {
+            (None, None) => empty_hash(),
+            (Some((leaf_key, leaf_value)), Some(value_hash)) => {
+                if leaf_key != key || *leaf_value != value_hash {
+                    return Err(FastCryptoError::InvalidProof);
+                }
+                leaf_hash::<H, DIGEST_LEN>(leaf_key, leaf_value)
+            }
+            (Some((leaf_key, leaf_value)), None) => {
+                // The search ended in a leaf with another key which must be on the path to `key`.
+                if leaf_key == key || (0..depth).any(|i| bit(leaf_key, i) != bit(key, i)) {
+                    return Err(FastCryptoError::InvalidProof);
+                }
+                leaf_hash::<H, DIGEST_LEN>(leaf_key, leaf_value)
+            }
+            (None, Some(_)) => return Err(FastCryptoError::InvalidProof),
+        };
+
+        let mut siblings = self.siblings.iter().rev();
+        for i in (0..depth).rev() {
+            let sibling = match self.non_empty_siblings[i / 8] & (0x80 >> (i % 8)) != 0 {
+                true => *siblings.next().expect("Number of siblings checked above"),
+                false => empty_hash(),
+            };
+            current = match bit(key, i) {
+                false => node_hash::<H, DIGEST_LEN>(&current, &sibling),
+                true => node_hash::<H, DIGEST_LEN>(&sibling, &current),
+            };
+        }
+
+        if current != *root {
+            return Err(FastCryptoError::InvalidProof);
+        }
+        Ok(())
+    }
+
+    fn non_empty_sibling_count(&self) -> usize {
+        self.non_empty_siblings
+            .iter()
+            .map(|b| b.count_ones() as usize)
+            .sum()
+    }
+}
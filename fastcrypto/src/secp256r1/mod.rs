@@ -385,7 +385,7 @@ impl Secp256r1KeyPair {
         let k = fr_p256_to_arkworks(
             &Scalar::from_repr(rfc6979::generate_k::<sha2::Sha256, _>(
                 &x.to_bytes(),
-                &NistP256::ORDER.encode_field_bytes(),
+                &FieldBytesEncoding::<NistP256>::encode_field_bytes(&NistP256::ORDER),
                 &fr_arkworks_to_p256(&z).to_bytes(),
                 &[],
             ))
@@ -3,12 +3,12 @@
 
 use crate::bls12381::min_pk::{BLS12381KeyPair, BLS12381Signature};
 use crate::groups::bls12381::{
-    reduce_mod_uniform_buffer, G1Element, G1ElementUncompressed, G2Element, GTElement, Scalar,
-    G1_ELEMENT_BYTE_LENGTH,
+    reduce_mod_uniform_buffer, G1Element, G1ElementUncompressed, G2Element, GTElement,
+    GTElementCompressed, Scalar, G1_ELEMENT_BYTE_LENGTH, GT_ELEMENT_COMPRESSED_BYTE_LENGTH,
 };
 use crate::groups::{
-    FromTrustedByteArray, GroupElement, HashToGroupElement, MultiScalarMul, Pairing,
-    Scalar as ScalarTrait,
+    BatchNormalize, FromTrustedByteArray, GroupElement, HashToGroupElement, MultiScalarMul,
+    Pairing, Scalar as ScalarTrait,
 };
 use crate::serde_helpers::ToFromByteArray;
 use crate::test_helpers::verify_serialization;
@@ -387,6 +387,98 @@ fn test_pairing_and_hash_to_curve() {
     );
 }
 
+#[test]
+fn test_batch_inverse() {
+    let scalars = (1..=10)
+        .map(|_| Scalar::rand(&mut thread_rng()))
+        .collect::<Vec<_>>();
+    let inverses = Scalar::batch_inverse(&scalars).unwrap();
+    assert_eq!(inverses.len(), scalars.len());
+    for (s, i) in scalars.iter().zip(inverses.iter()) {
+        assert_eq!(*i, s.inverse().unwrap());
+    }
+
+    assert_eq!(
+        Scalar::batch_inverse(&[Scalar::from(2)]).unwrap(),
+        vec![Scalar::from(2).inverse().unwrap()]
+    );
+    assert!(Scalar::batch_inverse(&[]).unwrap().is_empty());
+    assert!(Scalar::batch_inverse(&[Scalar::from(1), Scalar::zero(), Scalar::from(2)]).is_err());
+}
+
+#[test]
+fn test_batch_normalize() {
+    let mut points = (0..10)
+        .map(|i| match i % 4 {
+            0 => G1Element::zero(),
+            _ => G1Element::generator() * Scalar::rand(&mut thread_rng()),
+        })
+        .collect::<Vec<_>>();
+    let expected = points.clone();
+    G1Element::batch_normalize(&mut points);
+    assert_eq!(points, expected);
+    assert_eq!(
+        points.iter().map(|p| p.to_byte_array()).collect::<Vec<_>>(),
+        expected
+            .iter()
+            .map(|p| p.to_byte_array())
+            .collect::<Vec<_>>()
+    );
+    G1Element::batch_normalize(&mut []);
+
+    let mut points = (0..10)
+        .map(|i| match i % 4 {
+            0 => G2Element::zero(),
+            _ => G2Element::generator() * Scalar::rand(&mut thread_rng()),
+        })
+        .collect::<Vec<_>>();
+    let expected = points.clone();
+    G2Element::batch_normalize(&mut points);
+    assert_eq!(points, expected);
+    assert_eq!(
+        G1Element::multi_pairing(&[G1Element::generator(); 10], &points).unwrap(),
+        G1Element::multi_pairing(&[G1Element::generator(); 10], &expected).unwrap()
+    );
+}
+
+#[test]
+fn test_multi_pairing() {
+    let points_g1: Vec<G1Element> = (0..5)
+        .map(|_| G1Element::generator() * Scalar::rand(&mut thread_rng()))
+        .collect();
+    let points_g2: Vec<G2Element> = (0..5)
+        .map(|_| G2Element::generator() * Scalar::rand(&mut thread_rng()))
+        .collect();
+    let expected = points_g1
+        .iter()
+        .zip(points_g2.iter())
+        .map(|(g1, g2)| g1.pairing(g2))
+        .fold(GTElement::zero(), |acc, e| acc + e);
+    assert_eq!(
+        G1Element::multi_pairing(&points_g1, &points_g2).unwrap(),
+        expected
+    );
+    assert!(!G1Element::multi_pairing_is_one(&points_g1, &points_g2).unwrap());
+
+    // e(a*P, b*Q) + e(-b*P, a*Q) = 0
+    let a = Scalar::rand(&mut thread_rng());
+    let b = Scalar::rand(&mut thread_rng());
+    let g1 = G1Element::generator();
+    let g2 = G2Element::generator();
+    assert!(G1Element::multi_pairing_is_one(&[g1 * a, -(g1 * b)], &[g2 * b, g2 * a]).unwrap());
+    assert!(!G1Element::multi_pairing_is_one(&[g1 * a, g1 * b], &[g2 * b, g2 * a]).unwrap());
+
+    // Pairs with a zero element are ignored.
+    assert!(G1Element::multi_pairing_is_one(
+        &[g1 * a, G1Element::zero(), -(g1 * b), g1],
+        &[g2 * b, g2, g2 * a, G2Element::zero()]
+    )
+    .unwrap());
+    assert!(G1Element::multi_pairing_is_one(&[], &[]).unwrap());
+    assert!(G1Element::multi_pairing_is_one(&[G1Element::zero()], &[g2]).unwrap());
+    assert!(G1Element::multi_pairing_is_one(&[g1], &[]).is_err());
+}
+
 #[test]
 fn test_serde_and_regression() {
     let s1 = Scalar::generator();
@@ -745,6 +837,53 @@ fn test_g1_sum() {
     assert_eq!(sum, G1Element::zero());
 }
 
+#[test]
+fn test_gt_compression() {
+    let mut rng = thread_rng();
+    let elements = [
+        GTElement::zero(),
+        GTElement::generator(),
+        -GTElement::generator(),
+        GTElement::generator() * Scalar::rand(&mut rng),
+        G1Element::generator().pairing(&(G2Element::generator() * Scalar::rand(&mut rng))),
+    ];
+    for element in elements {
+        let compressed = GTElementCompressed::from(&element);
+        assert_eq!(GTElement::try_from(&compressed).unwrap(), element);
+        let bytes = compressed.into_byte_array();
+        assert_eq!(
+            GTElement::try_from(&GTElementCompressed::from_byte_array(bytes)).unwrap(),
+            element
+        );
+    }
+
+    // The identity is encoded as all zeros.
+    assert_eq!(
+        GTElementCompressed::from(&GTElement::zero()).into_byte_array(),
+        [0u8; GT_ELEMENT_COMPRESSED_BYTE_LENGTH]
+    );
+
+    // c1 = 0 is only allowed for the identity.
+    let mut bytes = [0u8; GT_ELEMENT_COMPRESSED_BYTE_LENGTH];
+    bytes[GT_ELEMENT_COMPRESSED_BYTE_LENGTH - 1] = 1;
+    assert!(GTElement::try_from(&GTElementCompressed::from_byte_array(bytes)).is_err());
+
+    // Random elements of the torus are not in GT.
+    let mut bytes = [0u8; GT_ELEMENT_COMPRESSED_BYTE_LENGTH];
+    for chunk in bytes.chunks_exact_mut(48) {
+        rng.fill_bytes(&mut chunk[1..]);
+    }
+    assert!(GTElement::try_from(&GTElementCompressed::from_byte_array(bytes)).is_err());
+
+    // Non-canonical field elements are rejected.
+    let mut bytes = GTElementCompressed::from(&GTElement::generator()).into_byte_array();
+    bytes[..48].copy_from_slice(&hex::decode("1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab").unwrap());
+    assert!(GTElement::try_from(&GTElementCompressed::from_byte_array(bytes)).is_err());
+    let mut bytes = [0u8; GT_ELEMENT_COMPRESSED_BYTE_LENGTH];
+    bytes[..48].copy_from_slice(&hex::decode("1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab").unwrap());
+    assert!(GTElement::try_from(&GTElementCompressed::from_byte_array(bytes)).is_err());
+}
+
 #[test]
 fn test_g1_large_sum() {
     let mut rng = thread_rng();
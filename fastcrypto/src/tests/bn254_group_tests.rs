@@ -0,0 +1,273 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::groups::bn254::{G1Element, G2Element, GTElement, Scalar};
+use crate::groups::{
+    BatchNormalize, Doubling, FiatShamirChallenge, GroupElement, HashToGroupElement,
+    MultiScalarMul, Pairing, Scalar as ScalarTrait,
+};
+use crate::serde_helpers::ToFromByteArray;
+use crate::test_helpers::verify_serialization;
+use ark_bn254::{Fq, Fq2, G2Affine};
+use ark_ec::{AffineRepr, CurveGroup};
+use rand::thread_rng;
+
+#[test]
+fn test_arithmetic() {
+    let p = G1Element::generator();
+    let s = Scalar::from(2);
+    assert_eq!(p + p, p.double());
+    assert_eq!(p + p, p * s);
+    assert_eq!(p, ((p + p) / s).unwrap());
+
+    let q = G2Element::generator();
+    assert_eq!(q + q, q.double());
+    assert_eq!(q + q, q * s);
+    assert_eq!(q, ((q + q) / s).unwrap());
+
+    let t = GTElement::generator();
+    assert_eq!(t + t, t * s);
+    assert_eq!(t - t, GTElement::zero());
+    assert_eq!(t, ((t + t) / s).unwrap());
+
+    assert!(Scalar::zero().inverse().is_err());
+}
+
+#[test]
+fn test_pairing() {
+    let a = Scalar::rand(&mut thread_rng());
+    let b = Scalar::rand(&mut thread_rng());
+    let g1 = G1Element::generator();
+    let g2 = G2Element::generator();
+    assert_eq!(g1.pairing(&g2), GTElement::generator());
+    assert_eq!(
+        (g1 * a).pairing(&(g2 * b)),
+        GTElement::generator() * (a * b)
+    );
+    assert_eq!((g1 * (a * b)).pairing(&g2), g1.pairing(&(g2 * (a * b))));
+    assert_eq!(G1Element::zero().pairing(&g2), GTElement::zero());
+
+    // The check done by the Ethereum pairing precompile.
+    assert_eq!(
+        G1Element::multi_pairing(&[g1 * a, -(g1 * b)], &[g2 * b, g2 * a]).unwrap(),
+        GTElement::zero()
+    );
+    assert_eq!(
+        G1Element::multi_pairing(&[g1 * a, g1 * b], &[g2, g2 * a]).unwrap(),
+        (g1 * a).pairing(&g2) + (g1 * b).pairing(&(g2 * a))
+    );
+    assert_eq!(
+        G1Element::multi_pairing(&[], &[]).unwrap(),
+        GTElement::zero()
+    );
+    assert!(G1Element::multi_pairing(&[g1], &[]).is_err());
+    assert!(G1Element::multi_pairing_is_one(&[g1 * a, -(g1 * b)], &[g2 * b, g2 * a]).unwrap());
+    assert!(!G1Element::multi_pairing_is_one(&[g1 * a, g1 * b], &[g2, g2 * a]).unwrap());
+}
+
+#[test]
+fn test_g1_serialization() {
+    // The encodings of G and 2G as used by the Ethereum precompiles.
+    let g = G1Element::generator();
+    assert_eq!(
+        hex::encode(g.to_byte_array()),
+        "00000000000000000000000000000000000000000000000000000000000000010000000000000000000000000000000000000000000000000000000000000002"
+    );
+    assert_eq!(
+        hex::encode(g.double().to_byte_array()),
+        "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd315ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4"
+    );
+
+    for p in [G1Element::zero(), g, g * Scalar::rand(&mut thread_rng())] {
+        assert_eq!(G1Element::from_byte_array(&p.to_byte_array()).unwrap(), p);
+        verify_serialization(&p, Some(p.to_byte_array().as_slice()));
+    }
+    assert_eq!(G1Element::zero().to_byte_array(), [0u8; 64]);
+
+    // Not on the curve.
+    let mut bytes = g.to_byte_array();
+    bytes[63] = 3;
+    assert!(G1Element::from_byte_array(&bytes).is_err());
+
+    // Non-canonical coordinate: x = 1 + p.
+    let mut bytes = g.to_byte_array();
+    bytes[..32].copy_from_slice(
+        &hex::decode("30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd48").unwrap(),
+    );
+    assert!(G1Element::from_byte_array(&bytes).is_err());
+}
+
+#[test]
+fn test_g2_serialization() {
+    // The generator as used by the Ethereum precompiles.
+    let g = G2Element::generator();
+    assert_eq!(
+        hex::encode(g.to_byte_array()),
+        "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+         1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+         090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+         12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"
+    );
+
+    for p in [G2Element::zero(), g, g * Scalar::rand(&mut thread_rng())] {
+        assert_eq!(G2Element::from_byte_array(&p.to_byte_array()).unwrap(), p);
+        verify_serialization(&p, Some(p.to_byte_array().as_slice()));
+    }
+    assert_eq!(G2Element::zero().to_byte_array(), [0u8; 128]);
+
+    // Not on the curve.
+    let mut bytes = g.to_byte_array();
+    bytes[127] ^= 1;
+    assert!(G2Element::from_byte_array(&bytes).is_err());
+
+    // On the curve but not in G2.
+    let point = (1u64..)
+        .find_map(|x| {
+            G2Affine::get_point_from_x_unchecked(Fq2::new(Fq::from(x), Fq::from(0)), false)
+        })
+        .unwrap();
+    assert!(!point.is_in_correct_subgroup_assuming_on_curve());
+    let bytes = G2Element(point.into_group()).to_byte_array();
+    assert!(G2Element::from_byte_array(&bytes).is_err());
+}
+
+#[test]
+fn test_gt_serialization() {
+    for p in [
+        GTElement::zero(),
+        GTElement::generator(),
+        GTElement::generator() * Scalar::rand(&mut thread_rng()),
+    ] {
+        assert_eq!(GTElement::from_byte_array(&p.to_byte_array()).unwrap(), p);
+        verify_serialization(&p, Some(p.to_byte_array().as_slice()));
+    }
+
+    // The element 1 is encoded with a single non-zero coefficient.
+    let mut expected = [0u8; 384];
+    expected[31] = 1;
+    assert_eq!(GTElement::zero().to_byte_array(), expected);
+
+    // The element 2 is not in GT, and 0 is not in the multiplicative group.
+    let mut bytes = [0u8; 384];
+    bytes[31] = 2;
+    assert!(GTElement::from_byte_array(&bytes).is_err());
+    assert!(GTElement::from_byte_array(&[0u8; 384]).is_err());
+}
+
+#[test]
+fn test_scalar_serialization() {
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    assert_eq!(Scalar::generator().to_byte_array(), one);
+
+    let s = Scalar::rand(&mut thread_rng());
+    assert_eq!(Scalar::from_byte_array(&s.to_byte_array()).unwrap(), s);
+    verify_serialization(&s, Some(s.to_byte_array().as_slice()));
+
+    // The group order is not a canonical encoding.
+    let r =
+        hex::decode("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001").unwrap();
+    assert!(Scalar::from_byte_array(&r.try_into().unwrap()).is_err());
+}
+
+#[test]
+fn test_multi_scalar_mul() {
+    let scalars = (0..10)
+        .map(|_| Scalar::rand(&mut thread_rng()))
+        .collect::<Vec<_>>();
+
+    let points = (0..10)
+        .map(|i| G1Element::generator() * Scalar::from(i as u128))
+        .collect::<Vec<_>>();
+    let expected = scalars
+        .iter()
+        .zip(points.iter())
+        .fold(G1Element::zero(), |acc, (s, p)| acc + *p * *s);
+    assert_eq!(
+        G1Element::multi_scalar_mul(&scalars, &points).unwrap(),
+        expected
+    );
+    assert!(G1Element::multi_scalar_mul(&scalars[1..], &points).is_err());
+
+    let points = (0..10)
+        .map(|i| G2Element::generator() * Scalar::from(i as u128))
+        .collect::<Vec<_>>();
+    let expected = scalars
+        .iter()
+        .zip(points.iter())
+        .fold(G2Element::zero(), |acc, (s, p)| acc + *p * *s);
+    assert_eq!(
+        G2Element::multi_scalar_mul(&scalars, &points).unwrap(),
+        expected
+    );
+    assert!(G2Element::multi_scalar_mul(&[], &[]).is_err());
+}
+
+#[test]
+fn test_hash_to_group_element() {
+    let p = G1Element::hash_to_group_element(b"test");
+    assert_ne!(p, G1Element::zero());
+    assert_eq!(p, G1Element::hash_to_group_element(b"test"));
+    assert_ne!(p, G1Element::hash_to_group_element(b"test2"));
+
+    let q = G2Element::hash_to_group_element(b"test");
+    assert_ne!(q, G2Element::zero());
+    assert_eq!(q, G2Element::hash_to_group_element(b"test"));
+    assert_ne!(q, G2Element::hash_to_group_element(b"test2"));
+    assert!(q.0.into_affine().is_in_correct_subgroup_assuming_on_curve());
+    assert_eq!(G2Element::from_byte_array(&q.to_byte_array()).unwrap(), q);
+}
+
+#[test]
+fn test_fiat_shamir_challenge() {
+    let buffer = [7u8; 64];
+    let s = Scalar::fiat_shamir_reduction_to_group_element(&buffer);
+    assert_eq!(s, Scalar::fiat_shamir_reduction_to_group_element(&buffer));
+    assert_ne!(
+        s,
+        Scalar::fiat_shamir_reduction_to_group_element(&buffer[1..])
+    );
+}
+
+#[test]
+fn test_batch_normalize_and_inverse() {
+    let mut points = (0..10)
+        .map(|i| match i % 4 {
+            0 => G1Element::zero(),
+            _ => G1Element::generator() * Scalar::rand(&mut thread_rng()),
+        })
+        .collect::<Vec<_>>();
+    let expected = points.clone();
+    G1Element::batch_normalize(&mut points);
+    assert_eq!(points, expected);
+    assert!(points
+        .iter()
+        .all(|p| p.0.z == Fq::from(1) || p.0.z == Fq::from(0)));
+
+    let mut points = (0..10)
+        .map(|i| match i % 4 {
+            0 => G2Element::zero(),
+            _ => G2Element::generator() * Scalar::rand(&mut thread_rng()),
+        })
+        .collect::<Vec<_>>();
+    let expected = points.clone();
+    G2Element::batch_normalize(&mut points);
+    assert_eq!(points, expected);
+    assert_eq!(
+        points.iter().map(|p| p.to_byte_array()).collect::<Vec<_>>(),
+        expected
+            .iter()
+            .map(|p| p.to_byte_array())
+            .collect::<Vec<_>>()
+    );
+
+    let scalars = (0..10)
+        .map(|_| Scalar::rand(&mut thread_rng()))
+        .collect::<Vec<_>>();
+    let inverses = Scalar::batch_inverse(&scalars).unwrap();
+    assert!(scalars
+        .iter()
+        .zip(inverses.iter())
+        .all(|(s, i)| *s * i == Scalar::generator()));
+    assert!(Scalar::batch_inverse(&[Scalar::generator(), Scalar::zero()]).is_err());
+}
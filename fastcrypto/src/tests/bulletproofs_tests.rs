@@ -1,9 +1,12 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
+use crate::groups::ristretto255::{RistrettoPoint, RistrettoScalar};
+use crate::groups::{GroupElement, HashToGroupElement, Scalar};
 use crate::{
-    bulletproofs::{BulletproofsRangeProof, PedersenCommitment},
+    bulletproofs::{BulletproofsRangeProof, PedersenCommitment, PedersenGenerators},
     traits::ToFromBytes,
 };
+use rand::thread_rng;
 
 ///
 /// Test Pedersen Commitments
@@ -134,6 +137,240 @@ fn test_handle_verify_invalid_upper_bound() {
         .is_err());
 }
 
+fn random_blindings(n: usize) -> Vec<RistrettoScalar> {
+    (0..n)
+        .map(|_| RistrettoScalar::rand(&mut thread_rng()))
+        .collect()
+}
+
+fn custom_generators() -> PedersenGenerators {
+    PedersenGenerators::new(
+        RistrettoPoint::hash_to_group_element(b"value generator"),
+        RistrettoPoint::hash_to_group_element(b"blinding generator"),
+    )
+}
+
+#[test]
+fn test_default_generators() {
+    let value = [3; 32];
+    let blinding = [4; 32];
+    let commitment = PedersenCommitment::new(value, blinding);
+    let commitment_with_generators = PedersenCommitment::new_with_generators(
+        &RistrettoScalar::from_bytes_mod_order(&value),
+        &RistrettoScalar::from_bytes_mod_order(&blinding),
+        &PedersenGenerators::default(),
+    );
+    assert_eq!(commitment, commitment_with_generators);
+    assert_eq!(
+        PedersenGenerators::default().value_generator(),
+        RistrettoPoint::generator()
+    );
+
+    // Commitments may be computed elsewhere.
+    let point = RistrettoPoint::from(&commitment);
+    assert_eq!(PedersenCommitment::from(point), commitment);
+}
+
+#[test]
+fn test_aggregated_range_proof() {
+    let generators = custom_generators();
+    for m in [1, 2, 3, 4, 7] {
+        let values = (0..m).map(|i| 1000 * i as u64).collect::<Vec<_>>();
+        let blindings = random_blindings(m);
+        let (commitments, proof) = BulletproofsRangeProof::prove_bit_length_multiple(
+            &values,
+            &blindings,
+            32,
+            &generators,
+            TEST_DOMAIN,
+        )
+        .unwrap();
+
+        // The commitments are the ones the caller would compute with the same generators.
+        for ((value, blinding), commitment) in values.iter().zip(&blindings).zip(&commitments) {
+            assert_eq!(
+                *commitment,
+                PedersenCommitment::new_with_generators(
+                    &RistrettoScalar::from(*value as u128),
+                    blinding,
+                    &generators
+                )
+            );
+        }
+        assert!(proof
+            .verify_bit_length_multiple(&commitments, 32, &generators, TEST_DOMAIN)
+            .is_ok());
+
+        // Wrong generators, bit length, domain or commitments.
+        assert!(proof
+            .verify_bit_length_multiple(
+                &commitments,
+                32,
+                &PedersenGenerators::default(),
+                TEST_DOMAIN
+            )
+            .is_err());
+        assert!(proof
+            .verify_bit_length_multiple(&commitments, 64, &generators, TEST_DOMAIN)
+            .is_err());
+        assert!(proof
+            .verify_bit_length_multiple(&commitments, 32, &generators, b"OTHER")
+            .is_err());
+        let mut reversed = commitments.clone();
+        reversed.reverse();
+        assert!(
+            m == 1
+                || proof
+                    .verify_bit_length_multiple(&reversed, 32, &generators, TEST_DOMAIN)
+                    .is_err()
+        );
+    }
+
+    // Values must be in range.
+    assert!(BulletproofsRangeProof::prove_bit_length_multiple(
+        &[1, 256],
+        &random_blindings(2),
+        8,
+        &generators,
+        TEST_DOMAIN
+    )
+    .is_err());
+    assert!(BulletproofsRangeProof::prove_bit_length_multiple(
+        &[1, 2],
+        &random_blindings(1),
+        8,
+        &generators,
+        TEST_DOMAIN
+    )
+    .is_err());
+}
+
+#[test]
+fn test_batch_verify() {
+    let generators = custom_generators();
+    let proofs = [1, 2, 3, 4]
+        .into_iter()
+        .map(|m| {
+            let values = (0..m).map(|i| (i as u64) << 20).collect::<Vec<_>>();
+            BulletproofsRangeProof::prove_bit_length_multiple(
+                &values,
+                &random_blindings(m),
+                64,
+                &generators,
+                TEST_DOMAIN,
+            )
+            .unwrap()
+        })
+        .collect::<Vec<_>>();
+    let batch = proofs
+        .iter()
+        .map(|(commitments, proof)| (proof, commitments.as_slice()))
+        .collect::<Vec<_>>();
+    assert!(BulletproofsRangeProof::batch_verify_bit_length(
+        &batch,
+        64,
+        &generators,
+        TEST_DOMAIN,
+        &mut thread_rng()
+    )
+    .is_ok());
+    assert!(BulletproofsRangeProof::batch_verify_bit_length(
+        &[],
+        64,
+        &generators,
+        TEST_DOMAIN,
+        &mut thread_rng()
+    )
+    .is_ok());
+    assert!(BulletproofsRangeProof::batch_verify_bit_length(
+        &batch,
+        32,
+        &generators,
+        TEST_DOMAIN,
+        &mut thread_rng()
+    )
+    .is_err());
+
+    // Replace a commitment in one of the proofs.
+    let mut modified = proofs[2].0.clone();
+    modified[1] = modified[0].clone();
+    let mut invalid_batch = batch.clone();
+    invalid_batch[2] = (&proofs[2].1, &modified);
+    assert!(BulletproofsRangeProof::batch_verify_bit_length(
+        &invalid_batch,
+        64,
+        &generators,
+        TEST_DOMAIN,
+        &mut thread_rng()
+    )
+    .is_err());
+}
+
+#[test]
+fn test_arbitrary_range_proof() {
+    let generators = custom_generators();
+    let blinding = RistrettoScalar::rand(&mut thread_rng());
+    for (value, lower, upper) in [
+        (5, 5, 5),
+        (18, 18, 65),
+        (65, 18, 65),
+        (1000, 300, 100_000),
+        (u64::MAX, 0, u64::MAX),
+        (u64::MAX - 1, u64::MAX - 100, u64::MAX - 1),
+    ] {
+        let (commitment, proof) = BulletproofsRangeProof::prove_range(
+            value,
+            &blinding,
+            lower,
+            upper,
+            &generators,
+            TEST_DOMAIN,
+        )
+        .unwrap();
+        assert_eq!(
+            commitment,
+            PedersenCommitment::new_with_generators(
+                &RistrettoScalar::from(value as u128),
+                &blinding,
+                &generators
+            )
+        );
+        assert!(proof
+            .verify_range(&commitment, lower, upper, &generators, TEST_DOMAIN)
+            .is_ok());
+        assert!(
+            upper == u64::MAX
+                || proof
+                    .verify_range(&commitment, lower + 1, upper + 1, &generators, TEST_DOMAIN)
+                    .is_err()
+        );
+    }
+
+    // Values outside the range cannot be proven.
+    assert!(
+        BulletproofsRangeProof::prove_range(17, &blinding, 18, 65, &generators, TEST_DOMAIN)
+            .is_err()
+    );
+    assert!(
+        BulletproofsRangeProof::prove_range(66, &blinding, 18, 65, &generators, TEST_DOMAIN)
+            .is_err()
+    );
+    assert!(
+        BulletproofsRangeProof::prove_range(20, &blinding, 65, 18, &generators, TEST_DOMAIN)
+            .is_err()
+    );
+
+    // A proof for a commitment to a value outside the range does not verify.
+    let (_, proof) =
+        BulletproofsRangeProof::prove_range(20, &blinding, 18, 65, &generators, TEST_DOMAIN)
+            .unwrap();
+    let commitment =
+        PedersenCommitment::new_with_generators(&RistrettoScalar::from(17), &blinding, &generators);
+    assert!(proof
+        .verify_range(&commitment, 18, 65, &generators, TEST_DOMAIN)
+        .is_err());
+}
+
 use crate::serde_helpers::ToFromByteArray;
 use proptest::arbitrary::Arbitrary;
 
@@ -0,0 +1,142 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ed25519::{Ed25519KeyPair, Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature};
+use crate::groups::ed25519::{EdwardsPoint, Scalar};
+use crate::groups::Scalar as ScalarTrait;
+use crate::groups::{Doubling, GroupElement, HashToGroupElement, MultiScalarMul};
+use crate::serde_helpers::ToFromByteArray;
+use crate::test_helpers::verify_serialization;
+use crate::traits::{KeyPair, ToFromBytes, VerifyingKey};
+use rand::thread_rng;
+
+/// A point of order 8.
+const ORDER_8_POINT: &str = "26e8958fc2b227b045c3f489f2ef98f0d5dfac05d3c63339b13802886d53fc05";
+
+fn order_8_point() -> EdwardsPoint {
+    EdwardsPoint::from_byte_array(&hex::decode(ORDER_8_POINT).unwrap().try_into().unwrap()).unwrap()
+}
+
+#[test]
+fn test_arithmetic() {
+    let p = EdwardsPoint::generator();
+    let two_p = p + p;
+    let s = Scalar::from(2);
+    assert_eq!(two_p, p.double());
+    assert_eq!(two_p, p * s);
+    assert_eq!(p, (two_p / s).unwrap());
+    assert_eq!(p - p, EdwardsPoint::zero());
+}
+
+#[test]
+fn test_serialization() {
+    let g = EdwardsPoint::generator();
+    assert_eq!(
+        hex::encode(g.to_byte_array()),
+        "5866666666666666666666666666666666666666666666666666666666666666"
+    );
+
+    for p in [
+        EdwardsPoint::zero(),
+        g,
+        g * Scalar::rand(&mut thread_rng()),
+        order_8_point(),
+    ] {
+        assert_eq!(
+            EdwardsPoint::from_byte_array(&p.to_byte_array()).unwrap(),
+            p
+        );
+        verify_serialization(&p, Some(p.to_byte_array().as_slice()));
+    }
+
+    // The y-coordinate p = 2^255 - 19 is a non-canonical encoding of y = 0.
+    let mut non_canonical = [0xffu8; 32];
+    non_canonical[0] = 0xed;
+    non_canonical[31] = 0x7f;
+    assert!(EdwardsPoint::from_byte_array(&non_canonical).is_err());
+    assert!(EdwardsPoint::from_byte_array(&[0u8; 32]).is_ok());
+
+    // y = 2 is not on the curve.
+    let mut invalid = [0u8; 32];
+    invalid[0] = 2;
+    assert!(EdwardsPoint::from_byte_array(&invalid).is_err());
+}
+
+#[test]
+fn test_torsion() {
+    let t = order_8_point();
+    assert!(t.is_small_order());
+    assert!(!t.is_torsion_free());
+    assert_ne!(t * Scalar::from(4), EdwardsPoint::zero());
+    assert_eq!(t.mul_by_cofactor(), EdwardsPoint::zero());
+
+    assert!(EdwardsPoint::zero().is_small_order());
+    assert!(EdwardsPoint::zero().is_torsion_free());
+
+    let p = EdwardsPoint::generator() * Scalar::rand(&mut thread_rng());
+    assert!(!p.is_small_order());
+    assert!(p.is_torsion_free());
+
+    let mixed = p + t;
+    assert!(!mixed.is_small_order());
+    assert!(!mixed.is_torsion_free());
+    assert!(mixed.mul_by_cofactor().is_torsion_free());
+    assert_eq!(mixed.mul_by_cofactor(), p * Scalar::from(8));
+}
+
+#[test]
+fn test_multi_scalar_mul() {
+    let scalars = (0..10)
+        .map(|_| Scalar::rand(&mut thread_rng()))
+        .collect::<Vec<_>>();
+    let points = (0..10)
+        .map(|i| EdwardsPoint::generator() * Scalar::from(i as u128))
+        .collect::<Vec<_>>();
+    let expected = scalars
+        .iter()
+        .zip(points.iter())
+        .fold(EdwardsPoint::zero(), |acc, (s, p)| acc + *p * *s);
+    assert_eq!(
+        EdwardsPoint::multi_scalar_mul(&scalars, &points).unwrap(),
+        expected
+    );
+    assert!(EdwardsPoint::multi_scalar_mul(&scalars[1..], &points).is_err());
+}
+
+#[test]
+fn test_public_key_conversion() {
+    let kp = Ed25519KeyPair::generate(&mut thread_rng());
+    let a = EdwardsPoint::from(kp.public());
+    assert_eq!(
+        a,
+        EdwardsPoint::generator()
+            * Scalar::from(&Ed25519PrivateKey::from_bytes(kp.as_ref()).unwrap())
+    );
+    assert_eq!(a.to_byte_array().as_slice(), kp.public().as_ref());
+    assert_eq!(&Ed25519PublicKey::from(&a), kp.public());
+}
+
+#[test]
+fn test_standard_signature() {
+    // Sign a message as in RFC 8032 using the group operations and check that the signature is
+    // accepted by the Ed25519 implementation.
+    let kp = Ed25519KeyPair::generate(&mut thread_rng());
+    let a = Scalar::from(&Ed25519PrivateKey::from_bytes(kp.as_ref()).unwrap());
+    let msg = b"Hello, world!";
+
+    let r = Scalar::rand(&mut thread_rng());
+    let big_r = EdwardsPoint::generator() * r;
+    let k = Scalar::hash_to_group_element(
+        &[
+            big_r.to_byte_array().as_slice(),
+            kp.public().as_ref(),
+            msg.as_slice(),
+        ]
+        .concat(),
+    );
+    let s = r + k * a;
+
+    let signature =
+        Ed25519Signature::from_bytes(&[big_r.to_byte_array(), s.to_byte_array()].concat()).unwrap();
+    assert!(kp.public().verify(msg, &signature).is_ok());
+}
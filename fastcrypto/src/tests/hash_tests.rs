@@ -3,8 +3,9 @@
 
 use crate::encoding::{Base64, Encoding};
 use crate::hash::{
-    Blake2b256, Digest, EllipticCurveMultisetHash, HashFunction, Keccak256, MultisetHash, Sha256,
-    Sha3_256, Sha3_512, Sha512,
+    Blake2b256, Blake3, Blake3Xof, CShake128, CShake256, Digest, EllipticCurveMultisetHash,
+    ExtendableOutputFunction, HashFunction, Keccak256, Kmac128, Kmac256, LtHash, MultisetHash,
+    Sha256, Sha384, Sha3_256, Sha3_384, Sha3_512, Sha512, Shake128, Shake256, LT_HASH_LIMBS,
 };
 use std::io::Write;
 
@@ -137,6 +138,181 @@ fn test_blake2b_256() {
     );
 }
 
+#[test]
+fn test_sha384() {
+    let digest = Sha384::digest(b"abc");
+    assert_eq!(
+        digest.as_ref(),
+        hex::decode("cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7").unwrap()
+    );
+}
+
+#[test]
+fn test_sha3_384() {
+    let digest = Sha3_384::digest(b"abc");
+    assert_eq!(
+        digest.as_ref(),
+        hex::decode("ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25").unwrap()
+    );
+}
+
+#[test]
+fn test_blake3() {
+    let digest = Blake3::digest(b"abc");
+    assert_eq!(
+        digest.as_ref(),
+        hex::decode("6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85").unwrap()
+    );
+
+    // The XOF output extends the digest.
+    let output = Blake3Xof::digest(b"abc", 64);
+    assert_eq!(output[..32], digest.digest);
+    assert_eq!(
+        output,
+        hex::decode("6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d851fb250ae7393f5d02813b65d521a0d492d9ba09cf7ce7f4cffd900f23374bf0b").unwrap()
+    );
+
+    let mut keyed = Blake3::new_keyed(&[7u8; 32]);
+    keyed.update(b"abc");
+    assert_eq!(
+        keyed.finalize().as_ref(),
+        hex::decode("b1495225f01fa8cd09e410d288d09b68c1cc9fb2414686c0d3ac13fc905497d9").unwrap()
+    );
+    let mut keyed_xof = Blake3Xof::new_keyed(&[7u8; 32]);
+    keyed_xof.update(b"abc");
+    assert_eq!(
+        keyed_xof.finalize(32),
+        hex::decode("b1495225f01fa8cd09e410d288d09b68c1cc9fb2414686c0d3ac13fc905497d9").unwrap()
+    );
+
+    let mut derive_key = Blake3::new_derive_key("fastcrypto 2024 test context");
+    derive_key.update(b"abc");
+    assert_eq!(
+        derive_key.finalize().as_ref(),
+        hex::decode("213fde0e08ed77f68d04b4b97470f574408a6148712813d2f4ac6ea63294021b").unwrap()
+    );
+}
+
+#[test]
+fn test_shake() {
+    assert_eq!(
+        Shake128::digest(b"", 32),
+        hex::decode("7f9c2ba4e88f827d616045507605853ed73b8093f6efbc88eb1a6eacfa66ef26").unwrap()
+    );
+    assert_eq!(
+        Shake256::digest(b"", 64),
+        hex::decode("46b9dd2b0ba88d13233b3feb743eeb243fcd52ea62b81b82b50c27646ed5762fd75dc4ddd8c0f200cb05019d67b592f6fc821c49479ab48640292eacb3b7c4be").unwrap()
+    );
+
+    // Output can be read incrementally.
+    let mut reader = Shake256::default().finalize_xof();
+    let mut first = [0u8; 10];
+    let mut second = [0u8; 54];
+    digest::XofReader::read(&mut reader, &mut first);
+    digest::XofReader::read(&mut reader, &mut second);
+    assert_eq!(
+        [first.to_vec(), second.to_vec()].concat(),
+        Shake256::digest(b"", 64)
+    );
+}
+
+#[test]
+fn test_cshake() {
+    // Sample #1 and #3 from https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values.
+    let mut cshake = CShake128::new(b"Email Signature");
+    cshake.update([0u8, 1, 2, 3]);
+    assert_eq!(
+        cshake.finalize(32),
+        hex::decode("c1c36925b6409a04f1b504fcbca9d82b4017277cb5ed2b2065fc1d3814d5aaf5").unwrap()
+    );
+
+    let mut cshake = CShake256::new(b"Email Signature");
+    cshake.update([0u8, 1, 2, 3]);
+    assert_eq!(
+        cshake.finalize(64),
+        hex::decode("d008828e2b80ac9d2218ffee1d070c48b8e4c87bff32c9699d5b6896eee0edd164020e2be0560858d9c00c037e34a96937c561a74c412bb4c746469527281c8c").unwrap()
+    );
+
+    // Without a function name and customization string, cSHAKE is equal to SHAKE.
+    let mut cshake = CShake128::new(b"");
+    cshake.update(b"abc");
+    assert_eq!(cshake.finalize(32), Shake128::digest(b"abc", 32));
+}
+
+#[test]
+fn test_kmac() {
+    // Samples from https://csrc.nist.gov/projects/cryptographic-standards-and-guidelines/example-values.
+    let key = (0x40u8..=0x5f).collect::<Vec<_>>();
+    let short_data = [0u8, 1, 2, 3];
+    let long_data = (0u8..=0xc7).collect::<Vec<_>>();
+    let tag = b"My Tagged Application";
+
+    let kmac128 = |data: &[u8], customization: &[u8]| {
+        let mut kmac = Kmac128::new(&key, customization);
+        kmac.update(data);
+        let mut output = [0u8; 32];
+        kmac.finalize_mac(&mut output);
+        output.to_vec()
+    };
+    assert_eq!(
+        kmac128(&short_data, b""),
+        hex::decode("e5780b0d3ea6f7d3a429c5706aa43a00fadbd7d49628839e3187243f456ee14e").unwrap()
+    );
+    assert_eq!(
+        kmac128(&short_data, tag),
+        hex::decode("3b1fba963cd8b0b59e8c1a6d71888b7143651af8ba0a7070c0979e2811324aa5").unwrap()
+    );
+    assert_eq!(
+        kmac128(&long_data, tag),
+        hex::decode("1f5b4e6cca02209e0dcb5ca635b89a15e271ecc760071dfd805faa38f9729230").unwrap()
+    );
+
+    let mut kmac = Kmac256::new(&key, tag);
+    kmac.update(short_data);
+    let mut output = [0u8; 64];
+    kmac.finalize_mac(&mut output);
+    assert_eq!(
+        output.to_vec(),
+        hex::decode("20c570c31346f703c9ac36c61c03cb64c3970d0cfc787e9b79599d273a68d2f7f69d4cc3de9d104a351689f27cf6f5951f0103f33f4f24871024d9c27773a8dd").unwrap()
+    );
+
+    // KMACXOF
+    let mut kmac = Kmac128::new(&key, b"");
+    kmac.update(short_data);
+    assert_eq!(
+        kmac.finalize(32),
+        hex::decode("cd83740bbd92ccc8cf032b1481a0f4460e7ca9dd12b08a0c4031178bacd6ec35").unwrap()
+    );
+
+    let mut kmac = Kmac256::new(&key, tag);
+    kmac.update(&long_data);
+    assert_eq!(
+        kmac.finalize(64),
+        hex::decode("d5be731c954ed7732846bb59dbe3a8e30f83e77a4bff4459f2f1c2b4ecebb8ce67ba01c62e8ab8578d2d499bd1bb276768781190020a306a97de281dcc30305d").unwrap()
+    );
+}
+
+#[test]
+fn test_digest_reader() {
+    // Use more data than fits in the internal buffer.
+    let data = (0..20000).map(|i| i as u8).collect::<Vec<_>>();
+    assert_eq!(
+        Sha256::digest_reader(data.as_slice()).unwrap(),
+        Sha256::digest(&data)
+    );
+
+    let mut hash_function = Blake3::default();
+    assert_eq!(
+        hash_function.update_reader(data.as_slice()).unwrap(),
+        data.len() as u64
+    );
+    assert_eq!(hash_function.finalize(), Blake3::digest(&data));
+
+    let mut xof = Shake128::default();
+    xof.update_reader(data.as_slice()).unwrap();
+    assert_eq!(xof.finalize(100), Shake128::digest(&data, 100));
+}
+
 #[test]
 fn test_accumulator() {
     let mut accumulator = EllipticCurveMultisetHash::default();
@@ -198,6 +374,72 @@ fn test_accumulator() {
     );
 }
 
+#[test]
+fn test_lt_hash() {
+    let mut accumulator = LtHash::<Blake3Xof>::default();
+
+    // Two different multisets should give different hashes
+    accumulator.insert(b"Hello");
+    let check1 = accumulator.clone();
+    accumulator.insert(b"World");
+    assert_ne!(check1, accumulator);
+    assert_ne!(check1.digest(), accumulator.digest());
+
+    // Hashing the same elements in any order should give the same hash
+    let mut accumulator2 = LtHash::<Blake3Xof>::default();
+    accumulator2.insert_all([b"World", b"Hello"]);
+    assert_eq!(accumulator, accumulator2);
+    assert_eq!(accumulator.digest(), accumulator2.digest());
+
+    // The union of two accumulators should be equal to if all elements were inserted into a single accumulator
+    let mut accumulator3 = LtHash::<Blake3Xof>::default();
+    accumulator3.insert(b"World");
+    accumulator3.union(&check1);
+    assert_eq!(accumulator, accumulator3);
+
+    // Adding the same element twice should give a different hash, and removing them again gives
+    // the original hash
+    accumulator2.insert_all([b"!", b"!"]);
+    accumulator3.insert(b"!");
+    assert_ne!(accumulator2, accumulator3);
+    accumulator2.remove_all([b"!", b"!"]);
+    assert_eq!(accumulator, accumulator2);
+
+    // Removing an element which was never inserted and then inserting it again
+    accumulator2.remove(b"?");
+    assert_ne!(accumulator, accumulator2);
+    accumulator2.insert(b"?");
+    assert_eq!(accumulator, accumulator2);
+
+    // The hash of an element is the XOF output interpreted as little-endian limbs
+    let mut single = LtHash::<Shake128>::default();
+    single.insert(b"Hello");
+    let expected = Shake128::digest(b"Hello", 2 * LT_HASH_LIMBS);
+    assert_eq!(single.to_bytes().to_vec(), expected);
+
+    // The digest of the empty multiset is the hash of the all-zero state
+    assert_eq!(
+        LtHash::<Blake3Xof>::default().digest().as_ref(),
+        Blake3Xof::digest([0u8; 2 * LT_HASH_LIMBS], 32)
+    );
+
+    // The XOF is part of the construction
+    let mut shake = LtHash::<Shake128>::default();
+    shake.insert_all([b"Hello", b"World"]);
+    assert_ne!(shake.digest(), accumulator.digest());
+}
+
+#[test]
+fn test_lt_hash_serde() {
+    let mut accumulator = LtHash::<Blake3Xof>::default();
+    accumulator.insert_all([b"Hello", b"World"]);
+    let serialized = bincode::serialize(&accumulator).unwrap();
+    assert_eq!(serialized, accumulator.to_bytes());
+    let deserialized: LtHash = bincode::deserialize(&serialized).unwrap();
+    assert_eq!(deserialized, accumulator);
+    assert!(bincode::deserialize::<LtHash>(&serialized[1..]).is_err());
+}
+
 #[test]
 fn test_digest_debug() {
     let digest = Sha256::digest(b"Hello World");
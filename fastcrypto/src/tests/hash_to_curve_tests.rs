@@ -0,0 +1,246 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::bls12381::min_pk::DST_G2;
+use crate::bls12381::min_sig::DST_G1;
+use crate::groups::bls12381::{G1Element, G2Element};
+use crate::groups::hash_to_curve::{
+    expand_message_xmd, expand_message_xof, hash_to_field, EncodeToCurve, ExpandMessage,
+    ExpandMsgXmd, ExpandMsgXof, HashToCurve, HashToField,
+};
+use crate::groups::ristretto255::{RistrettoPoint, RistrettoScalar};
+use crate::groups::{bls12381, secp256r1, GroupElement, HashToGroupElement};
+use crate::hash::{Sha256, Sha512, Shake128, Shake256};
+use crate::serde_helpers::ToFromByteArray;
+use ark_ff::PrimeField;
+use elliptic_curve::hash2curve::{ExpandMsg, Expander};
+
+/// Compute the output of expand_message using the implementation from the elliptic-curve crate.
+fn reference_expand<'a, E: ExpandMsg<'a>>(
+    msg: &[u8],
+    dst: &'a [&'a [u8]],
+    len_in_bytes: usize,
+) -> Vec<u8> {
+    let mut output = vec![0u8; len_in_bytes];
+    E::expand_message(&[msg], dst, len_in_bytes)
+        .unwrap()
+        .fill_bytes(&mut output);
+    output
+}
+
+#[test]
+fn test_expand_message_xmd_vectors() {
+    // Test vectors from Appendix K.1 of RFC 9380.
+    let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+    assert_eq!(
+        hex::encode(expand_message_xmd::<Sha256, 32>(b"", dst, 0x20).unwrap()),
+        "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+    );
+    assert_eq!(
+        hex::encode(expand_message_xmd::<Sha256, 32>(b"abc", dst, 0x20).unwrap()),
+        "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+    );
+}
+
+#[test]
+fn test_expand_message_xmd() {
+    let long_dst = [7u8; 300];
+    let dsts: [&[u8]; 3] = [b"DST", b"QUUX-V01-CS02-with-expander-SHA256-128", &long_dst];
+    for dst in dsts {
+        for msg in [b"".as_slice(), b"abc", &[1u8; 1000]] {
+            for len in [1, 32, 33, 128, 255, 8160] {
+                assert_eq!(
+                    expand_message_xmd::<Sha256, 32>(msg, dst, len).unwrap(),
+                    reference_expand::<elliptic_curve::hash2curve::ExpandMsgXmd<sha2::Sha256>>(
+                        msg,
+                        &[dst],
+                        len
+                    )
+                );
+                assert_eq!(
+                    expand_message_xmd::<Sha512, 64>(msg, dst, len).unwrap(),
+                    reference_expand::<elliptic_curve::hash2curve::ExpandMsgXmd<sha2::Sha512>>(
+                        msg,
+                        &[dst],
+                        len
+                    )
+                );
+            }
+        }
+    }
+
+    // Invalid inputs.
+    assert!(expand_message_xmd::<Sha256, 32>(b"abc", b"", 32).is_err());
+    assert!(expand_message_xmd::<Sha256, 32>(b"abc", b"DST", 255 * 32 + 1).is_err());
+    assert!(expand_message_xmd::<Sha256, 32>(b"abc", b"DST", 255 * 32).is_ok());
+
+    // The trait gives the same result.
+    assert_eq!(
+        ExpandMsgXmd::<Sha256, 32>::expand_message(b"abc", b"DST", 100).unwrap(),
+        expand_message_xmd::<Sha256, 32>(b"abc", b"DST", 100).unwrap()
+    );
+}
+
+#[test]
+fn test_expand_message_xof() {
+    let long_dst = [7u8; 300];
+    let dsts: [&[u8]; 3] = [b"DST", b"QUUX-V01-CS02-with-expander-SHAKE128", &long_dst];
+    for dst in dsts {
+        for msg in [b"".as_slice(), b"abc", &[1u8; 1000]] {
+            for len in [1, 32, 128, 1000] {
+                assert_eq!(
+                    expand_message_xof::<Shake128>(msg, dst, len, 128).unwrap(),
+                    reference_expand::<elliptic_curve::hash2curve::ExpandMsgXof<sha3::Shake128>>(
+                        msg,
+                        &[dst],
+                        len
+                    )
+                );
+            }
+        }
+    }
+    assert_eq!(
+        ExpandMsgXof::<Shake128>::expand_message(b"abc", b"DST", 100).unwrap(),
+        expand_message_xof::<Shake128>(b"abc", b"DST", 100, 128).unwrap()
+    );
+
+    // The security level only matters for long DSTs.
+    assert_eq!(
+        expand_message_xof::<Shake256>(b"abc", b"DST", 100, 256).unwrap(),
+        expand_message_xof::<Shake256>(b"abc", b"DST", 100, 128).unwrap()
+    );
+    assert_ne!(
+        expand_message_xof::<Shake256>(b"abc", &long_dst, 100, 256).unwrap(),
+        expand_message_xof::<Shake256>(b"abc", &long_dst, 100, 128).unwrap()
+    );
+
+    assert!(expand_message_xof::<Shake128>(b"abc", b"", 32, 128).is_err());
+    assert!(expand_message_xof::<Shake128>(b"abc", b"DST", 65536, 128).is_err());
+}
+
+fn test_hash_to_field_for<F: HashToField + GroupElement>() {
+    let elements = hash_to_field::<F, ExpandMsgXmd<Sha256, 32>>(b"msg", b"DST", 3).unwrap();
+    assert_eq!(elements.len(), 3);
+    assert_ne!(elements[0], elements[1]);
+
+    // The first element depends on the number of elements requested, since the requested length
+    // is part of the input to expand_message.
+    let other = hash_to_field::<F, ExpandMsgXmd<Sha256, 32>>(b"msg", b"DST", 2).unwrap();
+    assert_ne!(elements[0], other[0]);
+
+    // Different DSTs give different elements.
+    let other = hash_to_field::<F, ExpandMsgXmd<Sha256, 32>>(b"msg", b"DST2", 3).unwrap();
+    assert_ne!(elements, other);
+
+    // The bytes are interpreted as a big-endian integer.
+    let mut bytes = vec![0u8; F::L];
+    bytes[F::L - 1] = 5;
+    bytes[F::L - 2] = 1;
+    assert_eq!(
+        F::from_uniform_bytes(&bytes),
+        F::generator()
+            + F::generator()
+            + F::generator()
+            + F::generator()
+            + F::generator()
+            + (0..256).fold(F::zero(), |acc, _| acc + F::generator())
+    );
+
+    assert!(hash_to_field::<F, ExpandMsgXmd<Sha256, 32>>(b"msg", b"", 1).is_err());
+}
+
+#[test]
+fn test_hash_to_field() {
+    test_hash_to_field_for::<bls12381::Scalar>();
+    test_hash_to_field_for::<secp256r1::Scalar>();
+    test_hash_to_field_for::<RistrettoScalar>();
+}
+
+#[test]
+fn test_bls12381_hash_to_curve() {
+    // Test vector from Appendix J.9.1 of RFC 9380.
+    let point =
+        G1Element::hash_to_curve(b"", b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_")
+            .unwrap();
+    let bytes = point.to_byte_array();
+    // Ignore the flags in the first byte of the compressed encoding.
+    assert_eq!(bytes[0] & 0x1f, 0x05);
+    assert_eq!(
+        hex::encode(&bytes[1..]),
+        "2926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1"
+    );
+
+    // The existing hash functions use the signature DSTs.
+    assert_eq!(
+        G1Element::hash_to_curve(b"msg", DST_G1).unwrap(),
+        G1Element::hash_to_group_element(b"msg")
+    );
+    assert_eq!(
+        G2Element::hash_to_curve(b"msg", DST_G2).unwrap(),
+        G2Element::hash_to_group_element(b"msg")
+    );
+    assert_ne!(
+        G1Element::hash_to_curve(b"msg", b"OTHER-DST").unwrap(),
+        G1Element::hash_to_group_element(b"msg")
+    );
+
+    // The nonuniform encoding is different.
+    assert_ne!(
+        G1Element::encode_to_curve(b"msg", DST_G1).unwrap(),
+        G1Element::hash_to_curve(b"msg", DST_G1).unwrap()
+    );
+    assert_ne!(
+        G2Element::encode_to_curve(b"msg", DST_G2).unwrap(),
+        G2Element::hash_to_curve(b"msg", DST_G2).unwrap()
+    );
+
+    // The outputs are valid group elements.
+    let point = G2Element::encode_to_curve(b"msg", b"DST").unwrap();
+    assert_eq!(
+        G2Element::from_byte_array(&point.to_byte_array()).unwrap(),
+        point
+    );
+
+    assert!(G1Element::hash_to_curve(b"msg", b"").is_err());
+    assert!(G1Element::encode_to_curve(b"msg", b"").is_err());
+    assert!(G2Element::hash_to_curve(b"msg", b"").is_err());
+    assert!(G2Element::encode_to_curve(b"msg", b"").is_err());
+}
+
+#[test]
+fn test_secp256r1_hash_to_curve() {
+    // Test vector from Appendix J.1.1 of RFC 9380.
+    let point = secp256r1::ProjectivePoint::hash_to_curve(
+        b"",
+        b"QUUX-V01-CS02-with-P256_XMD:SHA-256_SSWU_RO_",
+    )
+    .unwrap();
+    let coordinate =
+        |hex: &str| ark_secp256r1::Fq::from_be_bytes_mod_order(&hex::decode(hex).unwrap());
+    let expected = ark_secp256r1::Affine::new(
+        coordinate("2c15230b26dbc6fc9a37051158c95b79656e17a1a920b11394ca91c44247d3e4"),
+        coordinate("8a7a74985cc5c776cdfe4b1f19884970453912e9d31528c060be9ab5c43e8415"),
+    );
+    assert_eq!(point.0, expected);
+
+    assert_ne!(
+        secp256r1::ProjectivePoint::encode_to_curve(b"msg", b"DST").unwrap(),
+        secp256r1::ProjectivePoint::hash_to_curve(b"msg", b"DST").unwrap()
+    );
+    assert!(secp256r1::ProjectivePoint::hash_to_curve(b"msg", b"").is_err());
+    assert!(secp256r1::ProjectivePoint::encode_to_curve(b"msg", b"").is_err());
+}
+
+#[test]
+fn test_ristretto255_hash_to_curve() {
+    let uniform_bytes = expand_message_xmd::<Sha512, 64>(b"msg", b"DST", 64).unwrap();
+    assert_eq!(
+        RistrettoPoint::hash_to_curve(b"msg", b"DST").unwrap(),
+        RistrettoPoint::from_uniform_bytes(&uniform_bytes.try_into().unwrap())
+    );
+    assert_ne!(
+        RistrettoPoint::hash_to_curve(b"msg", b"DST").unwrap(),
+        RistrettoPoint::hash_to_curve(b"msg", b"DST2").unwrap()
+    );
+    assert!(RistrettoPoint::hash_to_curve(b"msg", b"").is_err());
+}
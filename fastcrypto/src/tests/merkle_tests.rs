@@ -0,0 +1,255 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::FastCryptoError;
+use crate::hash::{Blake2b256, HashFunction, Sha256, Sha3_256};
+use crate::merkle::{leaf_hash, ConsistencyProof, MerkleMultiProof, MerkleProof, MerkleTree};
+
+/// Test leaves from the certificate transparency test data.
+fn ct_leaves() -> Vec<Vec<u8>> {
+    [
+        "",
+        "00",
+        "10",
+        "2021",
+        "3031",
+        "40414243",
+        "5051525354555657",
+        "606162636465666768696a6b6c6d6e6f",
+    ]
+    .iter()
+    .map(|s| hex::decode(s).unwrap())
+    .collect()
+}
+
+#[test]
+fn test_roots() {
+    let expected_roots = [
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        "6e340b9cffb37a989ca544e6bb780a2c78901d3fb33738768511a30617afa01d",
+        "fac54203e7cc696cf0dfcb42c92a1d9dbaf70ad9e621f4bd8d98662f00e3c125",
+        "aeb6bcfe274b70a14fb067a5e5578264db0fa9b51af5e0ba159158f329e06e77",
+        "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+        "4e3bbb1f7b478dcfe71fb631631519a3bca12c9aefca1612bfce4c13a86264d4",
+        "76e67dadbcdf1e10e1b74ddc608abd2f98dfb16fbce75277b5232a127f2087ef",
+        "ddb89be403809e325750d3d263cd78929c2942b7942a34b77e122c9594a74c8c",
+        "5dc9da79a70659a9ad559cb701ded9a2ab9d823aad2f4960cfe370eff4604328",
+    ];
+    let leaves = ct_leaves();
+    for (size, expected) in expected_roots.iter().enumerate() {
+        let tree = MerkleTree::<Sha256, 32>::build(&leaves[..size]);
+        assert_eq!(tree.len(), size);
+        assert_eq!(hex::encode(tree.root()), *expected);
+    }
+}
+
+#[test]
+fn test_inclusion_proofs() {
+    let leaves = (0u32..20).map(|i| i.to_le_bytes()).collect::<Vec<_>>();
+    for size in 1..=leaves.len() {
+        let tree = MerkleTree::<Sha3_256, 32>::build(&leaves[..size]);
+        let root = tree.root();
+        for (index, leaf) in leaves[..size].iter().enumerate() {
+            let proof = tree.get_proof(index).unwrap();
+            assert!(proof.verify::<Sha3_256>(&root, leaf, index, size).is_ok());
+
+            // Wrong leaf or index. Note that like in RFC 6962, the tree size is not bound by the
+            // proof but should be authenticated together with the root.
+            assert!(proof
+                .verify::<Sha3_256>(&root, b"other", index, size)
+                .is_err());
+            assert!(
+                proof
+                    .verify::<Sha3_256>(&root, leaf, (index + 1) % size, size)
+                    .is_err()
+                    || size == 1
+            );
+            assert!(proof.verify::<Sha3_256>(&root, leaf, size, size).is_err());
+        }
+    }
+
+    let tree = MerkleTree::<Sha3_256, 32>::build(&leaves);
+    assert_eq!(
+        tree.get_proof(leaves.len()),
+        Err(FastCryptoError::InvalidInput)
+    );
+}
+
+#[test]
+fn test_proof_with_wrong_length() {
+    let leaves = (0u32..13).map(|i| i.to_le_bytes()).collect::<Vec<_>>();
+    let tree = MerkleTree::<Sha256, 32>::build(&leaves);
+    let mut proof = tree.get_proof(5).unwrap();
+    proof.path.push(tree.root());
+    assert_eq!(
+        proof.verify::<Sha256>(&tree.root(), &leaves[5], 5, leaves.len()),
+        Err(FastCryptoError::InvalidProof)
+    );
+    proof.path.truncate(1);
+    assert_eq!(
+        proof.verify::<Sha256>(&tree.root(), &leaves[5], 5, leaves.len()),
+        Err(FastCryptoError::InvalidProof)
+    );
+}
+
+#[test]
+fn test_leaves_and_nodes_are_domain_separated() {
+    // An inner node must not be accepted as a leaf.
+    let leaves: Vec<&[u8]> = vec![b"a", b"b", b"c", b"d"];
+    let tree = MerkleTree::<Sha256, 32>::build(&leaves);
+    let inner_node = [
+        leaf_hash::<Sha256, 32>(b"a").digest,
+        leaf_hash::<Sha256, 32>(b"b").digest,
+    ]
+    .concat();
+    let two_leaf_tree = MerkleTree::<Sha256, 32>::build_from_leaf_hashes(vec![
+        Sha256::digest(inner_node.clone()),
+        tree.get_proof(3).unwrap().path[1],
+    ]);
+    assert_ne!(two_leaf_tree.root(), tree.root());
+}
+
+#[test]
+fn test_multiproofs() {
+    let leaves = (0u32..37).map(|i| i.to_le_bytes()).collect::<Vec<_>>();
+    let tree = MerkleTree::<Blake2b256, 32>::build(&leaves);
+    let root = tree.root();
+
+    let index_sets: [&[usize]; 6] = [
+        &[0],
+        &[36],
+        &[0, 1],
+        &[3, 4, 5, 17, 36],
+        &[10, 11, 12, 13, 14, 15],
+        &(0..37).collect::<Vec<_>>(),
+    ];
+    for indices in index_sets {
+        let proof = tree.get_multiproof(indices).unwrap();
+        let opened = indices
+            .iter()
+            .map(|&i| (i, leaves[i].as_slice()))
+            .collect::<Vec<_>>();
+        assert!(proof
+            .verify::<Blake2b256, _>(&root, &opened, leaves.len())
+            .is_ok());
+
+        // A multiproof is never larger than the individual proofs together.
+        let individual: usize = indices
+            .iter()
+            .map(|&i| tree.get_proof(i).unwrap().path.len())
+            .sum();
+        assert!(proof.hashes.len() <= individual);
+
+        // Changing a leaf invalidates the proof.
+        let mut modified = opened.clone();
+        modified[0].1 = b"other";
+        assert!(proof
+            .verify::<Blake2b256, _>(&root, &modified, leaves.len())
+            .is_err());
+    }
+
+    // Opening all leaves requires no extra hashes.
+    assert!(tree
+        .get_multiproof(&(0..37).collect::<Vec<_>>())
+        .unwrap()
+        .hashes
+        .is_empty());
+
+    // Indices must be strictly increasing and in range.
+    assert!(tree.get_multiproof(&[]).is_err());
+    assert!(tree.get_multiproof(&[2, 1]).is_err());
+    assert!(tree.get_multiproof(&[1, 1]).is_err());
+    assert!(tree.get_multiproof(&[37]).is_err());
+}
+
+#[test]
+fn test_consistency_proofs() {
+    let leaves = (0u32..20).map(|i| i.to_le_bytes()).collect::<Vec<_>>();
+    let trees = (0..=leaves.len())
+        .map(|size| MerkleTree::<Sha256, 32>::build(&leaves[..size]))
+        .collect::<Vec<_>>();
+
+    for new_size in 1..=leaves.len() {
+        let new_tree = &trees[new_size];
+        for old_size in 1..=new_size {
+            let proof = new_tree.get_consistency_proof(old_size).unwrap();
+            assert!(proof
+                .verify::<Sha256>(
+                    old_size,
+                    &trees[old_size].root(),
+                    new_size,
+                    &new_tree.root()
+                )
+                .is_ok());
+
+            // Wrong sizes or roots.
+            if old_size > 1 {
+                assert!(proof
+                    .verify::<Sha256>(
+                        old_size - 1,
+                        &trees[old_size - 1].root(),
+                        new_size,
+                        &new_tree.root()
+                    )
+                    .is_err());
+            }
+            assert!(proof
+                .verify::<Sha256>(
+                    old_size,
+                    &trees[old_size].root(),
+                    new_size,
+                    &trees[new_size - 1].root()
+                )
+                .is_err());
+        }
+    }
+
+    assert!(trees[5].get_consistency_proof(0).is_err());
+    assert!(trees[5].get_consistency_proof(6).is_err());
+}
+
+#[test]
+fn test_consistency_proof_vectors() {
+    // Expected proof sizes for the trees over the certificate transparency test leaves.
+    let leaves = ct_leaves();
+    let tree = MerkleTree::<Sha256, 32>::build(&leaves);
+    let proof = tree.get_consistency_proof(1).unwrap();
+    assert_eq!(
+        proof.path.iter().map(hex::encode).collect::<Vec<_>>(),
+        vec![
+            "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7",
+            "5f083f0a1a33ca076a95279832580db3e0ef4584bdff1f54c8a360f50de3031e",
+            "6b47aaf29ee3c2af9af889bc1fb9254dabd31177f16232dd6aab035ca39bf6e4",
+        ]
+    );
+    let proof = tree.get_consistency_proof(6).unwrap();
+    assert_eq!(
+        proof.path.iter().map(hex::encode).collect::<Vec<_>>(),
+        vec![
+            "0ebc5d3437fbe2db158b9f126a1d118e308181031d0a949f8dededebc558ef6a",
+            "ca854ea128ed050b41b35ffc1b87b8eb2bde461e9e3b5596ece6b9d5975a0ae0",
+            "d37ee418976dd95753c1c73862b9398fa2a2cf9b4ff0fdfe8b30cd95209614b7",
+        ]
+    );
+}
+
+#[test]
+fn test_serialize_deserialize() {
+    let leaves = (0u32..11).map(|i| i.to_le_bytes()).collect::<Vec<_>>();
+    let tree = MerkleTree::<Sha256, 32>::build(&leaves);
+
+    let proof = tree.get_proof(7).unwrap();
+    let deserialized: MerkleProof<32> =
+        bincode::deserialize(&bincode::serialize(&proof).unwrap()).unwrap();
+    assert_eq!(deserialized, proof);
+
+    let multiproof = tree.get_multiproof(&[1, 2, 9]).unwrap();
+    let deserialized: MerkleMultiProof<32> =
+        bincode::deserialize(&bincode::serialize(&multiproof).unwrap()).unwrap();
+    assert_eq!(deserialized, multiproof);
+
+    let consistency_proof = tree.get_consistency_proof(3).unwrap();
+    let deserialized: ConsistencyProof<32> =
+        bincode::deserialize(&bincode::serialize(&consistency_proof).unwrap()).unwrap();
+    assert_eq!(deserialized, consistency_proof);
+}
@@ -0,0 +1,132 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::hash::{Digest, HashFunction, Keccak256, Sha256};
+use crate::merkle::mmr::{bag_peaks, MerkleMountainRange};
+use crate::merkle::{leaf_hash, MerkleTree};
+
+fn leaves(n: u32) -> Vec<[u8; 4]> {
+    (0..n).map(|i| i.to_le_bytes()).collect()
+}
+
+#[test]
+fn test_roots_and_peaks() {
+    let leaves = leaves(40);
+    let mut mmr = MerkleMountainRange::<Sha256, 32>::new();
+    assert!(mmr.is_empty());
+    assert_eq!(mmr.root(), Sha256::digest([]));
+    assert!(mmr.peaks().is_empty());
+
+    for (i, leaf) in leaves.iter().enumerate() {
+        assert_eq!(mmr.append(leaf), i);
+        let size = i + 1;
+        assert_eq!(mmr.len(), size);
+
+        // The root is the same as the root of a Merkle tree over the same leaves.
+        let tree = MerkleTree::<Sha256, 32>::build(&leaves[..size]);
+        assert_eq!(mmr.root(), tree.root());
+
+        let peaks = mmr.peaks();
+        assert_eq!(peaks.len(), size.count_ones() as usize);
+        assert_eq!(bag_peaks::<Sha256, 32>(&peaks), mmr.root());
+    }
+
+    // The peaks of a range with a power of two leaves is a single Merkle root.
+    assert_eq!(
+        mmr.peaks_at(32).unwrap(),
+        vec![MerkleTree::<Sha256, 32>::build(&leaves[..32]).root()]
+    );
+
+    // Historical roots.
+    for size in 0..=leaves.len() {
+        assert_eq!(
+            mmr.root_at(size).unwrap(),
+            MerkleTree::<Sha256, 32>::build(&leaves[..size]).root()
+        );
+    }
+    assert!(mmr.root_at(leaves.len() + 1).is_err());
+}
+
+#[test]
+fn test_inclusion_proofs() {
+    let leaves = leaves(33);
+    let mut mmr = MerkleMountainRange::<Keccak256, 32>::new();
+    for leaf in &leaves {
+        mmr.append(leaf);
+    }
+
+    for size in 1..=leaves.len() {
+        let root = mmr.root_at(size).unwrap();
+        let tree = MerkleTree::<Keccak256, 32>::build(&leaves[..size]);
+        for (index, leaf) in leaves[..size].iter().enumerate() {
+            let proof = mmr.get_proof_at(index, size).unwrap();
+            assert_eq!(proof, tree.get_proof(index).unwrap());
+            assert!(proof.verify::<Keccak256>(&root, leaf, index, size).is_ok());
+            assert!(proof
+                .verify::<Keccak256>(&root, b"other", index, size)
+                .is_err());
+        }
+    }
+
+    // Proofs for old leaves against the latest root.
+    let root = mmr.root();
+    for (index, leaf) in leaves.iter().enumerate() {
+        let proof = mmr.get_proof(index).unwrap();
+        assert!(proof
+            .verify::<Keccak256>(&root, leaf, index, leaves.len())
+            .is_ok());
+    }
+
+    assert!(mmr.get_proof(leaves.len()).is_err());
+    assert!(mmr.get_proof_at(5, 5).is_err());
+    assert!(mmr.get_proof_at(0, leaves.len() + 1).is_err());
+}
+
+#[test]
+fn test_ancestry_proofs() {
+    let leaves = leaves(25);
+    let mut mmr = MerkleMountainRange::<Sha256, 32>::new();
+    for leaf in &leaves {
+        mmr.append(leaf);
+    }
+
+    for new_size in 1..=leaves.len() {
+        let new_root = mmr.root_at(new_size).unwrap();
+        let tree = MerkleTree::<Sha256, 32>::build(&leaves[..new_size]);
+        for old_size in 1..=new_size {
+            let old_root = mmr.root_at(old_size).unwrap();
+            let proof = mmr.get_ancestry_proof(old_size, new_size).unwrap();
+            assert_eq!(proof, tree.get_consistency_proof(old_size).unwrap());
+            assert!(proof
+                .verify::<Sha256>(old_size, &old_root, new_size, &new_root)
+                .is_ok());
+
+            // A range with a different leaf does not extend the old range.
+            let mut other = MerkleMountainRange::<Sha256, 32>::new();
+            for leaf in &leaves[..new_size - 1] {
+                other.append(leaf);
+            }
+            other.append(b"other");
+            assert!(proof
+                .verify::<Sha256>(old_size, &old_root, new_size, &other.root())
+                .is_err());
+        }
+    }
+
+    assert!(mmr.get_ancestry_proof(0, 5).is_err());
+    assert!(mmr.get_ancestry_proof(6, 5).is_err());
+    assert!(mmr.get_ancestry_proof(5, leaves.len() + 1).is_err());
+}
+
+#[test]
+fn test_append_leaf_hash() {
+    let leaves = leaves(10);
+    let mut mmr = MerkleMountainRange::<Sha256, 32>::new();
+    let mut mmr_from_hashes = MerkleMountainRange::<Sha256, 32>::default();
+    for leaf in &leaves {
+        mmr.append(leaf);
+        mmr_from_hashes.append_leaf_hash(leaf_hash::<Sha256, 32>(leaf));
+    }
+    assert_eq!(mmr.root(), mmr_from_hashes.root());
+    assert_ne!(mmr.root(), Digest::new([0u8; 32]));
+}
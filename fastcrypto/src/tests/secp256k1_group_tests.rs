@@ -0,0 +1,178 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::groups::hash_to_curve::HashToCurve;
+use crate::groups::{
+    Doubling, FiatShamirChallenge, GroupElement, HashToGroupElement, MultiScalarMul,
+};
+
+use crate::groups::secp256k1::{ProjectivePoint, Scalar, HASH_TO_GROUP_DST};
+use crate::groups::{secp256k1, Scalar as ScalarTrait};
+use crate::secp256k1::{Secp256k1KeyPair, Secp256k1PrivateKey, Secp256k1PublicKey};
+use crate::serde_helpers::ToFromByteArray;
+use crate::test_helpers::verify_serialization;
+use crate::traits::{KeyPair, ToFromBytes};
+use rand::thread_rng;
+use zeroize::Zeroize;
+
+#[test]
+fn test_to_from_byte_array() {
+    let scalar = secp256k1::Scalar::rand(&mut thread_rng());
+    let bytes = scalar.to_byte_array();
+    let reconstructed = Scalar::from_byte_array(&bytes).unwrap();
+    assert_eq!(scalar, reconstructed);
+}
+
+#[test]
+fn test_arithmetic() {
+    let p = ProjectivePoint::generator();
+    let two_p = p + p;
+    let s = Scalar::from(2);
+    assert_eq!(two_p, p.double());
+    assert_eq!(two_p, p * s);
+    assert_eq!(p, two_p * (Scalar::generator() / s).unwrap());
+
+    // Check that u128 is decoded correctly.
+    let x: u128 = 2 << 66;
+    let x_scalar = Scalar::from(x);
+    let res = x_scalar / Scalar::from(8);
+    assert_eq!(res.unwrap(), Scalar::from(2 << 63));
+}
+
+#[test]
+fn test_point_serialization() {
+    // The generator in SEC1 compressed and uncompressed form.
+    let g = ProjectivePoint::generator();
+    assert_eq!(
+        hex::encode(g.to_byte_array()),
+        "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+    );
+    assert_eq!(
+        hex::encode(g.to_uncompressed_bytes()),
+        "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"
+    );
+
+    for p in [
+        ProjectivePoint::zero(),
+        g,
+        g * Scalar::rand(&mut thread_rng()),
+        ProjectivePoint::hash_to_group_element(b"test"),
+    ] {
+        assert_eq!(
+            ProjectivePoint::from_byte_array(&p.to_byte_array()).unwrap(),
+            p
+        );
+        assert_eq!(
+            ProjectivePoint::from_uncompressed_bytes(&p.to_uncompressed_bytes()).unwrap(),
+            p
+        );
+        verify_serialization(&p, Some(p.to_byte_array().as_slice()));
+    }
+
+    // The identity is encoded as all zeros.
+    assert_eq!(ProjectivePoint::zero().to_byte_array(), [0u8; 33]);
+    assert_eq!(ProjectivePoint::zero().to_uncompressed_bytes(), [0u8; 65]);
+
+    // Invalid encodings.
+    let mut bytes = g.to_byte_array();
+    bytes[0] = 0x04;
+    assert!(ProjectivePoint::from_byte_array(&bytes).is_err());
+    let mut bytes = g.to_uncompressed_bytes();
+    bytes[64] ^= 1;
+    assert!(ProjectivePoint::from_uncompressed_bytes(&bytes).is_err());
+    let mut bytes = [0xffu8; 33];
+    bytes[0] = 0x02;
+    assert!(ProjectivePoint::from_byte_array(&bytes).is_err());
+}
+
+#[test]
+fn test_scalar_serialization() {
+    let s = Scalar::rand(&mut thread_rng());
+    verify_serialization(&s, Some(s.to_byte_array().as_slice()));
+}
+
+#[test]
+fn test_multi_scalar_mul() {
+    let scalars = (0..10)
+        .map(|_| Scalar::rand(&mut thread_rng()))
+        .collect::<Vec<_>>();
+    let points = (0..10)
+        .map(|i| ProjectivePoint::generator() * Scalar::from(i as u128))
+        .collect::<Vec<_>>();
+    let expected = scalars
+        .iter()
+        .zip(points.iter())
+        .fold(ProjectivePoint::zero(), |acc, (s, p)| acc + *p * *s);
+    assert_eq!(
+        ProjectivePoint::multi_scalar_mul(&scalars, &points).unwrap(),
+        expected
+    );
+
+    assert!(ProjectivePoint::multi_scalar_mul(&scalars[1..], &points).is_err());
+    assert!(ProjectivePoint::multi_scalar_mul(&[], &[]).is_err());
+}
+
+#[test]
+fn test_hash_to_group_element() {
+    let p = ProjectivePoint::hash_to_group_element(b"test");
+    assert_ne!(p, ProjectivePoint::zero());
+    assert_ne!(p, ProjectivePoint::hash_to_group_element(b"test2"));
+    assert_eq!(
+        p,
+        ProjectivePoint::hash_to_curve(b"test", HASH_TO_GROUP_DST).unwrap()
+    );
+}
+
+#[test]
+fn test_hash_to_curve_vectors() {
+    // Test vectors from appendix J.8.1 of RFC 9380.
+    let dst = b"QUUX-V01-CS02-with-secp256k1_XMD:SHA-256_SSWU_RO_";
+    for (msg, x, y) in [
+        (
+            "",
+            "c1cae290e291aee617ebaef1be6d73861479c48b841eaba9b7b5852ddfeb1346",
+            "64fa678e07ae116126f08b022a94af6de15985c996c3a91b64c406a960e51067",
+        ),
+        (
+            "abc",
+            "3377e01eab42db296b512293120c6cee72b6ecf9f9205760bd9ff11fb3cb2c4b",
+            "7f95890f33efebd1044d382a01b1bee0900fb6116f94688d487c6c7b9c8371f6",
+        ),
+    ] {
+        let p = ProjectivePoint::hash_to_curve(msg.as_bytes(), dst).unwrap();
+        assert_eq!(
+            hex::encode(p.to_uncompressed_bytes()),
+            format!("04{}{}", x, y)
+        );
+    }
+    assert!(ProjectivePoint::hash_to_curve(b"abc", b"").is_err());
+}
+
+#[test]
+fn test_public_key_conversion() {
+    let kp = Secp256k1KeyPair::generate(&mut thread_rng());
+    let point = ProjectivePoint::from(kp.public());
+    assert_eq!(
+        point,
+        ProjectivePoint::generator()
+            * Scalar::from(&Secp256k1PrivateKey::from_bytes(kp.secret.as_ref()).unwrap())
+    );
+    assert_eq!(point.to_byte_array().as_slice(), kp.public().as_ref());
+    assert_eq!(&Secp256k1PublicKey::try_from(&point).unwrap(), kp.public());
+    assert!(Secp256k1PublicKey::try_from(&ProjectivePoint::zero()).is_err());
+}
+
+#[test]
+fn test_fiat_shamir_challenge() {
+    let buffer = [7u8; 64];
+    let s = Scalar::fiat_shamir_reduction_to_group_element(&buffer);
+    assert_eq!(s, Scalar::fiat_shamir_reduction_to_group_element(&buffer));
+    assert_ne!(
+        s,
+        Scalar::fiat_shamir_reduction_to_group_element(&buffer[1..])
+    );
+
+    let mut s = s;
+    s.zeroize();
+    assert_eq!(s, Scalar::zero());
+}
@@ -1,12 +1,17 @@
 // Copyright (c) 2022, Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::groups::{Doubling, GroupElement};
+use crate::groups::hash_to_curve::HashToCurve;
+use crate::groups::{
+    BatchNormalize, Doubling, FiatShamirChallenge, GroupElement, HashToGroupElement, MultiScalarMul,
+};
 
-use crate::groups::secp256r1::{ProjectivePoint, Scalar};
+use crate::groups::secp256r1::{ProjectivePoint, Scalar, HASH_TO_GROUP_DST};
 use crate::groups::{secp256r1, Scalar as ScalarTrait};
 use crate::serde_helpers::ToFromByteArray;
+use crate::test_helpers::verify_serialization;
 use rand::thread_rng;
+use zeroize::Zeroize;
 
 #[test]
 fn test_to_from_byte_array() {
@@ -32,4 +37,131 @@ fn test_arithmetic() {
     assert_eq!(res.unwrap(), Scalar::from(2 << 63));
 }
 
-// TODO: add serde tests & regression
+#[test]
+fn test_point_serialization() {
+    // The generator in SEC1 compressed and uncompressed form.
+    let g = ProjectivePoint::generator();
+    assert_eq!(
+        hex::encode(g.to_byte_array()),
+        "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296"
+    );
+    assert_eq!(
+        hex::encode(g.to_uncompressed_bytes()),
+        "046b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c2964fe342e2fe1a7f9b8ee7eb4a7c0f9e162bce33576b315ececbb6406837bf51f5"
+    );
+
+    for p in [
+        ProjectivePoint::zero(),
+        g,
+        g * Scalar::rand(&mut thread_rng()),
+        ProjectivePoint::hash_to_group_element(b"test"),
+    ] {
+        assert_eq!(
+            ProjectivePoint::from_byte_array(&p.to_byte_array()).unwrap(),
+            p
+        );
+        assert_eq!(
+            ProjectivePoint::from_uncompressed_bytes(&p.to_uncompressed_bytes()).unwrap(),
+            p
+        );
+        verify_serialization(&p, Some(p.to_byte_array().as_slice()));
+    }
+
+    // The identity is encoded as all zeros.
+    assert_eq!(ProjectivePoint::zero().to_byte_array(), [0u8; 33]);
+    assert_eq!(ProjectivePoint::zero().to_uncompressed_bytes(), [0u8; 65]);
+
+    // Invalid encodings.
+    let mut bytes = g.to_byte_array();
+    bytes[0] = 0x04;
+    assert!(ProjectivePoint::from_byte_array(&bytes).is_err());
+    let mut bytes = g.to_uncompressed_bytes();
+    bytes[64] ^= 1;
+    assert!(ProjectivePoint::from_uncompressed_bytes(&bytes).is_err());
+    let mut bytes = [0xffu8; 33];
+    bytes[0] = 0x02;
+    assert!(ProjectivePoint::from_byte_array(&bytes).is_err());
+}
+
+#[test]
+fn test_scalar_serialization() {
+    let s = Scalar::rand(&mut thread_rng());
+    verify_serialization(&s, Some(s.to_byte_array().as_slice()));
+}
+
+#[test]
+fn test_multi_scalar_mul() {
+    let scalars = (0..10)
+        .map(|_| Scalar::rand(&mut thread_rng()))
+        .collect::<Vec<_>>();
+    let points = (0..10)
+        .map(|i| ProjectivePoint::generator() * Scalar::from(i as u128))
+        .collect::<Vec<_>>();
+    let expected = scalars
+        .iter()
+        .zip(points.iter())
+        .fold(ProjectivePoint::zero(), |acc, (s, p)| acc + *p * *s);
+    assert_eq!(
+        ProjectivePoint::multi_scalar_mul(&scalars, &points).unwrap(),
+        expected
+    );
+
+    assert!(ProjectivePoint::multi_scalar_mul(&scalars[1..], &points).is_err());
+    assert!(ProjectivePoint::multi_scalar_mul(&[], &[]).is_err());
+}
+
+#[test]
+fn test_hash_to_group_element() {
+    let p = ProjectivePoint::hash_to_group_element(b"test");
+    assert_ne!(p, ProjectivePoint::zero());
+    assert_ne!(p, ProjectivePoint::hash_to_group_element(b"test2"));
+    assert_eq!(
+        p,
+        ProjectivePoint::hash_to_curve(b"test", HASH_TO_GROUP_DST).unwrap()
+    );
+}
+
+#[test]
+fn test_fiat_shamir_challenge() {
+    let buffer = [7u8; 64];
+    let s = Scalar::fiat_shamir_reduction_to_group_element(&buffer);
+    assert_eq!(s, Scalar::fiat_shamir_reduction_to_group_element(&buffer));
+    assert_ne!(
+        s,
+        Scalar::fiat_shamir_reduction_to_group_element(&buffer[1..])
+    );
+
+    let mut s = s;
+    s.zeroize();
+    assert_eq!(s, Scalar::zero());
+}
+
+#[test]
+fn test_batch_normalize_and_inverse() {
+    let mut points = (0..10)
+        .map(|i| match i % 4 {
+            0 => ProjectivePoint::zero(),
+            _ => ProjectivePoint::generator() * Scalar::rand(&mut thread_rng()),
+        })
+        .collect::<Vec<_>>();
+    let expected = points.clone();
+    ProjectivePoint::batch_normalize(&mut points);
+    assert_eq!(points, expected);
+    assert_eq!(
+        points.iter().map(|p| p.to_byte_array()).collect::<Vec<_>>(),
+        expected
+            .iter()
+            .map(|p| p.to_byte_array())
+            .collect::<Vec<_>>()
+    );
+
+    let scalars = (0..10)
+        .map(|_| Scalar::rand(&mut thread_rng()))
+        .collect::<Vec<_>>();
+    let inverses = Scalar::batch_inverse(&scalars).unwrap();
+    assert!(scalars
+        .iter()
+        .zip(inverses.iter())
+        .all(|(s, i)| *s * i == Scalar::generator()));
+    assert!(Scalar::batch_inverse(&[Scalar::zero()]).is_err());
+}
@@ -0,0 +1,332 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::FastCryptoError;
+use crate::hash::{Blake2b256, Digest, HashFunction, Sha256, Sha3_256};
+use crate::merkle::sparse::{
+    InMemoryNodeStore, Key, Node, NodeStore, SparseMerkleProof, SparseMerkleTree,
+};
+use rand::prelude::SliceRandom;
+use rand::thread_rng;
+
+fn key(i: u32) -> Key {
+    Sha256::digest(i.to_le_bytes())
+}
+
+fn value(i: u32) -> Vec<u8> {
+    format!("value {}", i).into_bytes()
+}
+
+#[test]
+fn test_empty_tree() {
+    let tree = SparseMerkleTree::<Sha256, 32>::new();
+    assert_eq!(tree.root(), Digest::new([0u8; 32]));
+    let proof = tree.get_proof(&key(0)).unwrap();
+    assert!(proof
+        .verify_non_membership::<Sha256>(&tree.root(), &key(0))
+        .is_ok());
+    assert!(proof
+        .verify_membership::<Sha256>(&tree.root(), &key(0), &value(0))
+        .is_err());
+    assert_eq!(tree.get(&key(0)).unwrap(), None);
+}
+
+#[test]
+fn test_membership_and_non_membership() {
+    let mut tree = SparseMerkleTree::<Sha3_256, 32>::new();
+    for i in 0..50 {
+        tree.insert(key(i), &value(i)).unwrap();
+    }
+    let root = tree.root();
+
+    for i in 0..50 {
+        let proof = tree.get_proof(&key(i)).unwrap();
+        assert!(proof
+            .verify_membership::<Sha3_256>(&root, &key(i), &value(i))
+            .is_ok());
+        assert!(proof
+            .verify_membership::<Sha3_256>(&root, &key(i), &value(i + 1))
+            .is_err());
+        assert!(proof
+            .verify_non_membership::<Sha3_256>(&root, &key(i))
+            .is_err());
+        assert!(proof
+            .verify_membership::<Sha3_256>(&root, &key(i + 1), &value(i))
+            .is_err());
+        assert_eq!(tree.get(&key(i)).unwrap(), Some(Sha3_256::digest(value(i))));
+    }
+
+    for i in 50..100 {
+        let proof = tree.get_proof(&key(i)).unwrap();
+        assert!(proof
+            .verify_non_membership::<Sha3_256>(&root, &key(i))
+            .is_ok());
+        assert!(proof
+            .verify_membership::<Sha3_256>(&root, &key(i), &value(i))
+            .is_err());
+        // A non-membership proof for one key is not valid for other keys.
+        assert!(proof
+            .verify_non_membership::<Sha3_256>(&root, &key(0))
+            .is_err());
+    }
+
+    // Proofs are only valid for the root they were created for.
+    let proof = tree.get_proof(&key(3)).unwrap();
+    tree.insert(key(100), &value(100)).unwrap();
+    assert!(proof
+        .verify_membership::<Sha3_256>(&tree.root(), &key(3), &value(3))
+        .is_err());
+}
+
+#[test]
+fn test_root_is_independent_of_order() {
+    let mut indices = (0..64).collect::<Vec<u32>>();
+    let mut expected = None;
+    for _ in 0..5 {
+        indices.shuffle(&mut thread_rng());
+        let mut tree = SparseMerkleTree::<Blake2b256, 32>::new();
+        for i in &indices {
+            tree.insert(key(*i), &value(*i)).unwrap();
+        }
+        assert_eq!(*expected.get_or_insert(tree.root()), tree.root());
+    }
+
+    // A single batch gives the same root.
+    let mut tree = SparseMerkleTree::<Blake2b256, 32>::new();
+    tree.batch_update(
+        indices
+            .iter()
+            .map(|i| (key(*i), Some(Blake2b256::digest(value(*i))))),
+    )
+    .unwrap();
+    assert_eq!(expected.unwrap(), tree.root());
+}
+
+#[test]
+fn test_updates_and_removals() {
+    let mut tree = SparseMerkleTree::<Sha256, 32>::new();
+    let empty_root = tree.root();
+    for i in 0..20 {
+        tree.insert(key(i), &value(i)).unwrap();
+    }
+    let root_20 = tree.root();
+
+    // Updating a value changes the root and the proofs.
+    tree.insert(key(5), b"new value").unwrap();
+    assert_ne!(tree.root(), root_20);
+    let proof = tree.get_proof(&key(5)).unwrap();
+    assert!(proof
+        .verify_membership::<Sha256>(&tree.root(), &key(5), b"new value")
+        .is_ok());
+    tree.insert(key(5), &value(5)).unwrap();
+    assert_eq!(tree.root(), root_20);
+
+    // Removing a key which is not in the tree does nothing.
+    tree.remove(key(1000)).unwrap();
+    assert_eq!(tree.root(), root_20);
+
+    // Inserting and removing a key gives the previous root.
+    tree.insert(key(20), &value(20)).unwrap();
+    tree.remove(key(20)).unwrap();
+    assert_eq!(tree.root(), root_20);
+    let proof = tree.get_proof(&key(20)).unwrap();
+    assert!(proof
+        .verify_non_membership::<Sha256>(&tree.root(), &key(20))
+        .is_ok());
+
+    for i in 0..20 {
+        tree.remove(key(i)).unwrap();
+    }
+    assert_eq!(tree.root(), empty_root);
+}
+
+#[test]
+fn test_batch_update() {
+    let mut tree = SparseMerkleTree::<Sha256, 32>::new();
+    for i in 0..100 {
+        tree.insert(key(i), &value(i)).unwrap();
+    }
+
+    // Remove the even keys, update every third key and add some new ones.
+    let updates = (0..150)
+        .filter_map(|i| match (i % 2, i % 3, i < 100) {
+            (_, 0, _) => Some((key(i), Some(Sha256::digest(b"updated")))),
+            (0, _, true) => Some((key(i), None)),
+            (_, _, false) => Some((key(i), Some(Sha256::digest(value(i))))),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let mut expected = SparseMerkleTree::<Sha256, 32>::new();
+    for i in 0..150 {
+        if i % 3 == 0 {
+            expected.insert(key(i), b"updated").unwrap();
+        } else if i % 2 == 1 || i >= 100 {
+            expected.insert(key(i), &value(i)).unwrap();
+        }
+    }
+
+    let predicted = tree.root_after_updates(updates.clone()).unwrap();
+    let old_root = tree.root();
+    assert_eq!(tree.batch_update(updates).unwrap(), predicted);
+    assert_eq!(predicted, expected.root());
+
+    // The previous version can still be used since the nodes are kept in the store.
+    let store = tree.store().clone();
+    let old_tree = SparseMerkleTree::<Sha256, 32>::from_root(old_root, store);
+    let proof = old_tree.get_proof(&key(2)).unwrap();
+    assert!(proof
+        .verify_membership::<Sha256>(&old_root, &key(2), &value(2))
+        .is_ok());
+
+    // If a key is updated more than once in a batch, the last update is used.
+    let mut tree = SparseMerkleTree::<Sha256, 32>::new();
+    tree.batch_update([
+        (key(1), Some(Sha256::digest(b"a"))),
+        (key(2), Some(Sha256::digest(b"b"))),
+        (key(1), None),
+        (key(2), Some(Sha256::digest(b"c"))),
+    ])
+    .unwrap();
+    let mut expected = SparseMerkleTree::<Sha256, 32>::new();
+    expected.insert(key(2), b"c").unwrap();
+    assert_eq!(tree.root(), expected.root());
+}
+
+#[test]
+fn test_keys_with_common_prefix() {
+    // Keys which only differ in the last bit give a tree of maximal depth.
+    let mut tree = SparseMerkleTree::<Sha256, 32>::new();
+    let mut k1 = [0xAAu8; 32];
+    k1[31] = 0xAA;
+    let mut k2 = k1;
+    k2[31] = 0xAB;
+    let (k1, k2) = (Digest::new(k1), Digest::new(k2));
+    tree.insert(k1, b"one").unwrap();
+    tree.insert(k2, b"two").unwrap();
+
+    let proof = tree.get_proof(&k2).unwrap();
+    assert!(proof
+        .verify_membership::<Sha256>(&tree.root(), &k2, b"two")
+        .is_ok());
+
+    // Only the last sibling is not empty, so the proof is compressed.
+    let serialized = bincode::serialize(&proof).unwrap();
+    assert!(serialized.len() < 200);
+
+    tree.remove(k1).unwrap();
+    let mut expected = SparseMerkleTree::<Sha256, 32>::new();
+    expected.insert(k2, b"two").unwrap();
+    assert_eq!(tree.root(), expected.root());
+}
+
+#[test]
+fn test_non_membership_proof_ending_in_leaf() {
+    // With a single leaf, the proof for any other key ends in that leaf.
+    let mut tree = SparseMerkleTree::<Sha256, 32>::new();
+    tree.insert(key(0), &value(0)).unwrap();
+    let proof = tree.get_proof(&key(1)).unwrap();
+    assert!(proof
+        .verify_non_membership::<Sha256>(&tree.root(), &key(1))
+        .is_ok());
+
+    // The same proof cannot be used to show that the key of the leaf is not in the tree.
+    assert!(proof
+        .verify_non_membership::<Sha256>(&tree.root(), &key(0))
+        .is_err());
+}
+
+#[test]
+fn test_missing_nodes() {
+    let mut tree = SparseMerkleTree::<Sha256, 32>::new();
+    for i in 0..10 {
+        tree.insert(key(i), &value(i)).unwrap();
+    }
+    let tree = SparseMerkleTree::<Sha256, 32>::from_root(tree.root(), InMemoryNodeStore::default());
+    assert!(matches!(
+        tree.get_proof(&key(0)),
+        Err(FastCryptoError::GeneralError(_))
+    ));
+}
+
+#[test]
+fn test_custom_store() {
+    #[derive(Default)]
+    struct CountingStore {
+        inner: InMemoryNodeStore<32>,
+        inserts: usize,
+    }
+
+    impl NodeStore<32> for CountingStore {
+        fn get(&self, hash: &Digest<32>) -> Option<Node<32>> {
+            self.inner.get(hash)
+        }
+
+        fn insert(&mut self, hash: Digest<32>, node: Node<32>) {
+            self.inserts += 1;
+            self.inner.insert(hash, node);
+        }
+    }
+
+    let mut tree = SparseMerkleTree::<Sha256, 32, _>::new_with_store(CountingStore::default());
+    for i in 0..1000 {
+        tree.insert(key(i), &value(i)).unwrap();
+    }
+
+    // Updating a single key only writes the nodes on its path, which has logarithmic length.
+    let before = tree.store().inserts;
+    tree.insert(key(0), b"new value").unwrap();
+    assert!(tree.store().inserts - before < 40);
+}
+
+#[test]
+fn test_serialize_deserialize() {
+    let mut tree = SparseMerkleTree::<Sha256, 32>::new();
+    for i in 0..10 {
+        tree.insert(key(i), &value(i)).unwrap();
+    }
+    for k in [key(3), key(11)] {
+        let proof = tree.get_proof(&k).unwrap();
+        let deserialized: SparseMerkleProof<32> =
+            bincode::deserialize(&bincode::serialize(&proof).unwrap()).unwrap();
+        assert_eq!(deserialized, proof);
+    }
+}
+
+#[test]
+fn test_padding_bits_must_be_zero() {
+    let mut tree = SparseMerkleTree::<Sha256, 32>::new();
+    for i in 0..10 {
+        tree.insert(key(i), &value(i)).unwrap();
+    }
+    let proof = tree.get_proof(&key(3)).unwrap();
+    let bytes = bincode::serialize(&proof).unwrap();
+
+    // With bincode, the proof is serialized as the depth (2 bytes), the length of the bitmap (8
+    // bytes), the bitmap, the number of siblings (8 bytes) and the siblings.
+    let depth = u16::from_le_bytes([bytes[0], bytes[1]]) as usize;
+    assert_ne!(depth % 8, 0);
+    let bitmap_length = depth.div_ceil(8);
+    let siblings_offset = 10 + bitmap_length;
+    let sibling_count = u64::from_le_bytes(
+        bytes[siblings_offset..siblings_offset + 8]
+            .try_into()
+            .unwrap(),
+    );
+
+    // Setting a padding bit and adding an extra sibling first, which is never used when verifying,
+    // would otherwise give another valid proof.
+    let mut tampered = bytes[..siblings_offset].to_vec();
+    tampered[10 + bitmap_length - 1] |= 0x80 >> (depth % 8);
+    tampered.extend_from_slice(&(sibling_count + 1).to_le_bytes());
+    tampered.extend_from_slice(&[7u8; 32]);
+    tampered.extend_from_slice(&bytes[siblings_offset + 8..]);
+    let tampered: SparseMerkleProof<32> = bincode::deserialize(&tampered).unwrap();
+    assert_ne!(tampered, proof);
+    assert!(proof
+        .verify_membership::<Sha256>(&tree.root(), &key(3), &value(3))
+        .is_ok());
+    assert!(tampered
+        .verify_membership::<Sha256>(&tree.root(), &key(3), &value(3))
+        .is_err());
+}
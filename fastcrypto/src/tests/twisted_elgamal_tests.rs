@@ -0,0 +1,262 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::bulletproofs::{BulletproofsRangeProof, PedersenCommitment, PedersenGenerators};
+use crate::groups::ristretto255::RistrettoScalar;
+use crate::groups::{GroupElement, Scalar};
+use crate::twisted_elgamal::*;
+use rand::thread_rng;
+use static_assertions::{assert_impl_all, assert_not_impl_any};
+use zeroize::ZeroizeOnDrop;
+
+const DOMAIN: &[u8] = b"TEST_DOMAIN";
+
+assert_impl_all!(PrivateKey: ZeroizeOnDrop);
+assert_not_impl_any!(PrivateKey: std::fmt::Debug);
+
+fn keypair() -> (PrivateKey, PublicKey) {
+    let private_key = PrivateKey::generate(&mut thread_rng());
+    let public_key = PublicKey::from(&private_key);
+    (private_key, public_key)
+}
+
+#[test]
+fn test_encrypt_and_decrypt() {
+    let (private_key, public_key) = keypair();
+    let table = DiscreteLogTable::new(16).unwrap();
+    assert_eq!(table.bits(), 16);
+    for value in [0, 1, 255, 256, 1000, 65535] {
+        let (ciphertext, _) = Ciphertext::encrypt(&public_key, value, &mut thread_rng());
+        assert_eq!(private_key.decrypt(&ciphertext, &table).unwrap(), value);
+    }
+
+    // Values out of the range of the table cannot be decrypted.
+    let (ciphertext, _) = Ciphertext::encrypt(&public_key, 65536, &mut thread_rng());
+    assert!(private_key.decrypt(&ciphertext, &table).is_err());
+
+    // Decrypting with another key fails.
+    let (ciphertext, _) = Ciphertext::encrypt(&public_key, 7, &mut thread_rng());
+    let (other_private_key, _) = keypair();
+    assert!(other_private_key.decrypt(&ciphertext, &table).is_err());
+
+    // Odd bit lengths.
+    let table = DiscreteLogTable::new(9).unwrap();
+    for value in [0, 17, 511] {
+        let (ciphertext, _) = Ciphertext::encrypt(&public_key, value, &mut thread_rng());
+        assert_eq!(private_key.decrypt(&ciphertext, &table).unwrap(), value);
+    }
+    let (ciphertext, _) = Ciphertext::encrypt(&public_key, 512, &mut thread_rng());
+    assert!(private_key.decrypt(&ciphertext, &table).is_err());
+
+    assert!(DiscreteLogTable::new(0).is_err());
+    assert!(DiscreteLogTable::new(MAX_DISCRETE_LOG_BITS + 1).is_err());
+}
+
+#[test]
+fn test_homomorphism() {
+    let (private_key, public_key) = keypair();
+    let table = DiscreteLogTable::new(16).unwrap();
+    let (c1, r1) = Ciphertext::encrypt(&public_key, 1000, &mut thread_rng());
+    let (c2, r2) = Ciphertext::encrypt(&public_key, 234, &mut thread_rng());
+
+    let sum = c1.clone() + c2.clone();
+    assert_eq!(private_key.decrypt(&sum, &table).unwrap(), 1234);
+    assert_eq!(
+        sum,
+        Ciphertext::encrypt_with_randomness(&public_key, 1234, &(r1 + r2))
+    );
+
+    let difference = c1.clone() - c2.clone();
+    assert_eq!(private_key.decrypt(&difference, &table).unwrap(), 766);
+
+    // Negative values wrap around and cannot be decrypted.
+    assert!(private_key.decrypt(&(c2 - c1), &table).is_err());
+}
+
+#[test]
+fn test_serde() {
+    let (_, public_key) = keypair();
+    let (ciphertext, _) = Ciphertext::encrypt(&public_key, 42, &mut thread_rng());
+    let bytes = bcs::to_bytes(&ciphertext).unwrap();
+    assert_eq!(bytes.len(), 64);
+    assert_eq!(ciphertext, bcs::from_bytes(&bytes).unwrap());
+
+    let bytes = bcs::to_bytes(&public_key).unwrap();
+    assert_eq!(public_key, bcs::from_bytes(&bytes).unwrap());
+}
+
+#[test]
+fn test_decryption_proof() {
+    let (private_key, public_key) = keypair();
+    let table = DiscreteLogTable::new(16).unwrap();
+    let (ciphertext, _) = Ciphertext::encrypt(&public_key, 1234, &mut thread_rng());
+
+    let (value, proof) = DecryptionProof::decrypt_and_prove(
+        &private_key,
+        &ciphertext,
+        &table,
+        DOMAIN,
+        &mut thread_rng(),
+    )
+    .unwrap();
+    assert_eq!(value, 1234);
+    assert!(proof.verify(&public_key, &ciphertext, 1234, DOMAIN).is_ok());
+    assert!(proof
+        .verify(&public_key, &ciphertext, 1235, DOMAIN)
+        .is_err());
+    assert!(proof
+        .verify(&public_key, &ciphertext, 1234, b"OTHER_DOMAIN")
+        .is_err());
+    let (_, other_public_key) = keypair();
+    assert!(proof
+        .verify(&other_public_key, &ciphertext, 1234, DOMAIN)
+        .is_err());
+    let (other_ciphertext, _) = Ciphertext::encrypt(&public_key, 1234, &mut thread_rng());
+    assert!(proof
+        .verify(&public_key, &other_ciphertext, 1234, DOMAIN)
+        .is_err());
+
+    // Proving a wrong value fails.
+    assert!(
+        DecryptionProof::prove(&private_key, &ciphertext, 1235, DOMAIN, &mut thread_rng()).is_err()
+    );
+
+    // Values out of range of the table can still be proven if known.
+    let (ciphertext, _) = Ciphertext::encrypt(&public_key, 1 << 40, &mut thread_rng());
+    let proof = DecryptionProof::prove(
+        &private_key,
+        &ciphertext,
+        1 << 40,
+        DOMAIN,
+        &mut thread_rng(),
+    )
+    .unwrap();
+    assert!(proof
+        .verify(&public_key, &ciphertext, 1 << 40, DOMAIN)
+        .is_ok());
+
+    let bytes = bcs::to_bytes(&proof).unwrap();
+    assert_eq!(proof, bcs::from_bytes(&bytes).unwrap());
+}
+
+#[test]
+fn test_ciphertext_commitment_equality_proof() {
+    let (private_key, public_key) = keypair();
+    let generators = PedersenGenerators::default();
+    let value = 5678;
+    let (ciphertext, _) = Ciphertext::encrypt(&public_key, value, &mut thread_rng());
+    let blinding = RistrettoScalar::rand(&mut thread_rng());
+    let commitment = PedersenCommitment::new_with_generators(
+        &RistrettoScalar::from(value as u128),
+        &blinding,
+        &generators,
+    );
+
+    let proof = CiphertextCommitmentEqualityProof::prove(
+        &private_key,
+        &ciphertext,
+        &commitment,
+        value,
+        &blinding,
+        DOMAIN,
+        &mut thread_rng(),
+    )
+    .unwrap();
+    assert!(proof
+        .verify(&public_key, &ciphertext, &commitment, DOMAIN)
+        .is_ok());
+    assert!(proof
+        .verify(&public_key, &ciphertext, &commitment, b"OTHER_DOMAIN")
+        .is_err());
+
+    // A commitment to another value.
+    let other_commitment = PedersenCommitment::new_with_generators(
+        &RistrettoScalar::from(value as u128 + 1),
+        &blinding,
+        &generators,
+    );
+    assert!(proof
+        .verify(&public_key, &ciphertext, &other_commitment, DOMAIN)
+        .is_err());
+    assert!(CiphertextCommitmentEqualityProof::prove(
+        &private_key,
+        &ciphertext,
+        &other_commitment,
+        value,
+        &blinding,
+        DOMAIN,
+        &mut thread_rng(),
+    )
+    .is_err());
+
+    // Wrong blinding factor or value.
+    assert!(CiphertextCommitmentEqualityProof::prove(
+        &private_key,
+        &ciphertext,
+        &commitment,
+        value,
+        &(blinding + RistrettoScalar::generator()),
+        DOMAIN,
+        &mut thread_rng(),
+    )
+    .is_err());
+    assert!(CiphertextCommitmentEqualityProof::prove(
+        &private_key,
+        &ciphertext,
+        &commitment,
+        value + 1,
+        &blinding,
+        DOMAIN,
+        &mut thread_rng(),
+    )
+    .is_err());
+
+    let bytes = bcs::to_bytes(&proof).unwrap();
+    assert_eq!(proof, bcs::from_bytes(&bytes).unwrap());
+}
+
+#[test]
+fn test_range_proofs() {
+    let (private_key, public_key) = keypair();
+    let generators = PedersenGenerators::default();
+
+    // The encryptor knows the randomness, which is the blinding factor of the commitment.
+    let (ciphertext, r) = Ciphertext::encrypt(&public_key, 1000, &mut thread_rng());
+    let (commitments, proof) =
+        BulletproofsRangeProof::prove_bit_length_multiple(&[1000], &[r], 32, &generators, DOMAIN)
+            .unwrap();
+    assert_eq!(commitments[0], ciphertext.commitment());
+    assert!(proof
+        .verify_bit_length_multiple(&[ciphertext.commitment()], 32, &generators, DOMAIN)
+        .is_ok());
+
+    // The owner of a sum of ciphertexts only knows the private key, so they prove equality with a
+    // fresh commitment and a range proof for that.
+    let (other, _) = Ciphertext::encrypt(&public_key, 2000, &mut thread_rng());
+    let sum = ciphertext + other;
+    let blinding = RistrettoScalar::rand(&mut thread_rng());
+    let (commitments, range_proof) = BulletproofsRangeProof::prove_bit_length_multiple(
+        &[3000],
+        &[blinding],
+        32,
+        &generators,
+        DOMAIN,
+    )
+    .unwrap();
+    let equality_proof = CiphertextCommitmentEqualityProof::prove(
+        &private_key,
+        &sum,
+        &commitments[0],
+        3000,
+        &blinding,
+        DOMAIN,
+        &mut thread_rng(),
+    )
+    .unwrap();
+    assert!(equality_proof
+        .verify(&public_key, &sum, &commitments[0], DOMAIN)
+        .is_ok());
+    assert!(range_proof
+        .verify_bit_length_multiple(&commitments, 32, &generators, DOMAIN)
+        .is_ok());
+}
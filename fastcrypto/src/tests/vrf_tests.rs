@@ -5,8 +5,11 @@ use crate::encoding::{Encoding, Hex};
 use crate::groups::ristretto255::{RistrettoPoint, RistrettoScalar};
 use crate::serde_helpers::ToFromByteArray;
 use crate::test_helpers::verify_serialization;
+use crate::traits::{InsecureDefault, KeyPair, ToFromBytes, VerifyingKey};
+use crate::vrf::bls_vrf::{min_pk, min_sig};
 use crate::vrf::ecvrf::{ECVRFKeyPair, ECVRFProof, ECVRFPublicKey};
 use crate::vrf::{VRFKeyPair, VRFProof};
+use crate::{bls12381, traits::Signer};
 use rand::thread_rng;
 
 #[test]
@@ -89,3 +92,134 @@ fn test_ecvrf_invalid() {
         .verify_output(alpha_string, &public_key, output)
         .is_err());
 }
+
+#[test]
+fn test_bls_vrf_min_sig_proof() {
+    let kp = min_sig::BLSVRFKeyPair::generate(&mut thread_rng());
+    let input1 = b"Hello, world!";
+    let (output1, proof1) = kp.output(input1);
+
+    let input2 = b"Farewell, world!";
+    let (output2, proof2) = kp.output(input2);
+
+    assert!(proof1.verify_output(input1, &kp.pk, &output1).is_ok());
+    assert!(proof1.verify_output(input1, &kp.pk, &output2).is_err());
+
+    assert!(proof1.verify(input2, &kp.pk).is_err());
+    assert!(proof2.verify_output(input2, &kp.pk, &output2).is_ok());
+
+    assert_ne!(output1, output2);
+
+    // Proofs are unique.
+    assert_eq!(kp.prove(input1), proof1);
+
+    let other_kp = min_sig::BLSVRFKeyPair::generate(&mut thread_rng());
+    assert!(proof1.verify(input1, &other_kp.pk).is_err());
+}
+
+#[test]
+fn test_bls_vrf_min_pk_proof() {
+    let kp = min_pk::BLSVRFKeyPair::generate(&mut thread_rng());
+    let input1 = b"Hello, world!";
+    let (output1, proof1) = kp.output(input1);
+
+    let input2 = b"Farewell, world!";
+    let (output2, proof2) = kp.output(input2);
+
+    assert!(proof1.verify_output(input1, &kp.pk, &output1).is_ok());
+    assert!(proof1.verify_output(input1, &kp.pk, &output2).is_err());
+
+    assert!(proof1.verify(input2, &kp.pk).is_err());
+    assert!(proof2.verify_output(input2, &kp.pk, &output2).is_ok());
+
+    assert_ne!(output1, output2);
+    assert_eq!(kp.prove(input1), proof1);
+}
+
+#[test]
+fn test_bls_vrf_domain_separation() {
+    // A BLS signature on an input is not a valid VRF proof for it, and vice versa.
+    let bls_kp = bls12381::min_sig::BLS12381KeyPair::generate(&mut thread_rng());
+    let input = b"Hello, world!";
+    let signature = bls_kp.sign(input);
+
+    let vrf_pk = min_sig::BLSVRFPublicKey::from(bls_kp.public().clone());
+    let vrf_kp = min_sig::BLSVRFKeyPair::from(min_sig::BLSVRFPrivateKey::from(
+        bls12381::min_sig::BLS12381PrivateKey::from_bytes(bls_kp.as_ref()).unwrap(),
+    ));
+    assert_eq!(vrf_kp.pk, vrf_pk);
+
+    let proof_from_signature = min_sig::BLSVRFProof::from(signature.clone());
+    assert!(proof_from_signature.verify(input, &vrf_pk).is_err());
+
+    let proof = vrf_kp.prove(input);
+    assert!(proof.verify(input, &vrf_pk).is_ok());
+    assert_ne!(proof, proof_from_signature);
+
+    let proof_as_signature: bls12381::min_sig::BLS12381Signature =
+        bincode::deserialize(&bincode::serialize(&proof).unwrap()).unwrap();
+    assert!(bls_kp.public().verify(input, &proof_as_signature).is_err());
+    assert!(bls_kp.public().verify(input, &signature).is_ok());
+}
+
+#[test]
+fn test_bls_vrf_invalid_public_key() {
+    let kp = min_sig::BLSVRFKeyPair::generate(&mut thread_rng());
+    let input = b"Hello, world!";
+    let proof = kp.prove(input);
+
+    let identity =
+        min_sig::BLSVRFPublicKey::from(bls12381::min_sig::BLS12381PublicKey::insecure_default());
+    assert!(proof.verify(input, &identity).is_err());
+}
+
+#[test]
+fn test_bls_vrf_batch_verify() {
+    let kps = (0..5)
+        .map(|_| min_sig::BLSVRFKeyPair::generate(&mut thread_rng()))
+        .collect::<Vec<_>>();
+    let inputs: Vec<&[u8]> = vec![b"a", b"b", b"c", b"d", b"e"];
+    let proofs = kps
+        .iter()
+        .zip(inputs.iter())
+        .map(|(kp, input)| kp.prove(input))
+        .collect::<Vec<_>>();
+    let pks = kps.iter().map(|kp| kp.pk.clone()).collect::<Vec<_>>();
+
+    assert!(min_sig::BLSVRFProof::batch_verify(&proofs, &inputs, &pks).is_ok());
+
+    // Mismatching lengths or empty input.
+    assert!(min_sig::BLSVRFProof::batch_verify(&proofs[1..], &inputs, &pks).is_err());
+    assert!(min_sig::BLSVRFProof::batch_verify(&[], &[], &[]).is_err());
+
+    // Swapped proofs.
+    let mut swapped = proofs.clone();
+    swapped.swap(0, 1);
+    assert!(min_sig::BLSVRFProof::batch_verify(&swapped, &inputs, &pks).is_err());
+}
+
+#[test]
+fn test_bls_vrf_serialize_deserialize() {
+    let kp = min_sig::BLSVRFKeyPair::generate(&mut thread_rng());
+    let input = b"Hello, world!";
+    let (output, proof) = kp.output(input);
+
+    verify_serialization(&kp.pk, None);
+    verify_serialization(&kp.sk, None);
+    verify_serialization(&proof, None);
+
+    let proof_serialized = bincode::serialize(&proof).unwrap();
+    assert_eq!(bls12381::BLS_G1_LENGTH, proof_serialized.len());
+    let proof_reconstructed: min_sig::BLSVRFProof =
+        bincode::deserialize(&proof_serialized).unwrap();
+    assert!(proof_reconstructed
+        .verify_output(input, &kp.pk, &output)
+        .is_ok());
+
+    let kp = min_pk::BLSVRFKeyPair::generate(&mut thread_rng());
+    let proof = kp.prove(input);
+    assert_eq!(
+        bls12381::BLS_G2_LENGTH,
+        bincode::serialize(&proof).unwrap().len()
+    );
+}
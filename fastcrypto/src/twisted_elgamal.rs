@@ -0,0 +1,439 @@
+// Copyright (c) 2022, Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Implementation of twisted ElGamal encryption over [ristretto255](crate::groups::ristretto255),
+//! see Chen, Ma, Tang and Au, "PGC: Pretty Good Confidential Transaction System with
+//! Accountability" (ESORICS 2020).
+//!
+//! Values are encrypted in the exponent, so the encryption is additively homomorphic and
+//! decryption requires computing a bounded discrete logarithm, which is done using baby-step
+//! giant-step with a precomputed [DiscreteLogTable].
+//!
+//! A ciphertext of a value v under the public key P = s^{-1} H with randomness r is the pair
+//! (C, D) = (v G + r H, r P), where G and H are the default [PedersenGenerators]. The first part
+//! is a Pedersen commitment to v with blinding factor r, so it can be range proven using
+//! [BulletproofsRangeProof](crate::bulletproofs::BulletproofsRangeProof).
+//!
+//! # Example
+//! ```rust
+//! # use fastcrypto::twisted_elgamal::*;
+//! use rand::thread_rng;
+//! let private_key = PrivateKey::generate(&mut thread_rng());
+//! let public_key = PublicKey::from(&private_key);
+//! let (ciphertext, _) = Ciphertext::encrypt(&public_key, 1000, &mut thread_rng());
+//! let sum = ciphertext.clone() + ciphertext;
+//! let table = DiscreteLogTable::new(16).unwrap();
+//! assert_eq!(private_key.decrypt(&sum, &table).unwrap(), 2000);
+//! ```
+
+use crate::bulletproofs::{PedersenCommitment, PedersenGenerators};
+use crate::error::{FastCryptoError, FastCryptoResult};
+use crate::groups::ristretto255::{RistrettoPoint, RistrettoScalar};
+use crate::groups::{GroupElement, Scalar};
+use crate::traits::AllowedRng;
+use merlin::Transcript;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ops::{Add, Sub};
+use zeroize::ZeroizeOnDrop;
+
+/// The largest bit length supported by [DiscreteLogTable].
+pub const MAX_DISCRETE_LOG_BITS: u8 = 48;
+
+/// A private key s. It is zeroized when dropped and does not implement `Debug` to avoid leaking it.
+#[derive(Clone, PartialEq, Eq, ZeroizeOnDrop)]
+pub struct PrivateKey(RistrettoScalar);
+
+/// A public key P = s^{-1} H where H is the blinding generator of the default
+/// [PedersenGenerators].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicKey(RistrettoPoint);
+
+/// A ciphertext (C, D) = (v G + r H, r P) of a value v with randomness r under the public key P.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Ciphertext {
+    commitment: RistrettoPoint,
+    decryption_handle: RistrettoPoint,
+}
+
+impl PrivateKey {
+    /// Generate a new random private key.
+    pub fn generate<R: AllowedRng>(rng: &mut R) -> Self {
+        loop {
+            let s = RistrettoScalar::rand(rng);
+            if s != RistrettoScalar::zero() {
+                return Self(s);
+            }
+        }
+    }
+
+    /// Decrypt a ciphertext of a value in the range of the given table. Returns an error if the
+    /// value is not in the range.
+    pub fn decrypt(
+        &self,
+        ciphertext: &Ciphertext,
+        table: &DiscreteLogTable,
+    ) -> FastCryptoResult<u64> {
+        table.discrete_log(&self.decrypt_to_point(ciphertext))
+    }
+
+    /// Returns v G for a ciphertext of v.
+    fn decrypt_to_point(&self, ciphertext: &Ciphertext) -> RistrettoPoint {
+        ciphertext.commitment - ciphertext.decryption_handle * self.0
+    }
+}
+
+impl From<&PrivateKey> for PublicKey {
+    fn from(private_key: &PrivateKey) -> Self {
+        let h = PedersenGenerators::default().blinding_generator();
+        Self(h * private_key.0.inverse().expect("Private keys are non-zero"))
+    }
+}
+
+impl Ciphertext {
+    /// Encrypt a value under the given public key. Returns the ciphertext along with the
+    /// randomness r, which is also the blinding factor of the commitment of the ciphertext.
+    pub fn encrypt<R: AllowedRng>(
+        public_key: &PublicKey,
+        value: u64,
+        rng: &mut R,
+    ) -> (Self, RistrettoScalar) {
+        let r = RistrettoScalar::rand(rng);
+        (Self::encrypt_with_randomness(public_key, value, &r), r)
+    }
+
+    /// Encrypt a value under the given public key using the given randomness.
+    pub fn encrypt_with_randomness(
+        public_key: &PublicKey,
+        value: u64,
+        randomness: &RistrettoScalar,
+    ) -> Self {
+        let generators = PedersenGenerators::default();
+        Self {
+            commitment: generators.value_generator() * RistrettoScalar::from(value as u128)
+                + generators.blinding_generator() * randomness,
+            decryption_handle: public_key.0 * randomness,
+        }
+    }
+
+    /// The commitment v G + r H of this ciphertext, which is a Pedersen commitment with the
+    /// default [PedersenGenerators].
+    pub fn commitment(&self) -> PedersenCommitment {
+        self.commitment.into()
+    }
+
+    /// The decryption handle r P of this ciphertext.
+    pub fn decryption_handle(&self) -> RistrettoPoint {
+        self.decryption_handle
+    }
+}
+
+impl Add for Ciphertext {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            commitment: self.commitment + other.commitment,
+            decryption_handle: self.decryption_handle + other.decryption_handle,
+        }
+    }
+}
+
+impl Sub for Ciphertext {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            commitment: self.commitment - other.commitment,
+            decryption_handle: self.decryption_handle - other.decryption_handle,
+        }
+    }
+}
+
+/// A precomputed table for computing discrete logarithms v of v G for v in the range [0, 2^bits)
+/// using baby-step giant-step. The table holds 2^ceil(bits/2) points and a discrete logarithm is
+/// found using at most 2^floor(bits/2) group operations.
+#[derive(Debug, Clone)]
+pub struct DiscreteLogTable {
+    bits: u8,
+    baby_steps: HashMap<[u8; 32], u64>,
+    giant_step: RistrettoPoint,
+}
+
+impl DiscreteLogTable {
+    /// Create a new table for values in the range [0, 2^bits). The bit length must be between 1
+    /// and [MAX_DISCRETE_LOG_BITS].
+    pub fn new(bits: u8) -> FastCryptoResult<Self> {
+        if bits == 0 || bits > MAX_DISCRETE_LOG_BITS {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let g = PedersenGenerators::default().value_generator();
+        let m = 1u64 << bits.div_ceil(2);
+        let mut baby_steps = HashMap::with_capacity(m as usize);
+        let mut point = RistrettoPoint::zero();
+        for j in 0..m {
+            baby_steps.insert(point.compress(), j);
+            point += g;
+        }
+        Ok(Self {
+            bits,
+            baby_steps,
+            giant_step: point,
+        })
+    }
+
+    /// The bit length of the values supported by this table.
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+
+    /// Find v in [0, 2^bits) such that point = v G, or return an error if there is none.
+    pub fn discrete_log(&self, point: &RistrettoPoint) -> FastCryptoResult<u64> {
+        let m = self.baby_steps.len() as u64;
+        let mut point = *point;
+        for i in 0..(1u64 << (self.bits / 2)) {
+            if let Some(j) = self.baby_steps.get(&point.compress()) {
+                return Ok(i * m + j);
+            }
+            point -= self.giant_step;
+        }
+        Err(FastCryptoError::GeneralError(
+            "Value is out of range".to_string(),
+        ))
+    }
+}
+
+/// Proof that a ciphertext (C, D) under the public key P decrypts to v, which is a proof of
+/// knowledge of s such that H = s P and C - v G = s D.
+/// - Prover selects a random k and sends A_1 = k P and A_2 = k D.
+/// - Prover computes challenge c and sends z = k + c s.
+/// - Verifier checks that z P = A_1 + c H and z D = A_2 + c (C - v G).
+///
+/// The proof is (A_1, A_2, z) where c is implicitly computed using a Merlin transcript.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecryptionProof {
+    a_1: RistrettoPoint,
+    a_2: RistrettoPoint,
+    z: RistrettoScalar,
+}
+
+impl DecryptionProof {
+    /// Decrypt a ciphertext of a value in the range of the given table and prove that the
+    /// decryption is correct.
+    pub fn decrypt_and_prove<R: AllowedRng>(
+        private_key: &PrivateKey,
+        ciphertext: &Ciphertext,
+        table: &DiscreteLogTable,
+        domain: &'static [u8],
+        rng: &mut R,
+    ) -> FastCryptoResult<(u64, Self)> {
+        let value = private_key.decrypt(ciphertext, table)?;
+        Ok((
+            value,
+            Self::prove(private_key, ciphertext, value, domain, rng)?,
+        ))
+    }
+
+    /// Prove that the ciphertext decrypts to the given value. Returns an error if it does not.
+    pub fn prove<R: AllowedRng>(
+        private_key: &PrivateKey,
+        ciphertext: &Ciphertext,
+        value: u64,
+        domain: &'static [u8],
+        rng: &mut R,
+    ) -> FastCryptoResult<Self> {
+        let g = PedersenGenerators::default().value_generator();
+        if private_key.decrypt_to_point(ciphertext) != g * RistrettoScalar::from(value as u128) {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let public_key = PublicKey::from(private_key);
+
+        let k = RistrettoScalar::rand(rng);
+        let a_1 = public_key.0 * k;
+        let a_2 = ciphertext.decryption_handle * k;
+        let challenge =
+            Self::fiat_shamir_challenge(&public_key, ciphertext, value, &a_1, &a_2, domain);
+        Ok(Self {
+            a_1,
+            a_2,
+            z: k + challenge * private_key.0,
+        })
+    }
+
+    /// Verify that the ciphertext decrypts to the given value.
+    pub fn verify(
+        &self,
+        public_key: &PublicKey,
+        ciphertext: &Ciphertext,
+        value: u64,
+        domain: &'static [u8],
+    ) -> FastCryptoResult<()> {
+        let generators = PedersenGenerators::default();
+        let challenge = Self::fiat_shamir_challenge(
+            public_key, ciphertext, value, &self.a_1, &self.a_2, domain,
+        );
+        let decrypted = ciphertext.commitment
+            - generators.value_generator() * RistrettoScalar::from(value as u128);
+        if public_key.0 * self.z != self.a_1 + generators.blinding_generator() * challenge
+            || ciphertext.decryption_handle * self.z != self.a_2 + decrypted * challenge
+        {
+            return Err(FastCryptoError::InvalidProof);
+        }
+        Ok(())
+    }
+
+    /// Returns the challenge for Fiat-Shamir.
+    fn fiat_shamir_challenge(
+        public_key: &PublicKey,
+        ciphertext: &Ciphertext,
+        value: u64,
+        a_1: &RistrettoPoint,
+        a_2: &RistrettoPoint,
+        domain: &'static [u8],
+    ) -> RistrettoScalar {
+        let mut transcript = Transcript::new(domain);
+        transcript.append_message(b"dom-sep", b"twisted-elgamal-decryption-proof");
+        append_point(&mut transcript, b"P", &public_key.0);
+        append_ciphertext(&mut transcript, ciphertext);
+        transcript.append_u64(b"v", value);
+        append_point(&mut transcript, b"A_1", a_1);
+        append_point(&mut transcript, b"A_2", a_2);
+        challenge_scalar(&mut transcript)
+    }
+}
+
+/// Proof that a ciphertext (C, D) under the public key P and a Pedersen commitment C' with the
+/// default [PedersenGenerators] are for the same value, which is a proof of knowledge of s, v and
+/// r' such that H = s P, C = v G + s D and C' = v G + r' H.
+/// - Prover selects random k_s, k_v and k_r and sends A_1 = k_s P, A_2 = k_v G + k_s D and
+///   A_3 = k_v G + k_r H.
+/// - Prover computes challenge c and sends z_s = k_s + c s, z_v = k_v + c v and z_r = k_r + c r'.
+/// - Verifier checks that z_s P = A_1 + c H, z_v G + z_s D = A_2 + c C and
+///   z_v G + z_r H = A_3 + c C'.
+///
+/// The proof is (A_1, A_2, A_3, z_s, z_v, z_r) where c is implicitly computed using a Merlin
+/// transcript. This allows the owner of a ciphertext, who only knows the private key, to create a
+/// commitment with a known blinding factor for the same value, e.g. to prove that it is in a range.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CiphertextCommitmentEqualityProof {
+    a_1: RistrettoPoint,
+    a_2: RistrettoPoint,
+    a_3: RistrettoPoint,
+    z_s: RistrettoScalar,
+    z_v: RistrettoScalar,
+    z_r: RistrettoScalar,
+}
+
+impl CiphertextCommitmentEqualityProof {
+    /// Prove that the ciphertext and the commitment with the given blinding factor are for the
+    /// given value. Returns an error if they are not.
+    pub fn prove<R: AllowedRng>(
+        private_key: &PrivateKey,
+        ciphertext: &Ciphertext,
+        commitment: &PedersenCommitment,
+        value: u64,
+        blinding: &RistrettoScalar,
+        domain: &'static [u8],
+        rng: &mut R,
+    ) -> FastCryptoResult<Self> {
+        let generators = PedersenGenerators::default();
+        let (g, h) = (
+            generators.value_generator(),
+            generators.blinding_generator(),
+        );
+        let v = RistrettoScalar::from(value as u128);
+        if private_key.decrypt_to_point(ciphertext) != g * v
+            || RistrettoPoint::from(commitment) != g * v + h * blinding
+        {
+            return Err(FastCryptoError::InvalidInput);
+        }
+        let public_key = PublicKey::from(private_key);
+
+        let k_s = RistrettoScalar::rand(rng);
+        let k_v = RistrettoScalar::rand(rng);
+        let k_r = RistrettoScalar::rand(rng);
+        let a_1 = public_key.0 * k_s;
+        let a_2 = g * k_v + ciphertext.decryption_handle * k_s;
+        let a_3 = g * k_v + h * k_r;
+        let challenge = Self::fiat_shamir_challenge(
+            &public_key,
+            ciphertext,
+            commitment,
+            [&a_1, &a_2, &a_3],
+            domain,
+        );
+        Ok(Self {
+            a_1,
+            a_2,
+            a_3,
+            z_s: k_s + challenge * private_key.0,
+            z_v: k_v + challenge * v,
+            z_r: k_r + challenge * blinding,
+        })
+    }
+
+    /// Verify that the ciphertext and the commitment are for the same value.
+    pub fn verify(
+        &self,
+        public_key: &PublicKey,
+        ciphertext: &Ciphertext,
+        commitment: &PedersenCommitment,
+        domain: &'static [u8],
+    ) -> FastCryptoResult<()> {
+        let generators = PedersenGenerators::default();
+        let (g, h) = (
+            generators.value_generator(),
+            generators.blinding_generator(),
+        );
+        let challenge = Self::fiat_shamir_challenge(
+            public_key,
+            ciphertext,
+            commitment,
+            [&self.a_1, &self.a_2, &self.a_3],
+            domain,
+        );
+        if public_key.0 * self.z_s != self.a_1 + h * challenge
+            || g * self.z_v + ciphertext.decryption_handle * self.z_s
+                != self.a_2 + ciphertext.commitment * challenge
+            || g * self.z_v + h * self.z_r
+                != self.a_3 + RistrettoPoint::from(commitment) * challenge
+        {
+            return Err(FastCryptoError::InvalidProof);
+        }
+        Ok(())
+    }
+
+    /// Returns the challenge for Fiat-Shamir.
+    fn fiat_shamir_challenge(
+        public_key: &PublicKey,
+        ciphertext: &Ciphertext,
+        commitment: &PedersenCommitment,
+        [a_1, a_2, a_3]: [&RistrettoPoint; 3],
+        domain: &'static [u8],
+    ) -> RistrettoScalar {
+        let mut transcript = Transcript::new(domain);
+        transcript.append_message(b"dom-sep", b"twisted-elgamal-equality-proof");
+        append_point(&mut transcript, b"P", &public_key.0);
+        append_ciphertext(&mut transcript, ciphertext);
+        append_point(&mut transcript, b"C'", &RistrettoPoint::from(commitment));
+        append_point(&mut transcript, b"A_1", a_1);
+        append_point(&mut transcript, b"A_2", a_2);
+        append_point(&mut transcript, b"A_3", a_3);
+        challenge_scalar(&mut transcript)
+    }
+}
+
+fn append_point(transcript: &mut Transcript, label: &'static [u8], point: &RistrettoPoint) {
+    transcript.append_message(label, &point.compress());
+}
+
+fn append_ciphertext(transcript: &mut Transcript, ciphertext: &Ciphertext) {
+    append_point(transcript, b"C", &ciphertext.commitment);
+    append_point(transcript, b"D", &ciphertext.decryption_handle);
+}
+
+fn challenge_scalar(transcript: &mut Transcript) -> RistrettoScalar {
+    let mut bytes = [0u8; 64];
+    transcript.challenge_bytes(b"c", &mut bytes);
+    RistrettoScalar::from_bytes_mod_order_wide(&bytes)
+}
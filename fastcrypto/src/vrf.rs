@@ -271,3 +271,170 @@ pub mod ecvrf {
         }
     }
 }
+
+/// An implementation of a VRF based on the unique BLS signature scheme over BLS12-381.
+///
+/// The proof for an input is a BLS signature on the input and the output is the SHA-512 hash of that
+/// signature. Since BLS signatures are deterministic and unique for a valid public key, so is the
+/// output. The input is hashed to the curve using a domain separation tag that differs from the one
+/// used by [crate::bls12381], so a proof can never be used as a signature and vice versa.
+///
+/// Proofs are plain BLS signatures, so many proofs can be verified at the cost of roughly a single
+/// pairing each (see [bls_vrf::min_sig::BLSVRFProof::batch_verify]), and proofs can be computed by
+/// a threshold of parties holding shares of the private key.
+pub mod bls_vrf {
+    /// Defines the VRF types for one of the BLS12-381 modes (min_sig or min_pk).
+    macro_rules! define_bls_vrf {
+        ($bls:ident, $dst:expr) => {
+            use crate::bls12381::$bls::{
+                verify_batch_different_msg_with_dst, BLS12381KeyPair, BLS12381PrivateKey,
+                BLS12381PublicKey, BLS12381Signature,
+            };
+            use crate::error::{FastCryptoError, FastCryptoResult};
+            use crate::hash::{HashFunction, Sha512};
+            use crate::traits::{AllowedRng, KeyPair};
+            use crate::vrf::{VRFKeyPair, VRFPrivateKey, VRFProof, VRFPublicKey};
+            use serde::{Deserialize, Serialize};
+
+            /// Default hash function used to compute the output from a proof.
+            type H = Sha512;
+
+            #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+            pub struct BLSVRFPublicKey(BLS12381PublicKey);
+
+            impl VRFPublicKey for BLSVRFPublicKey {
+                type PrivateKey = BLSVRFPrivateKey;
+            }
+
+            /// Public keys are validated when verifying proofs, so any BLS public key can be used.
+            impl From<BLS12381PublicKey> for BLSVRFPublicKey {
+                fn from(pk: BLS12381PublicKey) -> Self {
+                    BLSVRFPublicKey(pk)
+                }
+            }
+
+            #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+            pub struct BLSVRFPrivateKey(BLS12381PrivateKey);
+
+            impl VRFPrivateKey for BLSVRFPrivateKey {
+                type PublicKey = BLSVRFPublicKey;
+            }
+
+            // BLS12381PrivateKey implements ZeroizeOnDrop.
+            impl zeroize::ZeroizeOnDrop for BLSVRFPrivateKey {}
+
+            impl From<BLS12381PrivateKey> for BLSVRFPrivateKey {
+                fn from(sk: BLS12381PrivateKey) -> Self {
+                    BLSVRFPrivateKey(sk)
+                }
+            }
+
+            #[derive(Serialize, Deserialize, PartialEq, Eq, Debug)]
+            pub struct BLSVRFKeyPair {
+                pub pk: BLSVRFPublicKey,
+                pub sk: BLSVRFPrivateKey,
+            }
+
+            impl VRFKeyPair<64> for BLSVRFKeyPair {
+                type Proof = BLSVRFProof;
+                type PrivateKey = BLSVRFPrivateKey;
+                type PublicKey = BLSVRFPublicKey;
+
+                fn generate<R: AllowedRng>(rng: &mut R) -> Self {
+                    let kp = BLS12381KeyPair::generate(rng);
+                    BLSVRFKeyPair {
+                        pk: BLSVRFPublicKey(kp.public().clone()),
+                        sk: BLSVRFPrivateKey(kp.private()),
+                    }
+                }
+
+                fn prove(&self, input: &[u8]) -> BLSVRFProof {
+                    BLSVRFProof(self.sk.0.sign_with_dst(input, $dst))
+                }
+            }
+
+            impl From<BLSVRFPrivateKey> for BLSVRFKeyPair {
+                fn from(sk: BLSVRFPrivateKey) -> Self {
+                    BLSVRFKeyPair {
+                        pk: BLSVRFPublicKey(BLS12381PublicKey::from(&sk.0)),
+                        sk,
+                    }
+                }
+            }
+
+            #[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+            pub struct BLSVRFProof(BLS12381Signature);
+
+            /// Allows using proofs that were computed elsewhere, e.g., by aggregating partial
+            /// signatures from a threshold of key holders. Proofs are validated in [VRFProof::verify].
+            impl From<BLS12381Signature> for BLSVRFProof {
+                fn from(sig: BLS12381Signature) -> Self {
+                    BLSVRFProof(sig)
+                }
+            }
+
+            impl VRFProof<64> for BLSVRFProof {
+                type PublicKey = BLSVRFPublicKey;
+
+                fn verify(
+                    &self,
+                    input: &[u8],
+                    public_key: &Self::PublicKey,
+                ) -> Result<(), FastCryptoError> {
+                    // Uniqueness of the output only holds for valid public keys (not the identity
+                    // and in the right subgroup), so the key must be validated.
+                    public_key.0.validate()?;
+                    public_key.0.verify_with_dst(input, &self.0, $dst)
+                }
+
+                fn to_hash(&self) -> [u8; 64] {
+                    let mut hash = H::default();
+                    hash.update($dst);
+                    hash.update(self.0.as_ref());
+                    hash.finalize().digest
+                }
+            }
+
+            impl BLSVRFProof {
+                /// Verify a batch of proofs, where `proofs[i]` is a proof for `inputs[i]` under the
+                /// public key `public_keys[i]`. This is faster than verifying the proofs one by one,
+                /// but if the verification fails, it is not possible to tell which proof was invalid.
+                pub fn batch_verify(
+                    proofs: &[BLSVRFProof],
+                    inputs: &[&[u8]],
+                    public_keys: &[BLSVRFPublicKey],
+                ) -> FastCryptoResult<()> {
+                    if proofs.is_empty()
+                        || proofs.len() != inputs.len()
+                        || proofs.len() != public_keys.len()
+                    {
+                        return Err(FastCryptoError::InvalidInput);
+                    }
+                    public_keys.iter().try_for_each(|pk| pk.0.validate())?;
+                    verify_batch_different_msg_with_dst(
+                        inputs,
+                        &public_keys.iter().map(|pk| &pk.0).collect::<Vec<_>>(),
+                        &proofs.iter().map(|proof| &proof.0).collect::<Vec<_>>(),
+                        $dst,
+                    )
+                }
+            }
+        };
+    }
+
+    /// BLS VRF where proofs are in G1 (48 bytes) and public keys are in G2 (96 bytes).
+    pub mod min_sig {
+        /// Hash-to-curve domain separation tag for VRF inputs. Differs from
+        /// [crate::bls12381::min_sig::DST_G1] which is used for signatures.
+        pub const VRF_DST_G1: &[u8] = b"BLS_VRF_BLS12381G1_XMD:SHA-256_SSWU_RO_NUL_";
+        define_bls_vrf!(min_sig, VRF_DST_G1);
+    }
+
+    /// BLS VRF where proofs are in G2 (96 bytes) and public keys are in G1 (48 bytes).
+    pub mod min_pk {
+        /// Hash-to-curve domain separation tag for VRF inputs. Differs from
+        /// [crate::bls12381::min_pk::DST_G2] which is used for signatures.
+        pub const VRF_DST_G2: &[u8] = b"BLS_VRF_BLS12381G2_XMD:SHA-256_SSWU_RO_NUL_";
+        define_bls_vrf!(min_pk, VRF_DST_G2);
+    }
+}